@@ -0,0 +1,25 @@
+//! Generates the types of the test fixtures, or of the namespace at
+//! `UAVCAN_DSDL_NAMESPACE` when set, such as the public regulated data types.
+
+use std::{env, path::PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=UAVCAN_DSDL_NAMESPACE");
+    println!("cargo:rustc-check-cfg=cfg(fixtures)");
+
+    let namespace = match env::var_os("UAVCAN_DSDL_NAMESPACE") {
+        Some(namespace) => PathBuf::from(namespace),
+        None => {
+            // Some fixtures, such as `uavcan.primitive.Value.1.0`, are not
+            // part of the public regulated data types.
+            println!("cargo:rustc-cfg=fixtures");
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/uavcan")
+        }
+    };
+    println!("cargo:rerun-if-changed={}", namespace.display());
+
+    uavcan_dsdl_codegen::Builder::new()
+        .namespace(namespace)
+        .build("dsdl.rs")
+        .unwrap();
+}
@@ -0,0 +1,137 @@
+//! Compiles the code generated for the test fixtures, or for the namespace at
+//! `UAVCAN_DSDL_NAMESPACE`, and checks the bytes produced by the generated
+//! types against hand-encoded transfers.
+
+include!(concat!(env!("OUT_DIR"), "/dsdl.rs"));
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+
+    use uavcan::dsdl::{deserialize_from_slice, serialize_to_slice, Deserialize, Serialize};
+
+    use super::uavcan::node::{
+        health_1_0::Health, heartbeat_1_0::Heartbeat, mode_1_0::Mode, version_1_0::Version,
+    };
+
+    /// Checks that `value` is serialized to `bytes` and deserialized back
+    /// from them.
+    fn round_trip<T: Serialize + Deserialize + PartialEq + Debug>(value: T, bytes: &[u8]) {
+        let mut buffer = [0; 512];
+        let len = serialize_to_slice(&value, &mut buffer).unwrap();
+
+        assert_eq!(&buffer[..len], bytes);
+        assert_eq!(deserialize_from_slice::<T>(bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn a_heartbeat_is_serialized_with_each_nested_type_on_its_own_byte() {
+        round_trip(
+            Heartbeat {
+                uptime: 0x01020304,
+                health: Health {
+                    value: Health::CAUTION,
+                },
+                mode: Mode {
+                    value: Mode::INITIALIZATION,
+                },
+                vendor_specific_status_code: 0x55,
+            },
+            &[0x04, 0x03, 0x02, 0x01, 0x02, 0x01, 0x55],
+        );
+    }
+
+    #[test]
+    fn a_version_is_serialized_as_its_major_then_minor_number() {
+        round_trip(Version { major: 1, minor: 2 }, &[0x01, 0x02]);
+    }
+
+    #[cfg(fixtures)]
+    mod fixtures {
+        use uavcan::heapless::Vec;
+
+        use super::super::uavcan::file::{
+            path_2_0::Path,
+            read_1_1::{ReadRequest, ReadResponse},
+        };
+        use super::super::uavcan::node::{
+            get_info_1_0::{GetInfoRequest, GetInfoResponse},
+            version_1_0::Version,
+        };
+        use super::super::uavcan::primitive::{empty_1_0::Empty, value_1_0::Value};
+        use super::round_trip;
+
+        fn path(text: &str) -> Path {
+            Path {
+                path: Vec::from_slice(text.as_bytes()).unwrap(),
+            }
+        }
+
+        #[test]
+        fn a_variable_length_array_is_prefixed_by_its_length() {
+            round_trip(path("a/b"), &[0x03, b'a', b'/', b'b']);
+        }
+
+        #[test]
+        fn a_sealed_type_is_nested_without_a_delimiter_header() {
+            round_trip(
+                ReadRequest {
+                    offset: 0x0102030405,
+                    path: path("ab"),
+                },
+                &[0x05, 0x04, 0x03, 0x02, 0x01, 0x02, b'a', b'b'],
+            );
+        }
+
+        #[test]
+        fn the_length_of_an_array_of_up_to_256_elements_takes_two_bytes() {
+            round_trip(
+                ReadResponse {
+                    error: 0x0102,
+                    data: Vec::from_slice(&[0xAA]).unwrap(),
+                },
+                &[0x02, 0x01, 0x01, 0x00, 0xAA],
+            );
+        }
+
+        #[test]
+        fn an_empty_request_is_serialized_to_no_bytes() {
+            round_trip(GetInfoRequest {}, &[]);
+        }
+
+        #[test]
+        fn a_get_info_response_is_serialized_field_by_field_with_its_padding() {
+            let mut bytes = std::vec![0x01, 0x00, 0x02, 0x01, 0x03, 0x02];
+            bytes.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+            bytes.extend(0..16);
+            bytes.extend_from_slice(&[0x00, 0x02, b'n', b'd', 0x01]);
+            bytes.extend_from_slice(&[0xEF, 0xCD, 0xAB, 0x89, 0x67, 0x45, 0x23, 0x01]);
+            bytes.extend_from_slice(&[0x01, 0xCA]);
+
+            round_trip(
+                GetInfoResponse {
+                    protocol_version: Version { major: 1, minor: 0 },
+                    hardware_version: Version { major: 2, minor: 1 },
+                    software_version: Version { major: 3, minor: 2 },
+                    software_vcs_revision_id: 0x0807060504030201,
+                    unique_id: core::array::from_fn(|index| index as u8),
+                    name: Vec::from_slice(b"nd").unwrap(),
+                    software_image_crc: Vec::from_slice(&[0x0123456789ABCDEF]).unwrap(),
+                    certificate_of_authenticity: Vec::from_slice(&[0xCA]).unwrap(),
+                },
+                &bytes,
+            );
+        }
+
+        #[test]
+        fn each_variant_of_a_union_is_prefixed_by_its_tag() {
+            round_trip(Value::Empty(Empty {}), &[0x00]);
+            round_trip(
+                Value::Integers(Vec::from_slice(&[1, -1]).unwrap()),
+                &[0x01, 0x02, 0x01, 0xF0, 0xFF],
+            );
+            round_trip(Value::Real(1.0), &[0x02, 0x00, 0x3C]);
+            round_trip(Value::Flags([true, false, true]), &[0x03, 0x05]);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Parsing of single DSDL definition files into an unresolved representation.
+//!
+//! Expressions are kept in their textual form, as they may reference
+//! constants of other definitions and can thus only be evaluated once the
+//! whole namespace is known; see [crate::namespace].
+
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveType {
+    Boolean,
+    Unsigned(u8),
+    Signed(u8),
+    Float(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastMode {
+    Saturated,
+    Truncated,
+}
+
+/// A reference to a versioned composite type, as written in a definition.
+///
+/// `name` may either be fully qualified or relative to the namespace of the
+/// definition containing the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeReference {
+    pub name: String,
+    pub major: u8,
+    pub minor: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementType {
+    Primitive(PrimitiveType, CastMode),
+    Composite(TypeReference),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Scalar(ElementType),
+    /// A fixed-length array, whose length is the contained expression.
+    FixedArray(ElementType, String),
+    /// A variable-length array, whose capacity is the contained expression.
+    VariableArray(ElementType, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Field {
+        name: String,
+        ty: FieldType,
+    },
+    Padding(u8),
+    Constant {
+        name: String,
+        ty: PrimitiveType,
+        expression: String,
+    },
+}
+
+/// The content of a message, or of either section of a service.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Body {
+    pub statements: Vec<Statement>,
+    pub is_union: bool,
+    pub is_sealed: bool,
+    pub is_deprecated: bool,
+    /// The expression of the `@extent` directive, in bits.
+    pub extent: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Message(Body),
+    Service { request: Body, response: Body },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub namespace: Vec<String>,
+    pub short_name: String,
+    pub major: u8,
+    pub minor: u8,
+    pub port_id: Option<u16>,
+    pub kind: DefinitionKind,
+    pub path: PathBuf,
+}
+
+impl Definition {
+    pub fn full_name(&self) -> String {
+        let mut components = self.namespace.clone();
+        components.push(self.short_name.clone());
+
+        components.join(".")
+    }
+}
+
+/// The information encoded in the name of a definition file, in the form
+/// `[port_id.]ShortName.major.minor.dsdl`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileName {
+    pub port_id: Option<u16>,
+    pub short_name: String,
+    pub major: u8,
+    pub minor: u8,
+}
+
+pub fn parse_file_name(path: &Path) -> Result<FileName, Error> {
+    let invalid = || Error::parse(path, 0, "the file name is not a valid DSDL file name");
+
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".dsdl"))
+        .ok_or_else(invalid)?;
+
+    let components: Vec<&str> = stem.split('.').collect();
+    let (port_id, components) = match components.as_slice() {
+        [port_id, rest @ ..] if rest.len() == 3 => {
+            (Some(port_id.parse::<u16>().map_err(|_| invalid())?), rest)
+        }
+        rest if rest.len() == 3 => (None, rest),
+        _ => return Err(invalid()),
+    };
+
+    Ok(FileName {
+        port_id,
+        short_name: String::from(components[0]),
+        major: components[1].parse().map_err(|_| invalid())?,
+        minor: components[2].parse().map_err(|_| invalid())?,
+    })
+}
+
+fn parse_primitive(name: &str) -> Option<PrimitiveType> {
+    let bits = |prefix: &str, allowed: &dyn Fn(u8) -> bool| {
+        name.strip_prefix(prefix)
+            .and_then(|bits| bits.parse::<u8>().ok())
+            .filter(|bits| allowed(*bits))
+    };
+
+    match name {
+        "bool" => Some(PrimitiveType::Boolean),
+        "byte" | "utf8" => Some(PrimitiveType::Unsigned(8)),
+        _ => bits("uint", &|bits| (1..=64).contains(&bits))
+            .map(PrimitiveType::Unsigned)
+            .or_else(|| bits("int", &|bits| (2..=64).contains(&bits)).map(PrimitiveType::Signed))
+            .or_else(|| {
+                bits("float", &|bits| matches!(bits, 16 | 32 | 64)).map(PrimitiveType::Float)
+            }),
+    }
+}
+
+pub fn parse_type_reference(name: &str) -> Option<TypeReference> {
+    let components: Vec<&str> = name.split('.').collect();
+    match components.as_slice() {
+        [path @ .., major, minor] if !path.is_empty() => Some(TypeReference {
+            name: path.join("."),
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_element_type(mut words: &[&str]) -> Option<ElementType> {
+    let cast_mode = match words.first() {
+        Some(&"saturated") => {
+            words = &words[1..];
+            CastMode::Saturated
+        }
+        Some(&"truncated") => {
+            words = &words[1..];
+            CastMode::Truncated
+        }
+        _ => CastMode::Saturated,
+    };
+
+    match words {
+        [name] => parse_primitive(name)
+            .map(|primitive| ElementType::Primitive(primitive, cast_mode))
+            .or_else(|| parse_type_reference(name).map(ElementType::Composite)),
+        _ => None,
+    }
+}
+
+/// Splits a type with an optional array suffix, such as `uint8[<=50]`, into
+/// the element type and the array suffix.
+fn parse_field_type(declaration: &str) -> Option<FieldType> {
+    match declaration.find('[') {
+        Some(open) => {
+            let close = declaration.rfind(']')?;
+            let element =
+                parse_element_type(&declaration[..open].split_whitespace().collect::<Vec<_>>())?;
+            let dimension = declaration[open + 1..close].trim();
+
+            Some(if let Some(capacity) = dimension.strip_prefix("<=") {
+                FieldType::VariableArray(element, String::from(capacity.trim()))
+            } else if let Some(bound) = dimension.strip_prefix('<') {
+                FieldType::VariableArray(element, format!("({}) - 1", bound.trim()))
+            } else {
+                FieldType::FixedArray(element, String::from(dimension))
+            })
+        }
+        None => parse_element_type(&declaration.split_whitespace().collect::<Vec<_>>())
+            .map(FieldType::Scalar),
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, character) in line.char_indices() {
+        match (quote, character) {
+            (None, '#') => return &line[..index],
+            (None, '\'') | (None, '"') => quote = Some(character),
+            (Some(open), close) if open == close => quote = None,
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Finds the `=` of a constant declaration, ignoring the `<=` of array types.
+fn find_assignment(line: &str) -> Option<usize> {
+    line.char_indices()
+        .find(|(index, character)| *character == '=' && !line[..*index].ends_with('<'))
+        .map(|(index, _)| index)
+}
+
+fn parse_statement(line: &str) -> Option<Statement> {
+    if let Some(assignment) = find_assignment(line) {
+        let (declaration, expression) = (&line[..assignment], &line[assignment + 1..]);
+        let mut words = declaration.split_whitespace().collect::<Vec<_>>();
+        let name = words.pop()?;
+        let ty = parse_primitive(words.last()?)?;
+
+        return Some(Statement::Constant {
+            name: String::from(name),
+            ty,
+            expression: String::from(expression.trim()),
+        });
+    }
+
+    if let Some(bits) = line.strip_prefix("void") {
+        return bits.trim().parse().ok().map(Statement::Padding);
+    }
+
+    // The name of the field is the last word, while the type may contain
+    // whitespace, as in `saturated uint8[<= 8]`.
+    let split = line.trim_end().rfind(char::is_whitespace)?;
+    let (declaration, name) = (&line[..split], line[split..].trim());
+
+    Some(Statement::Field {
+        name: String::from(name),
+        ty: parse_field_type(declaration)?,
+    })
+}
+
+fn parse_directive(
+    body: &mut Body,
+    directive: &str,
+    path: &Path,
+    line: usize,
+) -> Result<(), Error> {
+    let (name, argument) = directive
+        .split_once(char::is_whitespace)
+        .map(|(name, argument)| (name, argument.trim()))
+        .unwrap_or((directive, ""));
+
+    match name {
+        "union" => body.is_union = true,
+        "sealed" => body.is_sealed = true,
+        "deprecated" => body.is_deprecated = true,
+        "extent" => body.extent = Some(String::from(argument)),
+        // Assertions and prints are only meaningful to validation tools.
+        "assert" | "print" => {}
+        _ => {
+            return Err(Error::parse(
+                path,
+                line,
+                &format!("unknown directive `@{}`", name),
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the content of a definition file.
+///
+/// `namespace` is the namespace the definition belongs to, which is derived
+/// from the directory in which the file is stored.
+pub fn parse_definition(
+    path: &Path,
+    namespace: Vec<String>,
+    source: &str,
+) -> Result<Definition, Error> {
+    let file_name = parse_file_name(path)?;
+
+    let mut bodies = vec![Body::default()];
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+        let body = bodies.last_mut().unwrap();
+
+        if line.is_empty() {
+            continue;
+        } else if line == "---" {
+            if bodies.len() > 1 {
+                return Err(Error::parse(
+                    path,
+                    line_number,
+                    "duplicate service separator",
+                ));
+            }
+            bodies.push(Body::default());
+        } else if let Some(directive) = line.strip_prefix('@') {
+            parse_directive(body, directive, path, line_number)?;
+        } else {
+            let statement = parse_statement(line)
+                .ok_or_else(|| Error::parse(path, line_number, "invalid statement"))?;
+            body.statements.push(statement);
+        }
+    }
+
+    let kind = if bodies.len() == 2 {
+        let response = bodies.pop().unwrap();
+        let request = bodies.pop().unwrap();
+        DefinitionKind::Service { request, response }
+    } else {
+        DefinitionKind::Message(bodies.pop().unwrap())
+    };
+
+    Ok(Definition {
+        namespace,
+        short_name: file_name.short_name,
+        major: file_name.major,
+        minor: file_name.minor,
+        port_id: file_name.port_id,
+        kind,
+        path: PathBuf::from(path),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, source: &str) -> Definition {
+        parse_definition(
+            Path::new(name),
+            vec![String::from("uavcan"), String::from("node")],
+            source,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn the_port_id_and_version_are_taken_from_the_file_name() {
+        assert_eq!(
+            parse_file_name(Path::new("7509.Heartbeat.1.0.dsdl")).unwrap(),
+            FileName {
+                port_id: Some(7509),
+                short_name: String::from("Heartbeat"),
+                major: 1,
+                minor: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn a_file_name_without_a_version_is_an_error() {
+        assert!(parse_file_name(Path::new("Heartbeat.dsdl")).is_err());
+    }
+
+    #[test]
+    fn fields_padding_constants_and_directives_are_parsed() {
+        let definition = parse(
+            "Health.1.0.dsdl",
+            "# comment\n\
+             uint2 value # trailing comment\n\
+             void6\n\
+             uint2 WARNING = 2\n\
+             @sealed\n",
+        );
+
+        assert_eq!(
+            definition.kind,
+            DefinitionKind::Message(Body {
+                statements: vec![
+                    Statement::Field {
+                        name: String::from("value"),
+                        ty: FieldType::Scalar(ElementType::Primitive(
+                            PrimitiveType::Unsigned(2),
+                            CastMode::Saturated
+                        )),
+                    },
+                    Statement::Padding(6),
+                    Statement::Constant {
+                        name: String::from("WARNING"),
+                        ty: PrimitiveType::Unsigned(2),
+                        expression: String::from("2"),
+                    },
+                ],
+                is_sealed: true,
+                ..Body::default()
+            })
+        );
+    }
+
+    #[test]
+    fn arrays_of_composite_types_are_parsed() {
+        let definition = parse(
+            "List.1.0.dsdl",
+            "truncated uavcan.node.ID.1.0[<=8] nodes\nint8[<3] values\n@extent 64 * 8",
+        );
+
+        let body = match definition.kind {
+            DefinitionKind::Message(body) => body,
+            _ => panic!("expected a message"),
+        };
+
+        assert_eq!(
+            body.statements,
+            vec![
+                Statement::Field {
+                    name: String::from("nodes"),
+                    ty: FieldType::VariableArray(
+                        ElementType::Composite(TypeReference {
+                            name: String::from("uavcan.node.ID"),
+                            major: 1,
+                            minor: 0,
+                        }),
+                        String::from("8")
+                    ),
+                },
+                Statement::Field {
+                    name: String::from("values"),
+                    ty: FieldType::VariableArray(
+                        ElementType::Primitive(PrimitiveType::Signed(8), CastMode::Saturated),
+                        String::from("(3) - 1")
+                    ),
+                },
+            ]
+        );
+        assert_eq!(body.extent, Some(String::from("64 * 8")));
+    }
+
+    #[test]
+    fn a_service_separator_splits_request_and_response() {
+        let definition = parse(
+            "430.GetInfo.1.0.dsdl",
+            "@extent 0\n---\nuint8[<=50] name\n@extent 448 * 8\n",
+        );
+
+        assert!(matches!(
+            definition.kind,
+            DefinitionKind::Service { ref request, ref response }
+                if request.statements.is_empty() && response.statements.len() == 1
+        ));
+    }
+
+    #[test]
+    fn a_comment_character_inside_a_string_literal_is_not_a_comment() {
+        assert_eq!(
+            strip_comment("uint8 HASH = '#' # comment"),
+            "uint8 HASH = '#' "
+        );
+    }
+}
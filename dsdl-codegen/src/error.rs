@@ -0,0 +1,55 @@
+use std::{fmt, io, path::Path, path::PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    Resolution {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl Error {
+    pub(crate) fn parse(path: &Path, line: usize, message: &str) -> Self {
+        Error::Parse {
+            path: PathBuf::from(path),
+            line,
+            message: String::from(message),
+        }
+    }
+
+    pub(crate) fn resolution(path: &Path, message: String) -> Self {
+        Error::Resolution {
+            path: PathBuf::from(path),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            Error::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            Error::Resolution { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
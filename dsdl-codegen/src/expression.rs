@@ -0,0 +1,455 @@
+//! A small evaluator for the subset of DSDL expressions that can appear in
+//! constant values, array capacities and the `@extent` directive.
+
+use std::fmt;
+
+/// An exact rational number, as used by DSDL for every numeric expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Result<Self, String> {
+        if denominator == 0 {
+            return Err(String::from("division by zero"));
+        }
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Ok(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    pub fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    fn checked(numerator: Option<i128>, denominator: Option<i128>) -> Result<Self, String> {
+        match (numerator, denominator) {
+            (Some(numerator), Some(denominator)) => Self::new(numerator, denominator),
+            _ => Err(String::from("arithmetic overflow")),
+        }
+    }
+
+    fn add(self, other: Self) -> Result<Self, String> {
+        let left = self.numerator.checked_mul(other.denominator);
+        let right = other.numerator.checked_mul(self.denominator);
+
+        Self::checked(
+            left.zip(right)
+                .and_then(|(left, right)| left.checked_add(right)),
+            self.denominator.checked_mul(other.denominator),
+        )
+    }
+
+    fn negate(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+
+    fn multiply(self, other: Self) -> Result<Self, String> {
+        Self::checked(
+            self.numerator.checked_mul(other.numerator),
+            self.denominator.checked_mul(other.denominator),
+        )
+    }
+
+    fn divide(self, other: Self) -> Result<Self, String> {
+        Self::checked(
+            self.numerator.checked_mul(other.denominator),
+            self.denominator.checked_mul(other.numerator),
+        )
+    }
+
+    fn remainder(self, other: Self) -> Result<Self, String> {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(_), Some(0)) => Err(String::from("division by zero")),
+            (Some(left), Some(right)) => Ok(Self::integer(left % right)),
+            _ => Err(String::from("the operands of `%` must be integers")),
+        }
+    }
+
+    fn power(self, exponent: Self) -> Result<Self, String> {
+        let exponent = exponent
+            .as_integer()
+            .ok_or_else(|| String::from("exponents must be integers"))?;
+
+        let (base, exponent) = if exponent < 0 {
+            (Self::integer(1).divide(self)?, -exponent)
+        } else {
+            (self, exponent)
+        };
+
+        (0..exponent).try_fold(Self::integer(1), |accumulator, _| {
+            accumulator.multiply(base)
+        })
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Rational(Rational),
+    Boolean(bool),
+    String(String),
+}
+
+impl Value {
+    pub fn as_rational(&self) -> Result<Rational, String> {
+        match self {
+            Value::Rational(rational) => Ok(*rational),
+            Value::String(string) if string.chars().count() == 1 => {
+                Ok(Rational::integer(string.chars().next().unwrap() as i128))
+            }
+            other => Err(format!("expected a number, found {:?}", other)),
+        }
+    }
+
+    pub fn as_integer(&self) -> Result<i128, String> {
+        let rational = self.as_rational()?;
+
+        rational
+            .as_integer()
+            .ok_or_else(|| format!("expected an integer, found {}", rational))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Rational),
+    String(String),
+    Identifier(String),
+    Operator(&'static str),
+    OpenParenthesis,
+    CloseParenthesis,
+}
+
+const OPERATORS: [&str; 6] = ["**", "+", "-", "*", "/", "%"];
+
+fn parse_number(literal: &str) -> Result<Rational, String> {
+    let literal = literal.replace('_', "");
+    let invalid = || format!("invalid number literal `{}`", literal);
+
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(digits) = literal.strip_prefix(prefix) {
+            return i128::from_str_radix(digits, radix)
+                .map(Rational::integer)
+                .map_err(|_| invalid());
+        }
+    }
+
+    let (mantissa, exponent) = match literal.find(['e', 'E']) {
+        Some(index) => (
+            &literal[..index],
+            literal[index + 1..]
+                .parse::<i128>()
+                .map_err(|_| invalid())?,
+        ),
+        None => (literal.as_str(), 0),
+    };
+
+    let (integer_part, fractional_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer_part, fractional_part);
+    let numerator = digits.parse::<i128>().map_err(|_| invalid())?;
+
+    Rational::integer(numerator).multiply(
+        Rational::integer(10).power(Rational::integer(exponent - fractional_part.len() as i128))?,
+    )
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut characters = source.char_indices().peekable();
+
+    while let Some(&(start, character)) = characters.peek() {
+        if character.is_whitespace() {
+            characters.next();
+        } else if character == '(' {
+            characters.next();
+            tokens.push(Token::OpenParenthesis);
+        } else if character == ')' {
+            characters.next();
+            tokens.push(Token::CloseParenthesis);
+        } else if character == '\'' || character == '"' {
+            characters.next();
+            let mut string = String::new();
+            loop {
+                match characters.next() {
+                    Some((_, '\\')) => match characters.next() {
+                        Some((_, 'n')) => string.push('\n'),
+                        Some((_, 'r')) => string.push('\r'),
+                        Some((_, 't')) => string.push('\t'),
+                        Some((_, escaped)) => string.push(escaped),
+                        None => return Err(String::from("unterminated string literal")),
+                    },
+                    Some((_, closing)) if closing == character => break,
+                    Some((_, other)) => string.push(other),
+                    None => return Err(String::from("unterminated string literal")),
+                }
+            }
+            tokens.push(Token::String(string));
+        } else if character.is_ascii_digit() {
+            let mut end = start;
+            while let Some(&(index, next)) = characters.peek() {
+                let previous = source[..index].chars().last();
+                let is_exponent_sign = (next == '-' || next == '+')
+                    && matches!(previous, Some('e') | Some('E'))
+                    && !source[start..index].starts_with("0x");
+                if next.is_ascii_alphanumeric() || next == '.' || next == '_' || is_exponent_sign {
+                    end = index + next.len_utf8();
+                    characters.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Number(parse_number(&source[start..end])?));
+        } else if character.is_alphabetic() || character == '_' {
+            let mut end = start;
+            while let Some(&(index, next)) = characters.peek() {
+                if next.is_alphanumeric() || next == '_' || next == '.' {
+                    end = index + next.len_utf8();
+                    characters.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Identifier(String::from(&source[start..end])));
+        } else {
+            let operator = OPERATORS
+                .iter()
+                .find(|operator| source[start..].starts_with(*operator))
+                .ok_or_else(|| format!("unexpected character `{}`", character))?;
+            for _ in 0..operator.len() {
+                characters.next();
+            }
+            tokens.push(Token::Operator(operator));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a, R: Fn(&str) -> Result<Value, String>> {
+    tokens: Vec<Token>,
+    position: usize,
+    resolve: &'a R,
+}
+
+impl<R: Fn(&str) -> Result<Value, String>> Parser<'_, R> {
+    fn peek_operator(&self, candidates: &[&str]) -> Option<&'static str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Operator(operator)) if candidates.contains(operator) => Some(*operator),
+            _ => None,
+        }
+    }
+
+    fn additive(&mut self) -> Result<Value, String> {
+        let mut left = self.multiplicative()?;
+        while let Some(operator) = self.peek_operator(&["+", "-"]) {
+            self.position += 1;
+            let right = self.multiplicative()?;
+            left = match (left, right, operator) {
+                (Value::String(left), Value::String(right), "+") => {
+                    Value::String(format!("{}{}", left, right))
+                }
+                (left, right, "+") => {
+                    Value::Rational(left.as_rational()?.add(right.as_rational()?)?)
+                }
+                (left, right, _) => {
+                    Value::Rational(left.as_rational()?.add(right.as_rational()?.negate())?)
+                }
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Value, String> {
+        let mut left = self.power()?;
+        while let Some(operator) = self.peek_operator(&["*", "/", "%"]) {
+            self.position += 1;
+            let (left_operand, right_operand) = (left.as_rational()?, self.power()?.as_rational()?);
+            left = Value::Rational(match operator {
+                "*" => left_operand.multiply(right_operand)?,
+                "/" => left_operand.divide(right_operand)?,
+                _ => left_operand.remainder(right_operand)?,
+            });
+        }
+
+        Ok(left)
+    }
+
+    fn power(&mut self) -> Result<Value, String> {
+        let base = self.unary()?;
+        if self.peek_operator(&["**"]).is_some() {
+            self.position += 1;
+            let exponent = self.power()?;
+
+            return Ok(Value::Rational(
+                base.as_rational()?.power(exponent.as_rational()?)?,
+            ));
+        }
+
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<Value, String> {
+        match self.peek_operator(&["+", "-"]) {
+            Some("-") => {
+                self.position += 1;
+                Ok(Value::Rational(self.unary()?.as_rational()?.negate()))
+            }
+            Some(_) => {
+                self.position += 1;
+                self.unary()
+            }
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Value, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| String::from("unexpected end of expression"))?;
+        self.position += 1;
+
+        match token {
+            Token::Number(number) => Ok(Value::Rational(number)),
+            Token::String(string) => Ok(Value::String(string)),
+            Token::Identifier(identifier) if identifier == "true" => Ok(Value::Boolean(true)),
+            Token::Identifier(identifier) if identifier == "false" => Ok(Value::Boolean(false)),
+            Token::Identifier(identifier) => (self.resolve)(&identifier),
+            Token::OpenParenthesis => {
+                let value = self.additive()?;
+                match self.tokens.get(self.position) {
+                    Some(Token::CloseParenthesis) => {
+                        self.position += 1;
+                        Ok(value)
+                    }
+                    _ => Err(String::from("expected `)`")),
+                }
+            }
+            other => Err(format!("unexpected token {:?}", other)),
+        }
+    }
+}
+
+/// Evaluates `source`, using `resolve` to obtain the value of the
+/// identifiers, such as constants, that appear in it.
+pub fn evaluate<R: Fn(&str) -> Result<Value, String>>(
+    source: &str,
+    resolve: &R,
+) -> Result<Value, String> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+        resolve,
+    };
+
+    let value = parser.additive()?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(value),
+        Some(token) => Err(format!("unexpected token {:?}", token)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate_closed(source: &str) -> Result<Value, String> {
+        evaluate(source, &|name: &str| {
+            Err(format!("unknown identifier `{}`", name))
+        })
+    }
+
+    #[test]
+    fn arithmetic_follows_the_usual_precedence_rules() {
+        assert_eq!(
+            evaluate_closed("2 + 3 * 4 ** 2 - (1 + 1)"),
+            Ok(Value::Rational(Rational::integer(48)))
+        );
+    }
+
+    #[test]
+    fn division_is_exact() {
+        assert_eq!(
+            evaluate_closed("1 / 3 * 3"),
+            Ok(Value::Rational(Rational::integer(1)))
+        );
+    }
+
+    #[test]
+    fn real_literals_are_parsed_exactly() {
+        assert_eq!(
+            evaluate_closed("1.5e-3"),
+            Ok(Value::Rational(Rational::new(3, 2000).unwrap()))
+        );
+    }
+
+    #[test]
+    fn integer_literals_can_be_written_in_other_bases() {
+        assert_eq!(
+            evaluate_closed("0xFF + 0b1 + 0o7"),
+            Ok(Value::Rational(Rational::integer(263)))
+        );
+    }
+
+    #[test]
+    fn a_single_character_string_can_be_used_as_a_number() {
+        assert_eq!(evaluate_closed("'/'").unwrap().as_integer(), Ok(47));
+    }
+
+    #[test]
+    fn identifiers_are_resolved_trough_the_provided_function() {
+        let resolve = |name: &str| match name {
+            "uavcan.file.Path.2.0.MAX_LENGTH" => Ok(Value::Rational(Rational::integer(255))),
+            _ => Err(String::from("unknown")),
+        };
+
+        assert_eq!(
+            evaluate("uavcan.file.Path.2.0.MAX_LENGTH + 1", &resolve),
+            Ok(Value::Rational(Rational::integer(256)))
+        );
+    }
+
+    #[test]
+    fn a_division_by_zero_is_an_error() {
+        assert!(evaluate_closed("1 / (2 - 2)").is_err());
+    }
+}
@@ -0,0 +1,671 @@
+//! Emission of Rust source code from resolved definitions.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    definition::{CastMode, Definition, DefinitionKind, PrimitiveType},
+    error::Error,
+    expression::Value,
+    namespace::{
+        Namespace, ResolvedBody, ResolvedElement, ResolvedFieldType, ResolvedMember, TypeKey,
+    },
+};
+
+const KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        String::from(name)
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let characters: Vec<char> = name.chars().collect();
+    let mut snake_case = String::new();
+
+    for (index, character) in characters.iter().enumerate() {
+        if character.is_uppercase() {
+            let previous = index.checked_sub(1).map(|previous| characters[previous]);
+            let next = characters.get(index + 1);
+
+            let starts_word = match previous {
+                Some(previous) if previous.is_lowercase() || previous.is_ascii_digit() => true,
+                Some(previous) if previous.is_uppercase() => {
+                    next.is_some_and(|next| next.is_lowercase())
+                }
+                _ => false,
+            };
+            if starts_word {
+                snake_case.push('_');
+            }
+
+            snake_case.extend(character.to_lowercase());
+        } else {
+            snake_case.push(*character);
+        }
+    }
+
+    snake_case
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut characters = word.chars();
+            characters
+                .next()
+                .map(|first| first.to_uppercase().chain(characters).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn module_name(definition: &Definition) -> String {
+    format!(
+        "{}_{}_{}",
+        to_snake_case(&definition.short_name),
+        definition.major,
+        definition.minor
+    )
+}
+
+fn primitive_rust_type(primitive: PrimitiveType) -> &'static str {
+    match primitive {
+        PrimitiveType::Boolean => "bool",
+        PrimitiveType::Unsigned(bits) if bits <= 8 => "u8",
+        PrimitiveType::Unsigned(bits) if bits <= 16 => "u16",
+        PrimitiveType::Unsigned(bits) if bits <= 32 => "u32",
+        PrimitiveType::Unsigned(_) => "u64",
+        PrimitiveType::Signed(bits) if bits <= 8 => "i8",
+        PrimitiveType::Signed(bits) if bits <= 16 => "i16",
+        PrimitiveType::Signed(bits) if bits <= 32 => "i32",
+        PrimitiveType::Signed(_) => "i64",
+        PrimitiveType::Float(bits) if bits <= 32 => "f32",
+        PrimitiveType::Float(_) => "f64",
+    }
+}
+
+fn is_standard_width(bits: u8) -> bool {
+    matches!(bits, 8 | 16 | 32 | 64)
+}
+
+fn write_element(element: &ResolvedElement, place: &str) -> String {
+    match element {
+        ResolvedElement::Primitive(primitive, cast_mode) => {
+            let saturated = *cast_mode == CastMode::Saturated;
+            match *primitive {
+                PrimitiveType::Boolean => format!("writer.write_bool({})?;", place),
+                PrimitiveType::Unsigned(bits) if saturated && !is_standard_width(bits) => {
+                    format!(
+                        "writer.write_unsigned_saturated({} as u64, {})?;",
+                        place, bits
+                    )
+                }
+                PrimitiveType::Unsigned(bits) => {
+                    format!("writer.write_bits({} as u64, {})?;", place, bits)
+                }
+                PrimitiveType::Signed(bits) if saturated && !is_standard_width(bits) => {
+                    format!(
+                        "writer.write_signed_saturated({} as i64, {})?;",
+                        place, bits
+                    )
+                }
+                PrimitiveType::Signed(bits) => {
+                    format!("writer.write_signed({} as i64, {})?;", place, bits)
+                }
+                PrimitiveType::Float(16) if saturated => {
+                    format!("writer.write_f16_saturated({})?;", place)
+                }
+                PrimitiveType::Float(16) => format!("writer.write_f16({})?;", place),
+                PrimitiveType::Float(32) => format!("writer.write_f32({})?;", place),
+                PrimitiveType::Float(_) => format!("writer.write_f64({})?;", place),
+            }
+        }
+        ResolvedElement::Composite(_) => format!("writer.write_composite(&{})?;", place),
+    }
+}
+
+fn read_element(element: &ResolvedElement, path: &dyn Fn(&TypeKey) -> String) -> String {
+    match element {
+        ResolvedElement::Primitive(primitive, _) => match *primitive {
+            PrimitiveType::Boolean => String::from("reader.read_bool()"),
+            PrimitiveType::Unsigned(bits) => format!(
+                "reader.read_bits({}) as {}",
+                bits,
+                primitive_rust_type(*primitive)
+            ),
+            PrimitiveType::Signed(bits) => format!(
+                "reader.read_signed({}) as {}",
+                bits,
+                primitive_rust_type(*primitive)
+            ),
+            PrimitiveType::Float(16) => String::from("reader.read_f16()"),
+            PrimitiveType::Float(32) => String::from("reader.read_f32()"),
+            PrimitiveType::Float(_) => String::from("reader.read_f64()"),
+        },
+        ResolvedElement::Composite(key) => format!("reader.read_composite::<{}>()?", path(key)),
+    }
+}
+
+fn render_constant(ty: PrimitiveType, value: &Value) -> Result<String, String> {
+    match (ty, value) {
+        (PrimitiveType::Boolean, Value::Boolean(value)) => Ok(value.to_string()),
+        (PrimitiveType::Boolean, other) => Err(format!("expected a boolean, found {:?}", other)),
+        (PrimitiveType::Float(bits), value) => {
+            let value = value.as_rational()?.as_f64();
+            Ok(if bits == 64 {
+                format!("{:?}", value)
+            } else {
+                format!("{:?}", value as f32)
+            })
+        }
+        (_, value) => value.as_integer().map(|value| value.to_string()),
+    }
+}
+
+/// Emits the Rust source code of the definitions of a namespace.
+pub struct Generator<'a> {
+    namespace: &'a Namespace,
+    crate_path: &'a str,
+}
+
+struct ModuleTree<'a> {
+    children: BTreeMap<String, ModuleTree<'a>>,
+    definitions: Vec<&'a Definition>,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(namespace: &'a Namespace, crate_path: &'a str) -> Self {
+        Self {
+            namespace,
+            crate_path,
+        }
+    }
+
+    pub fn generate(&self) -> Result<String, Error> {
+        let mut tree = ModuleTree {
+            children: BTreeMap::new(),
+            definitions: Vec::new(),
+        };
+        for definition in self.namespace.definitions() {
+            let node = definition
+                .namespace
+                .iter()
+                .fold(&mut tree, |node, component| {
+                    node.children
+                        .entry(component.clone())
+                        .or_insert_with(|| ModuleTree {
+                            children: BTreeMap::new(),
+                            definitions: Vec::new(),
+                        })
+                });
+            node.definitions.push(definition);
+        }
+
+        let mut output = String::from(
+            "// This file was generated by uavcan-dsdl-codegen. Do not edit it by hand.\n",
+        );
+        for (name, child) in &tree.children {
+            output.push_str(
+                "\n#[allow(unused_variables, unused_mut, deprecated, non_camel_case_types, clippy::all)]\n",
+            );
+            self.generate_module(&mut output, name, child, 0)?;
+        }
+
+        Ok(output)
+    }
+
+    fn generate_module(
+        &self,
+        output: &mut String,
+        name: &str,
+        tree: &ModuleTree<'_>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let indentation = "    ".repeat(depth);
+        let _ = writeln!(output, "{}pub mod {} {{", indentation, identifier(name));
+
+        for definition in &tree.definitions {
+            let mut module = String::new();
+            self.generate_definition(&mut module, definition)?;
+
+            let _ = writeln!(
+                output,
+                "{}    pub mod {} {{",
+                indentation,
+                module_name(definition)
+            );
+            for line in module.lines() {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    let _ = writeln!(output, "{}        {}", indentation, line);
+                }
+            }
+            let _ = writeln!(output, "{}    }}", indentation);
+        }
+
+        for (name, child) in &tree.children {
+            self.generate_module(output, name, child, depth + 1)?;
+        }
+
+        let _ = writeln!(output, "{}}}", indentation);
+        Ok(())
+    }
+
+    /// Returns the path, relative to the module generated for `from`, of the
+    /// type identified by `key`.
+    fn type_path(&self, from: &Definition, key: &TypeKey) -> String {
+        let definition = self
+            .namespace
+            .get(key)
+            .expect("the namespace should contain every resolved type");
+
+        let mut components = vec![String::from("super"); from.namespace.len() + 1];
+        components.extend(
+            definition
+                .namespace
+                .iter()
+                .map(|component| identifier(component)),
+        );
+        components.push(module_name(definition));
+        components.push(identifier(&definition.short_name));
+
+        components.join("::")
+    }
+
+    fn generate_definition(
+        &self,
+        output: &mut String,
+        definition: &Definition,
+    ) -> Result<(), Error> {
+        let crate_path = self.crate_path;
+        let _ = writeln!(
+            output,
+            "use {}::dsdl::{{BitReader, BitWriter, DataType, DeserializationError, Deserialize, SerializationError, Serialize}};",
+            crate_path
+        );
+
+        match &definition.kind {
+            DefinitionKind::Message(body) => {
+                if let Some(port_id) = definition.port_id {
+                    if port_id >= 8192 {
+                        return Err(Error::resolution(
+                            &definition.path,
+                            format!("{} is not a valid subject id", port_id),
+                        ));
+                    }
+
+                    let _ = writeln!(
+                        output,
+                        "\npub const SUBJECT_ID: {0}::session_id::SubjectId = {0}::session_id::SubjectId::from_const({1});",
+                        crate_path, port_id
+                    );
+                }
+
+                let body = self.namespace.resolve_body(definition, body)?;
+                self.generate_body(output, definition, &definition.short_name, &body)
+            }
+            DefinitionKind::Service { request, response } => {
+                if let Some(port_id) = definition.port_id {
+                    if port_id >= 512 {
+                        return Err(Error::resolution(
+                            &definition.path,
+                            format!("{} is not a valid service id", port_id),
+                        ));
+                    }
+
+                    let _ = writeln!(
+                        output,
+                        "\npub const SERVICE_ID: {0}::session_id::ServiceId = {0}::session_id::ServiceId::from_const({1});",
+                        crate_path, port_id
+                    );
+                }
+
+                let request_name = format!("{}Request", definition.short_name);
+                let request = self.namespace.resolve_body(definition, request)?;
+                self.generate_body(output, definition, &request_name, &request)?;
+
+                let response_name = format!("{}Response", definition.short_name);
+                let response = self.namespace.resolve_body(definition, response)?;
+                self.generate_body(output, definition, &response_name, &response)?;
+
+                if definition.port_id.is_some() {
+                    let _ = write!(
+                        output,
+                        "\n/// The `{0}` service, served on `SERVICE_ID`.\npub struct {1};\n\nimpl {2}::presentation::Service for {1} {{\n    type Request = {3};\n    type Response = {4};\n\n    const SERVICE_ID: {2}::session_id::ServiceId = SERVICE_ID;\n}}\n",
+                        definition.full_name(),
+                        identifier(&definition.short_name),
+                        crate_path,
+                        identifier(&request_name),
+                        identifier(&response_name)
+                    );
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn field_type(&self, definition: &Definition, ty: &ResolvedFieldType) -> String {
+        let element_type = |element: &ResolvedElement| match element {
+            ResolvedElement::Primitive(primitive, _) => {
+                String::from(primitive_rust_type(*primitive))
+            }
+            ResolvedElement::Composite(key) => self.type_path(definition, key),
+        };
+
+        match ty {
+            ResolvedFieldType::Scalar(element) => element_type(element),
+            ResolvedFieldType::FixedArray(element, length) => {
+                format!("[{}; {}]", element_type(element), length)
+            }
+            ResolvedFieldType::VariableArray(element, capacity) => format!(
+                "{0}::heapless::Vec<{1}, {2}>",
+                self.crate_path,
+                element_type(element),
+                capacity
+            ),
+        }
+    }
+
+    fn write_field(&self, ty: &ResolvedFieldType, place: &str) -> String {
+        match ty {
+            ResolvedFieldType::Scalar(element) => write_element(element, place),
+            ResolvedFieldType::FixedArray(element, _) => format!(
+                "for element in {}.iter() {{\n    {}\n}}",
+                place,
+                write_element(element, "*element")
+            ),
+            ResolvedFieldType::VariableArray(element, capacity) => format!(
+                "writer.write_array_length({0}.len(), {1})?;\nfor element in {0}.iter() {{\n    {2}\n}}",
+                place,
+                capacity,
+                write_element(element, "*element")
+            ),
+        }
+    }
+
+    fn read_field(&self, definition: &Definition, ty: &ResolvedFieldType) -> String {
+        let path = |key: &TypeKey| self.type_path(definition, key);
+
+        match ty {
+            ResolvedFieldType::Scalar(element) => read_element(element, &path),
+            ResolvedFieldType::FixedArray(element, _) => format!(
+                "{{\n    let mut array = {};\n    for element in array.iter_mut() {{\n        *element = {};\n    }}\n    array\n}}",
+                default_value(ty),
+                read_element(element, &path)
+            ),
+            ResolvedFieldType::VariableArray(element, capacity) => format!(
+                "{{\n    let length = reader.read_array_length({})?;\n    let mut vec = {}::heapless::Vec::new();\n    for _ in 0..length {{\n        // The length was checked against the capacity of the vector.\n        let _ = vec.push({});\n    }}\n    vec\n}}",
+                capacity,
+                self.crate_path,
+                read_element(element, &path)
+            ),
+        }
+    }
+
+    fn generate_body(
+        &self,
+        output: &mut String,
+        definition: &Definition,
+        name: &str,
+        body: &ResolvedBody,
+    ) -> Result<(), Error> {
+        let name = identifier(name);
+        let fields: Vec<(String, &ResolvedFieldType)> = body
+            .members
+            .iter()
+            .filter_map(|member| match member {
+                ResolvedMember::Field { name, ty } => Some((identifier(name), ty)),
+                ResolvedMember::Padding(_) => None,
+            })
+            .collect();
+
+        let _ = writeln!(
+            output,
+            "\n/// Generated from `{}.{}.{}`.",
+            definition.full_name(),
+            definition.major,
+            definition.minor
+        );
+        if body.is_deprecated {
+            output.push_str("#[deprecated]\n");
+        }
+        output.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+
+        let (type_definition, default, serialize, deserialize) = if body.is_union {
+            let variants: Vec<(String, &ResolvedFieldType)> = fields
+                .iter()
+                .map(|(field, ty)| (to_camel_case(field.trim_start_matches("r#")), *ty))
+                .collect();
+            if variants.is_empty() {
+                return Err(Error::resolution(
+                    &definition.path,
+                    String::from("a union must have at least one variant"),
+                ));
+            }
+
+            let mut type_definition = format!("pub enum {} {{\n", name);
+            let mut serialize = String::from("match self {\n");
+            let mut deserialize = format!("match reader.read_union_tag({})? {{\n", variants.len());
+            for (tag, (variant, ty)) in variants.iter().enumerate() {
+                // Arrays are iterated through the reference bound by the
+                // match, while scalars are copied out of it.
+                let place = match ty {
+                    ResolvedFieldType::Scalar(_) => "*value",
+                    _ => "value",
+                };
+                let _ = writeln!(
+                    type_definition,
+                    "    {}({}),",
+                    variant,
+                    self.field_type(definition, ty)
+                );
+                let _ = writeln!(
+                    serialize,
+                    "    Self::{}(value) => {{\n        writer.write_union_tag({}, {})?;\n{}\n    }}",
+                    variant,
+                    tag,
+                    variants.len(),
+                    indent(&self.write_field(ty, place), 2)
+                );
+                let _ = writeln!(
+                    deserialize,
+                    "    {} => Ok(Self::{}({})),",
+                    tag,
+                    variant,
+                    indent(&self.read_field(definition, ty), 1).trim_start()
+                );
+            }
+            type_definition.push('}');
+            serialize.push_str("}\nOk(())");
+            deserialize
+                .push_str("    tag => Err(DeserializationError::UnionTagOutOfBounds(tag)),\n}");
+
+            let (first_variant, first_type) = &variants[0];
+            let default = format!("Self::{}({})", first_variant, default_value(first_type));
+
+            (type_definition, default, serialize, deserialize)
+        } else {
+            let mut type_definition = format!("pub struct {} {{\n", name);
+            let mut default = String::from("Self {\n");
+            for (field, ty) in &fields {
+                let _ = writeln!(
+                    type_definition,
+                    "    pub {}: {},",
+                    field,
+                    self.field_type(definition, ty)
+                );
+                let _ = writeln!(default, "    {}: {},", field, default_value(ty));
+            }
+            type_definition.push('}');
+            default.push('}');
+
+            // Fields are read directly into the struct expression, whose
+            // fields are evaluated in order, so that their names cannot shadow
+            // the reader. Padding is read before the field that follows it.
+            let mut serialize = String::new();
+            let mut deserialize = String::from("let value = Self {\n");
+            let mut pending_padding = String::new();
+            for member in &body.members {
+                match member {
+                    ResolvedMember::Field { name: field, ty } => {
+                        let field = identifier(field);
+                        let _ = writeln!(
+                            serialize,
+                            "{}",
+                            self.write_field(ty, &format!("self.{}", field))
+                        );
+
+                        let read = self.read_field(definition, ty);
+                        let read = if pending_padding.is_empty() {
+                            read
+                        } else {
+                            format!(
+                                "{{\n{}\n{}\n}}",
+                                indent(&pending_padding, 1),
+                                indent(&read, 1)
+                            )
+                        };
+                        pending_padding.clear();
+                        let _ = writeln!(
+                            deserialize,
+                            "    {}: {},",
+                            field,
+                            indent(&read, 1).trim_start()
+                        );
+                    }
+                    ResolvedMember::Padding(bits) => {
+                        let _ = writeln!(serialize, "writer.write_bits(0, {})?;", bits);
+                        let _ = writeln!(pending_padding, "reader.read_bits({});", bits);
+                    }
+                }
+            }
+            serialize.push_str("Ok(())");
+            deserialize.push_str("};\n");
+            deserialize.push_str(&pending_padding);
+            deserialize.push_str("Ok(value)");
+
+            (type_definition, default, serialize, deserialize)
+        };
+
+        output.push_str(&type_definition);
+        output.push('\n');
+
+        if !body.constants.is_empty() {
+            let _ = writeln!(output, "\nimpl {} {{", name);
+            for constant in &body.constants {
+                let value = render_constant(constant.ty, &constant.value)
+                    .map_err(|message| Error::resolution(&definition.path, message))?;
+                let _ = writeln!(
+                    output,
+                    "    pub const {}: {} = {};",
+                    identifier(&constant.name),
+                    primitive_rust_type(constant.ty),
+                    value
+                );
+            }
+            output.push_str("}\n");
+        }
+
+        let _ = writeln!(
+            output,
+            "\nimpl Default for {} {{\n    fn default() -> Self {{\n{}\n    }}\n}}",
+            name,
+            indent(&default, 2)
+        );
+        let _ = writeln!(
+            output,
+            "\nimpl DataType for {} {{\n    const EXTENT_BYTES: usize = {};\n    const SEALED: bool = {};\n}}",
+            name, body.extent_bytes, body.is_sealed
+        );
+        let _ = writeln!(
+            output,
+            "\nimpl Serialize for {} {{\n    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {{\n{}\n    }}\n}}",
+            name,
+            indent(&serialize, 2)
+        );
+        let _ = writeln!(
+            output,
+            "\nimpl Deserialize for {} {{\n    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {{\n{}\n    }}\n}}",
+            name,
+            indent(&deserialize, 2)
+        );
+
+        Ok(())
+    }
+}
+
+fn default_value(ty: &ResolvedFieldType) -> &'static str {
+    match ty {
+        ResolvedFieldType::FixedArray(..) => "core::array::from_fn(|_| Default::default())",
+        _ => "Default::default()",
+    }
+}
+
+fn indent(source: &str, levels: usize) -> String {
+    let indentation = "    ".repeat(levels);
+    source
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indentation, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_names_are_converted_to_snake_case_module_names() {
+        assert_eq!(to_snake_case("Heartbeat"), "heartbeat");
+        assert_eq!(to_snake_case("GetInfo"), "get_info");
+        assert_eq!(to_snake_case("HTTPGet"), "http_get");
+        assert_eq!(to_snake_case("Natural16"), "natural16");
+    }
+
+    #[test]
+    fn union_field_names_are_converted_to_camel_case_variant_names() {
+        assert_eq!(to_camel_case("natural16"), "Natural16");
+        assert_eq!(to_camel_case("unstructured_value"), "UnstructuredValue");
+    }
+
+    #[test]
+    fn rust_keywords_are_escaped() {
+        assert_eq!(identifier("type"), "r#type");
+        assert_eq!(identifier("value"), "value");
+    }
+
+    #[test]
+    fn non_standard_saturated_integers_are_saturated_on_write() {
+        assert_eq!(
+            write_element(
+                &ResolvedElement::Primitive(PrimitiveType::Unsigned(3), CastMode::Saturated),
+                "self.mode"
+            ),
+            "writer.write_unsigned_saturated(self.mode as u64, 3)?;"
+        );
+        assert_eq!(
+            write_element(
+                &ResolvedElement::Primitive(PrimitiveType::Unsigned(3), CastMode::Truncated),
+                "self.mode"
+            ),
+            "writer.write_bits(self.mode as u64, 3)?;"
+        );
+    }
+}
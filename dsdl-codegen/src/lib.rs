@@ -0,0 +1,127 @@
+//! Generates Rust types implementing the `uavcan` serialization traits from
+//! DSDL namespaces.
+//!
+//! The generator is meant to be used from a build script:
+//!
+//! ```no_run
+//! // build.rs
+//! uavcan_dsdl_codegen::Builder::new()
+//!     .namespace("dsdl/public_regulated_data_types/uavcan")
+//!     .build("dsdl.rs")
+//!     .unwrap();
+//! ```
+//!
+//! after which the generated modules can be included anywhere in the crate:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/dsdl.rs"));
+//! ```
+//!
+//! Each definition is generated in a module named after its short name and
+//! version, nested into one module for each component of its namespace, so
+//! that `uavcan.node.Heartbeat.1.0` becomes
+//! `uavcan::node::heartbeat_1_0::Heartbeat`. Services generate a `Request` and
+//! a `Response` type in the same module, such as `GetInfoRequest` and
+//! `GetInfoResponse`. Fixed port IDs are generated as `SUBJECT_ID` or
+//! `SERVICE_ID` constants of that module, and services with a fixed port ID
+//! also get a type implementing `uavcan::presentation::Service`, such as
+//! `GetInfo`.
+//!
+//! Definitions are only read from the local file system, so no network
+//! access is required at build time.
+
+#![deny(
+    noop_method_call,
+    single_use_lifetimes,
+    unreachable_pub,
+    unsafe_code,
+    unused_import_braces,
+    unused_lifetimes,
+    warnings
+)]
+
+pub mod definition;
+pub mod error;
+pub mod expression;
+pub mod generator;
+pub mod namespace;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub use error::Error;
+pub use generator::Generator;
+pub use namespace::Namespace;
+
+/// Configures and runs the generation of the Rust source code for a set of
+/// root namespaces.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    roots: Vec<PathBuf>,
+    crate_path: String,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            crate_path: String::from("::uavcan"),
+        }
+    }
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a root namespace directory, such as the `uavcan` directory of the
+    /// public regulated data types.
+    ///
+    /// Every root namespace that is referenced by the definitions to generate
+    /// must be provided.
+    pub fn namespace<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.roots.push(root.into());
+        self
+    }
+
+    /// Sets the path trough which the generated code refers to the `uavcan`
+    /// crate. Defaults to `::uavcan`.
+    pub fn crate_path(mut self, crate_path: &str) -> Self {
+        self.crate_path = String::from(crate_path);
+        self
+    }
+
+    pub fn generate(&self) -> Result<String, Error> {
+        let namespace = Namespace::load(&self.roots)?;
+        Generator::new(&namespace, &self.crate_path).generate()
+    }
+
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.generate()?).map_err(|error| Error::Io(PathBuf::from(path), error))
+    }
+
+    /// Generates the code into `file_name` in the `OUT_DIR` of the build
+    /// script that calls it, asking cargo to rerun the build script when the
+    /// namespaces change.
+    pub fn build(&self, file_name: &str) -> Result<(), Error> {
+        let out_dir = env::var_os("OUT_DIR").map(PathBuf::from).ok_or_else(|| {
+            Error::Io(
+                PathBuf::from("OUT_DIR"),
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "OUT_DIR is not set, is this called from a build script?",
+                ),
+            )
+        })?;
+
+        for root in &self.roots {
+            println!("cargo:rerun-if-changed={}", root.display());
+        }
+
+        self.write_to(out_dir.join(file_name))
+    }
+}
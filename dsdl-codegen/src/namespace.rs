@@ -0,0 +1,613 @@
+//! Loading of namespace directories and resolution of the references between
+//! the definitions they contain.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    definition::{
+        parse_definition, parse_type_reference, Body, CastMode, Definition, DefinitionKind,
+        ElementType, FieldType, PrimitiveType, Statement, TypeReference,
+    },
+    error::Error,
+    expression::{evaluate, Value},
+};
+
+/// Deeper chains of constants referencing other constants are assumed to be
+/// cyclic.
+const MAX_RESOLUTION_DEPTH: usize = 32;
+
+/// Identifies a definition by its full name, major and minor version.
+pub type TypeKey = (String, u8, u8);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedElement {
+    Primitive(PrimitiveType, CastMode),
+    Composite(TypeKey),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedFieldType {
+    Scalar(ResolvedElement),
+    FixedArray(ResolvedElement, usize),
+    VariableArray(ResolvedElement, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedMember {
+    Field { name: String, ty: ResolvedFieldType },
+    Padding(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedConstant {
+    pub name: String,
+    pub ty: PrimitiveType,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedBody {
+    pub members: Vec<ResolvedMember>,
+    pub constants: Vec<ResolvedConstant>,
+    pub is_union: bool,
+    pub is_sealed: bool,
+    pub is_deprecated: bool,
+    pub extent_bytes: usize,
+}
+
+fn round_up_to_byte(bits: u64) -> u64 {
+    bits.div_ceil(8) * 8
+}
+
+fn primitive_bits(primitive: PrimitiveType) -> u64 {
+    match primitive {
+        PrimitiveType::Boolean => 1,
+        PrimitiveType::Unsigned(bits)
+        | PrimitiveType::Signed(bits)
+        | PrimitiveType::Float(bits) => bits as u64,
+    }
+}
+
+fn standard_bit_length_for(max_value: u64) -> u64 {
+    [8, 16, 32]
+        .iter()
+        .copied()
+        .find(|bits| max_value < (1 << bits))
+        .unwrap_or(64)
+}
+
+#[derive(Debug, Default)]
+pub struct Namespace {
+    definitions: BTreeMap<TypeKey, Definition>,
+}
+
+impl Namespace {
+    /// Loads every definition found under the provided root namespace
+    /// directories.
+    ///
+    /// The name of each root directory is the name of the root namespace it
+    /// contains, so that, for example, the public regulated data types are
+    /// loaded by providing the path to their `uavcan` directory.
+    pub fn load<P: AsRef<Path>>(roots: &[P]) -> Result<Self, Error> {
+        let mut namespace = Self::default();
+        for root in roots {
+            let root = root.as_ref();
+            let name = root
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| {
+                    Error::resolution(root, String::from("invalid root namespace name"))
+                })?;
+
+            namespace.load_directory(root, vec![String::from(name)])?;
+        }
+
+        Ok(namespace)
+    }
+
+    fn load_directory(&mut self, directory: &Path, namespace: Vec<String>) -> Result<(), Error> {
+        let mut entries = fs::read_dir(directory)
+            .map_err(|error| Error::Io(PathBuf::from(directory), error))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| Error::Io(PathBuf::from(directory), error))?;
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                let mut nested = namespace.clone();
+                nested.extend(
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .map(String::from),
+                );
+
+                self.load_directory(&path, nested)?;
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "dsdl")
+            {
+                let source =
+                    fs::read_to_string(&path).map_err(|error| Error::Io(path.clone(), error))?;
+                self.insert(parse_definition(&path, namespace.clone(), &source)?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, definition: Definition) -> Result<(), Error> {
+        let key = (definition.full_name(), definition.major, definition.minor);
+
+        if let Some(existing) = self.definitions.get(&key) {
+            return Err(Error::resolution(
+                &definition.path,
+                format!(
+                    "duplicate definition, also found at {}",
+                    existing.path.display()
+                ),
+            ));
+        }
+
+        self.definitions.insert(key, definition);
+        Ok(())
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.values()
+    }
+
+    pub fn get(&self, key: &TypeKey) -> Option<&Definition> {
+        self.definitions.get(key)
+    }
+
+    /// Finds the definition referenced from `from`, looking first for a fully
+    /// qualified name and then for a name relative to the namespace of `from`.
+    pub fn lookup(
+        &self,
+        reference: &TypeReference,
+        from: &Definition,
+    ) -> Result<&Definition, Error> {
+        let relative = format!("{}.{}", from.namespace.join("."), reference.name);
+
+        [reference.name.clone(), relative]
+            .iter()
+            .find_map(|name| {
+                self.definitions
+                    .get(&(name.clone(), reference.major, reference.minor))
+            })
+            .ok_or_else(|| {
+                Error::resolution(
+                    &from.path,
+                    format!(
+                        "unknown type `{}.{}.{}`",
+                        reference.name, reference.major, reference.minor
+                    ),
+                )
+            })
+    }
+
+    fn resolve_identifier(
+        &self,
+        definition: &Definition,
+        body: &Body,
+        name: &str,
+        depth: usize,
+    ) -> Result<Value, String> {
+        if depth > MAX_RESOLUTION_DEPTH {
+            return Err(format!("cyclic reference while resolving `{}`", name));
+        }
+
+        if let Some(split) = name.rfind('.') {
+            let (type_name, constant) = (&name[..split], &name[split + 1..]);
+            let reference = parse_type_reference(type_name)
+                .ok_or_else(|| format!("`{}` is not a valid reference", name))?;
+            let nested = self
+                .lookup(&reference, definition)
+                .map_err(|error| error.to_string())?;
+
+            return match &nested.kind {
+                DefinitionKind::Message(body) => {
+                    self.resolve_identifier(nested, body, constant, depth + 1)
+                }
+                DefinitionKind::Service { .. } => Err(format!(
+                    "cannot reference constants of service `{}`",
+                    type_name
+                )),
+            };
+        }
+
+        body.statements
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Constant {
+                    name: constant,
+                    expression,
+                    ..
+                } if constant == name => Some(expression),
+                _ => None,
+            })
+            .ok_or_else(|| format!("unknown identifier `{}`", name))
+            .and_then(|expression| self.evaluate(definition, body, expression, depth + 1))
+    }
+
+    fn evaluate(
+        &self,
+        definition: &Definition,
+        body: &Body,
+        expression: &str,
+        depth: usize,
+    ) -> Result<Value, String> {
+        evaluate(expression, &|name: &str| {
+            self.resolve_identifier(definition, body, name, depth)
+        })
+    }
+
+    fn evaluate_size(
+        &self,
+        definition: &Definition,
+        body: &Body,
+        expression: &str,
+    ) -> Result<usize, Error> {
+        self.evaluate(definition, body, expression, 0)
+            .and_then(|value| value.as_integer())
+            .and_then(|size| {
+                usize::try_from(size).map_err(|_| format!("`{}` is not a valid size", expression))
+            })
+            .map_err(|message| Error::resolution(&definition.path, message))
+    }
+
+    fn resolve_element(
+        &self,
+        definition: &Definition,
+        element: &ElementType,
+    ) -> Result<ResolvedElement, Error> {
+        match element {
+            ElementType::Primitive(primitive, cast_mode) => {
+                Ok(ResolvedElement::Primitive(*primitive, *cast_mode))
+            }
+            ElementType::Composite(reference) => {
+                let nested = self.lookup(reference, definition)?;
+                if let DefinitionKind::Service { .. } = nested.kind {
+                    return Err(Error::resolution(
+                        &definition.path,
+                        format!("service `{}` cannot be used as a field", nested.full_name()),
+                    ));
+                }
+
+                Ok(ResolvedElement::Composite((
+                    nested.full_name(),
+                    nested.major,
+                    nested.minor,
+                )))
+            }
+        }
+    }
+
+    /// Resolves the types, array sizes, constants and extent of `body`, which
+    /// is the body of, or a section of, `definition`.
+    pub fn resolve_body(
+        &self,
+        definition: &Definition,
+        body: &Body,
+    ) -> Result<ResolvedBody, Error> {
+        let mut members = Vec::new();
+        let mut constants = Vec::new();
+
+        for statement in &body.statements {
+            match statement {
+                Statement::Field { name, ty } => {
+                    let ty = match ty {
+                        FieldType::Scalar(element) => {
+                            ResolvedFieldType::Scalar(self.resolve_element(definition, element)?)
+                        }
+                        FieldType::FixedArray(element, length) => ResolvedFieldType::FixedArray(
+                            self.resolve_element(definition, element)?,
+                            self.evaluate_size(definition, body, length)?,
+                        ),
+                        FieldType::VariableArray(element, capacity) => {
+                            ResolvedFieldType::VariableArray(
+                                self.resolve_element(definition, element)?,
+                                self.evaluate_size(definition, body, capacity)?,
+                            )
+                        }
+                    };
+
+                    members.push(ResolvedMember::Field {
+                        name: name.clone(),
+                        ty,
+                    });
+                }
+                Statement::Padding(bits) => members.push(ResolvedMember::Padding(*bits)),
+                Statement::Constant {
+                    name,
+                    ty,
+                    expression,
+                } => constants.push(ResolvedConstant {
+                    name: name.clone(),
+                    ty: *ty,
+                    value: self
+                        .evaluate(definition, body, expression, 0)
+                        .map_err(|message| Error::resolution(&definition.path, message))?,
+                }),
+            }
+        }
+
+        if body.is_union
+            && members
+                .iter()
+                .any(|member| matches!(member, ResolvedMember::Padding(_)))
+        {
+            return Err(Error::resolution(
+                &definition.path,
+                String::from("unions cannot contain padding fields"),
+            ));
+        }
+
+        let extent_bytes = match (&body.extent, body.is_sealed) {
+            (Some(_), true) => {
+                return Err(Error::resolution(
+                    &definition.path,
+                    String::from("a sealed type cannot specify an extent"),
+                ))
+            }
+            (Some(extent), false) => {
+                let extent_bits = self.evaluate_size(definition, body, extent)?;
+                if extent_bits % 8 != 0 {
+                    return Err(Error::resolution(
+                        &definition.path,
+                        String::from("the extent must be a multiple of 8 bits"),
+                    ));
+                }
+
+                extent_bits / 8
+            }
+            (None, true) => (self.max_bits(definition, body, 0)? / 8) as usize,
+            (None, false) => {
+                return Err(Error::resolution(
+                    &definition.path,
+                    String::from("either `@sealed` or `@extent` must be specified"),
+                ))
+            }
+        };
+
+        Ok(ResolvedBody {
+            members,
+            constants,
+            is_union: body.is_union,
+            is_sealed: body.is_sealed,
+            is_deprecated: body.is_deprecated,
+            extent_bytes,
+        })
+    }
+
+    /// Computes an upper bound for the length of the serialized
+    /// representation of `body`, in bits, rounded up to a byte boundary.
+    fn max_bits(&self, definition: &Definition, body: &Body, depth: usize) -> Result<u64, Error> {
+        if depth > MAX_RESOLUTION_DEPTH {
+            return Err(Error::resolution(
+                &definition.path,
+                String::from("cyclic composite type nesting"),
+            ));
+        }
+
+        let element_bits = |element: &ElementType| -> Result<u64, Error> {
+            match element {
+                ElementType::Primitive(primitive, _) => Ok(primitive_bits(*primitive)),
+                ElementType::Composite(reference) => {
+                    let nested = self.lookup(reference, definition)?;
+                    match &nested.kind {
+                        DefinitionKind::Message(nested_body) if nested_body.is_sealed => {
+                            self.max_bits(nested, nested_body, depth + 1)
+                        }
+                        DefinitionKind::Message(nested_body) => {
+                            Ok(
+                                self.resolve_body(nested, nested_body)?.extent_bytes as u64 * 8
+                                    + 32,
+                            )
+                        }
+                        DefinitionKind::Service { .. } => Err(Error::resolution(
+                            &definition.path,
+                            format!("service `{}` cannot be used as a field", nested.full_name()),
+                        )),
+                    }
+                }
+            }
+        };
+        let align = |offset: u64, element: &ElementType| match element {
+            ElementType::Composite(_) => round_up_to_byte(offset),
+            ElementType::Primitive(..) => offset,
+        };
+
+        let mut field_sizes = Vec::new();
+        for statement in &body.statements {
+            field_sizes.push(match statement {
+                Statement::Field { ty, .. } => match ty {
+                    FieldType::Scalar(element) => (align(0, element), element_bits(element)?),
+                    FieldType::FixedArray(element, length) => (
+                        align(0, element),
+                        self.evaluate_size(definition, body, length)? as u64
+                            * element_bits(element)?,
+                    ),
+                    FieldType::VariableArray(element, capacity) => {
+                        let capacity = self.evaluate_size(definition, body, capacity)? as u64;
+                        let prefix = standard_bit_length_for(capacity);
+
+                        (
+                            0,
+                            align(prefix, element) + capacity * element_bits(element)?,
+                        )
+                    }
+                },
+                Statement::Padding(bits) => (0, *bits as u64),
+                Statement::Constant { .. } => continue,
+            });
+        }
+
+        let bits = if body.is_union {
+            let tag = standard_bit_length_for(field_sizes.len().saturating_sub(1) as u64);
+            tag + field_sizes
+                .iter()
+                .map(|(alignment, size)| alignment + size)
+                .max()
+                .unwrap_or(0)
+        } else {
+            field_sizes.iter().fold(0, |offset, (alignment, size)| {
+                let offset = if *alignment > 0 {
+                    round_up_to_byte(offset)
+                } else {
+                    offset
+                };
+
+                offset + size
+            })
+        };
+
+        Ok(round_up_to_byte(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn namespace(definitions: &[(&str, &[&str], &str)]) -> Namespace {
+        let mut namespace = Namespace::default();
+        for (file_name, path, source) in definitions {
+            namespace
+                .insert(
+                    parse_definition(
+                        Path::new(file_name),
+                        path.iter()
+                            .map(|component| String::from(*component))
+                            .collect(),
+                        source,
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+        }
+
+        namespace
+    }
+
+    fn resolve_message(namespace: &Namespace, name: &str) -> ResolvedBody {
+        let definition = namespace
+            .definitions()
+            .find(|definition| definition.short_name == name)
+            .unwrap();
+
+        match &definition.kind {
+            DefinitionKind::Message(body) => namespace.resolve_body(definition, body).unwrap(),
+            _ => panic!("expected a message"),
+        }
+    }
+
+    #[test]
+    fn array_capacities_can_reference_constants_of_other_types() {
+        let namespace = namespace(&[
+            (
+                "Path.2.0.dsdl",
+                &["uavcan", "file"],
+                "uint8 MAX_LENGTH = 255\nuint8[<=MAX_LENGTH] path\n@sealed",
+            ),
+            (
+                "Read.1.0.dsdl",
+                &["uavcan", "file"],
+                "uint8[<=Path.2.0.MAX_LENGTH + 1] data\n@extent 300 * 8",
+            ),
+        ]);
+
+        assert_eq!(
+            resolve_message(&namespace, "Read").members,
+            vec![ResolvedMember::Field {
+                name: String::from("data"),
+                ty: ResolvedFieldType::VariableArray(
+                    ResolvedElement::Primitive(PrimitiveType::Unsigned(8), CastMode::Saturated),
+                    256
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn the_extent_of_a_sealed_type_is_its_maximum_size() {
+        let namespace = namespace(&[
+            (
+                "Health.1.0.dsdl",
+                &["uavcan", "node"],
+                "uint2 value\n@sealed",
+            ),
+            (
+                "Status.1.0.dsdl",
+                &["uavcan", "node"],
+                "uint32 uptime\nHealth.1.0 health\nuint8[<=3] codes\n@sealed",
+            ),
+        ]);
+
+        assert_eq!(resolve_message(&namespace, "Health").extent_bytes, 1);
+        assert_eq!(
+            resolve_message(&namespace, "Status").extent_bytes,
+            4 + 1 + 1 + 3
+        );
+    }
+
+    #[test]
+    fn a_delimited_nested_type_contributes_its_extent_and_header_to_the_maximum_size() {
+        let namespace = namespace(&[
+            ("Inner.1.0.dsdl", &["ns"], "uint8 value\n@extent 16 * 8"),
+            (
+                "Outer.1.0.dsdl",
+                &["ns"],
+                "bool flag\nInner.1.0 inner\n@sealed",
+            ),
+        ]);
+
+        assert_eq!(
+            resolve_message(&namespace, "Outer").extent_bytes,
+            1 + 4 + 16
+        );
+    }
+
+    #[test]
+    fn the_size_of_a_union_is_its_tag_plus_its_largest_variant() {
+        let namespace = namespace(&[(
+            "Value.1.0.dsdl",
+            &["ns"],
+            "@union\nuint8 small\nuint32 large\n@sealed",
+        )]);
+
+        assert_eq!(resolve_message(&namespace, "Value").extent_bytes, 1 + 4);
+    }
+
+    #[test]
+    fn a_type_that_is_neither_sealed_nor_has_an_extent_is_an_error() {
+        let namespace = namespace(&[("Open.1.0.dsdl", &["ns"], "uint8 value")]);
+        let definition = namespace.definitions().next().unwrap();
+
+        match &definition.kind {
+            DefinitionKind::Message(body) => {
+                assert!(namespace.resolve_body(definition, body).is_err())
+            }
+            _ => panic!("expected a message"),
+        }
+    }
+
+    #[test]
+    fn referencing_an_unknown_type_is_an_error() {
+        let namespace = namespace(&[("Broken.1.0.dsdl", &["ns"], "Missing.1.0 value\n@sealed")]);
+        let definition = namespace.definitions().next().unwrap();
+
+        match &definition.kind {
+            DefinitionKind::Message(body) => {
+                assert!(namespace.resolve_body(definition, body).is_err())
+            }
+            _ => panic!("expected a message"),
+        }
+    }
+}
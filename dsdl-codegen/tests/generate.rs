@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use uavcan_dsdl_codegen::Builder;
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/uavcan")
+}
+
+fn generate() -> String {
+    Builder::new().namespace(fixtures()).generate().unwrap()
+}
+
+#[test]
+fn every_definition_is_generated_in_a_module_named_after_its_namespace_and_version() {
+    let output = generate();
+
+    assert!(output.contains("pub mod uavcan {"));
+    assert!(output.contains("pub mod node {"));
+    assert!(output.contains("pub mod heartbeat_1_0 {"));
+    assert!(output.contains("pub mod get_info_1_0 {"));
+    assert!(output.contains("pub mod read_1_1 {"));
+}
+
+#[test]
+fn fixed_port_ids_are_generated_as_session_id_constants() {
+    let output = generate();
+
+    assert!(output.contains(
+        "pub const SUBJECT_ID: ::uavcan::session_id::SubjectId = ::uavcan::session_id::SubjectId::from_const(7509);"
+    ));
+    assert!(output.contains(
+        "pub const SERVICE_ID: ::uavcan::session_id::ServiceId = ::uavcan::session_id::ServiceId::from_const(430);"
+    ));
+}
+
+#[test]
+fn services_generate_a_request_and_a_response_type() {
+    let output = generate();
+
+    assert!(output.contains("pub struct GetInfoRequest {"));
+    assert!(output.contains("pub struct GetInfoResponse {"));
+}
+
+#[test]
+fn services_with_a_fixed_port_id_implement_the_service_trait() {
+    let output = generate();
+
+    assert!(output.contains("impl ::uavcan::presentation::Service for GetInfo {"));
+    assert!(output.contains("type Request = GetInfoRequest;"));
+    assert!(output.contains("type Response = GetInfoResponse;"));
+}
+
+#[test]
+fn constants_are_generated_as_associated_constants() {
+    let output = generate();
+
+    assert!(output.contains("pub const MAX_PUBLICATION_PERIOD: u16 = 1;"));
+    assert!(output.contains("pub const SEPARATOR: u8 = 47;"));
+}
+
+#[test]
+fn the_extent_and_sealing_of_each_type_is_generated() {
+    let output = generate();
+
+    assert!(output.contains("const EXTENT_BYTES: usize = 12;\n"));
+    assert!(output.contains("const EXTENT_BYTES: usize = 448;\n"));
+}
+
+#[test]
+fn unions_are_generated_as_enums() {
+    let output = generate();
+
+    assert!(output.contains("pub enum Value {"));
+    assert!(output.contains("Integers(::uavcan::heapless::Vec<i16, 4>),"));
+}
+
+#[test]
+fn the_crate_path_used_by_the_generated_code_can_be_changed() {
+    let output = Builder::new()
+        .namespace(fixtures())
+        .crate_path("crate")
+        .generate()
+        .unwrap();
+
+    assert!(output.contains("use crate::dsdl::"));
+    assert!(!output.contains("use ::uavcan::"));
+    assert!(!output.contains("::uavcan::session_id::"));
+}
+
+#[test]
+fn a_missing_namespace_directory_is_an_error() {
+    assert!(Builder::new()
+        .namespace(fixtures().join("missing"))
+        .generate()
+        .is_err());
+}
@@ -1,62 +1,14 @@
-use heapless::consts::*;
 use rand::RngCore;
-use socketcan::{CANFrame, CANSocket};
 use uavcan::rx::rx_network::{RxConsumer, RxNetwork, RxProducer};
 use uavcan::session_id::{NodeId, SessionKind, SubjectId, TransferPriority};
-use uavcan::tx::{
-    stream_transmitter::{CanWriter, StreamTransmitter},
-    transmitter::send,
-};
-use uavcan::{CanFrame, CLASSIC_MTU};
+use uavcan::socket_can::{Event, SocketCan, SocketCanFrame};
+use uavcan::tx::{stream_transmitter::StreamTransmitter, transmitter::send};
+use uavcan::CLASSIC_MTU;
 
-#[derive(Debug)]
-struct ClassicFrame {
-    data: [u8; 8],
-    id: u32,
-    len: usize,
-}
-
-impl From<(u32, [u8; 8], usize)> for ClassicFrame {
-    fn from((id, data, len): (u32, [u8; 8], usize)) -> Self {
-        Self { data, id, len }
-    }
-}
-
-impl From<CANFrame> for ClassicFrame {
-    fn from(frame: CANFrame) -> Self {
-        let (id, original_data) = (frame.id(), frame.data());
-
-        let mut data = [0u8; 8];
-        data[..original_data.len()].copy_from_slice(original_data);
-
-        Self::from((id, data, original_data.len()))
-    }
-}
-
-impl CanFrame<CLASSIC_MTU> for ClassicFrame {
-    fn id(&self) -> u32 {
-        self.id
-    }
-
-    fn payload(&self) -> (&[u8; CLASSIC_MTU], usize) {
-        (&self.data, self.len)
-    }
-}
-
-struct CanTx(CANSocket);
-
-impl CanWriter<ClassicFrame, CLASSIC_MTU> for CanTx {
-    type Error = std::io::Error;
-
-    fn write_frame(&mut self, frame: ClassicFrame) -> Result<(), Self::Error> {
-        let (payload, payload_len) = frame.payload();
-        self.0
-            .write_frame(&CANFrame::new(frame.id(), &payload[..payload_len], false, false).unwrap())
-    }
-}
+type Frame = SocketCanFrame<CLASSIC_MTU>;
 
 fn transmit(
-    transmitter: &mut StreamTransmitter<CanTx, ClassicFrame, CLASSIC_MTU>,
+    transmitter: &mut StreamTransmitter<SocketCan<CLASSIC_MTU>, Frame, CLASSIC_MTU>,
     node_id: NodeId,
 ) {
     println!("Building random payload for transmission.");
@@ -79,19 +31,21 @@ fn transmit(
 }
 
 fn receive<'a>(
-    rx_socket: &CANSocket,
-    receiver: &mut RxProducer<'a, ClassicFrame, U64, U512, CLASSIC_MTU>,
+    rx_socket: &mut SocketCan<CLASSIC_MTU>,
+    receiver: &mut RxProducer<'a, Frame, 64, 512, CLASSIC_MTU>,
 ) {
     println!("Looking for frames from socket.");
-    while let Ok(frame) = rx_socket.read_frame() {
-        println!("Found frame {:?}.", frame);
-        println!("Storing frame for later.");
-        receiver.receive(ClassicFrame::from(frame)).unwrap();
-        println!("Frame stored for later.");
+    while let Some(event) = rx_socket.read().unwrap() {
+        if let Event::Received(frame) = event {
+            println!("Found frame {:?}.", frame);
+            println!("Storing frame for later.");
+            receiver.receive(frame).unwrap();
+            println!("Frame stored for later.");
+        }
     }
 }
 
-fn process(receiver: &mut RxConsumer<ClassicFrame, U64, U512, CLASSIC_MTU>) {
+fn process(receiver: &mut RxConsumer<Frame, 64, 512, CLASSIC_MTU>) {
     println!("Looking for stored transfers.");
     for transfer in receiver {
         println!("Found transfer {:?}", transfer);
@@ -100,20 +54,16 @@ fn process(receiver: &mut RxConsumer<ClassicFrame, U64, U512, CLASSIC_MTU>) {
 
 fn main() {
     println!("Opening Sockets.");
-    let tx_socket = CANSocket::open("vcan0").unwrap();
-    let rx_socket = CANSocket::open("vcan0").unwrap();
-    rx_socket
-        .set_read_timeout(std::time::Duration::from_millis(500))
-        .unwrap();
+    let tx_socket = SocketCan::<CLASSIC_MTU>::open("vcan0").unwrap();
+    let mut rx_socket = SocketCan::<CLASSIC_MTU>::open("vcan0").unwrap();
     println!("Socket opened.");
 
     println!("Initializing transmitter.");
-    let mut transmitter =
-        StreamTransmitter::<CanTx, ClassicFrame, CLASSIC_MTU>::new(CanTx(tx_socket));
+    let mut transmitter = StreamTransmitter::new(tx_socket);
     println!("Transmitter initialized.");
 
     println!("Initializing receiver network.");
-    let mut rx_network = RxNetwork::<ClassicFrame, U64, U512, CLASSIC_MTU>::default();
+    let mut rx_network = RxNetwork::<Frame, 64, 512, CLASSIC_MTU>::default();
     let (mut rx_producer, mut rx_consumer) = rx_network.split();
     println!("Receiver network initialized.");
 
@@ -124,7 +74,8 @@ fn main() {
     println!("Starting the loop.");
     loop {
         transmit(&mut transmitter, node_id);
-        receive(&rx_socket, &mut rx_producer);
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        receive(&mut rx_socket, &mut rx_producer);
         process(&mut rx_consumer);
     }
 }
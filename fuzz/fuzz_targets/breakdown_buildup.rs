@@ -0,0 +1,48 @@
+//! Breaks arbitrary payloads down into frames and builds them up again, which
+//! must give back the original payload.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use uavcan::candump::LoggedFrame;
+use uavcan::rx::rx_network::RxNetwork;
+use uavcan::session_id::SessionId;
+use uavcan::tx::breakdown::Breakdown;
+use uavcan::{CLASSIC_MTU, EXTENDED_MTU};
+
+const TRANSFER_CAPACITY: usize = 512;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    is_fd: bool,
+    can_id: u32,
+    payload: Vec<u8>,
+}
+
+fn round_trip<const MTU: usize>(can_id: u32, payload: &[u8]) {
+    let mut network = RxNetwork::<LoggedFrame<MTU>, 2, TRANSFER_CAPACITY, MTU>::default();
+    let (mut producer, mut consumer) = network.split();
+
+    for frame in Breakdown::<LoggedFrame<MTU>, MTU>::new(payload, can_id) {
+        producer.receive(frame).unwrap();
+    }
+
+    let transfer = consumer.next().unwrap();
+    assert_eq!(&transfer.payload[..], payload);
+    assert!(consumer.next().is_none());
+}
+
+fuzz_target!(|input: Input| {
+    // Only valid sessions are built up, and the payload must fit the
+    // receiver along with the CRC of multi-frame transfers.
+    if !SessionId::from(input.can_id).is_valid() || input.payload.len() > TRANSFER_CAPACITY - 2 {
+        return;
+    }
+
+    if input.is_fd {
+        round_trip::<EXTENDED_MTU>(input.can_id, &input.payload);
+    } else {
+        round_trip::<CLASSIC_MTU>(input.can_id, &input.payload);
+    }
+});
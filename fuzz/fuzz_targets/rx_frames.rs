@@ -0,0 +1,42 @@
+//! Feeds arbitrary sequences of frames, including frames claiming more bytes
+//! than the MTU, to a receiver, which must reject them without panicking.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use uavcan::candump::LoggedFrame;
+use uavcan::rx::rx_network::RxNetwork;
+use uavcan::{CLASSIC_MTU, EXTENDED_MTU};
+
+#[derive(Arbitrary, Debug)]
+struct Frame {
+    id: u32,
+    len: u8,
+    data: [u8; EXTENDED_MTU],
+}
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    Classic(Vec<Frame>),
+    Fd(Vec<Frame>),
+}
+
+fn receive_all<const MTU: usize>(frames: Vec<Frame>) {
+    let mut network = RxNetwork::<LoggedFrame<MTU>, 4, 256, MTU>::default();
+    let (mut producer, mut consumer) = network.split();
+
+    for frame in frames {
+        let mut data = [0; MTU];
+        data.copy_from_slice(&frame.data[..MTU]);
+
+        let _ = producer.receive(LoggedFrame::from((frame.id, data, usize::from(frame.len))));
+        // Keeps room in the queue so that every transfer can be completed.
+        consumer.next();
+    }
+}
+
+fuzz_target!(|input: Input| match input {
+    Input::Classic(frames) => receive_all::<CLASSIC_MTU>(frames),
+    Input::Fd(frames) => receive_all::<EXTENDED_MTU>(frames),
+});
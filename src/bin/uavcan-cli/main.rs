@@ -0,0 +1,204 @@
+//! Publishes, subscribes and calls services over a SocketCAN interface, for
+//! bench testing.
+//!
+//! ```text
+//! uavcan-cli --node-id 42 --hex 0102 --rate 10 publish 1000
+//! uavcan-cli subscribe 7509
+//! uavcan-cli --node-id 42 --timeout 500 call 10 430
+//! ```
+
+mod options;
+
+use std::{
+    env, process, thread,
+    time::{Duration, Instant},
+};
+
+use options::{parse_options, Command, Options, USAGE};
+use uavcan::rx::{rx_network::RxNetwork, transfer::Transfer};
+use uavcan::session_id::{session_kind::Request, NodeId, ServiceId, SessionKind, SubjectId};
+use uavcan::socket_can::{Event, Filter, SocketCan, SocketCanFrame};
+use uavcan::tail_byte::TransferId;
+use uavcan::tx::{stream_transmitter::StreamTransmitter, transmitter::send_with_transfer_id};
+use uavcan::{CLASSIC_MTU, EXTENDED_MTU};
+
+/// The transfers that can wait to be printed.
+const QUEUE_CAPACITY: usize = 16;
+/// The largest transfer that is reassembled.
+const TRANSFER_CAPACITY: usize = 2048;
+
+type Network<const MTU: usize> =
+    RxNetwork<SocketCanFrame<MTU>, QUEUE_CAPACITY, TRANSFER_CAPACITY, MTU>;
+
+fn print_transfer<const CAPACITY: usize>(transfer: &Transfer<CAPACITY>, source: NodeId) {
+    let micros = transfer.timestamp.unwrap_or_default().as_micros();
+    let payload: String = transfer
+        .payload
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect();
+
+    println!(
+        "({}.{:06}) src={} tid={} len={} {}",
+        micros / 1_000_000,
+        micros % 1_000_000,
+        u8::from_le_bytes(source.into_bytes()),
+        u8::from_le_bytes(transfer.transfer_id.into_bytes()),
+        transfer.payload.len(),
+        payload
+    );
+}
+
+fn local_node_id(options: &Options) -> Result<NodeId, String> {
+    options
+        .node_id
+        .ok_or_else(|| "--node-id is needed to send transfers".to_string())
+}
+
+fn transmitter<const MTU: usize>(
+    options: &Options,
+) -> Result<StreamTransmitter<SocketCan<MTU>, SocketCanFrame<MTU>, MTU>, String> {
+    SocketCan::open(&options.interface)
+        .map(StreamTransmitter::new)
+        .map_err(|err| format!("{}: {}", options.interface, err))
+}
+
+fn receiver<const MTU: usize>(options: &Options, filter: Filter) -> Result<SocketCan<MTU>, String> {
+    let socket = SocketCan::open(&options.interface)
+        .map_err(|err| format!("{}: {}", options.interface, err))?;
+    socket
+        .set_filters(&[filter])
+        .map_err(|err| err.to_string())?;
+
+    Ok(socket)
+}
+
+fn publish<const MTU: usize>(options: &Options, subject_id: SubjectId) -> Result<(), String> {
+    let kind = SessionKind::Message {
+        source_node_id: local_node_id(options)?,
+        subject_id,
+    };
+    let mut transmitter = transmitter::<MTU>(options)?;
+    let mut transfer_id = TransferId::new();
+    let count = options.count.unwrap_or(if options.period.is_some() {
+        u64::MAX
+    } else {
+        1
+    });
+    let start = Instant::now();
+
+    for index in 0..count {
+        if let Some(period) = options.period {
+            let next = start + period.mul_f64(index as f64);
+            thread::sleep(next.saturating_duration_since(Instant::now()));
+        }
+
+        send_with_transfer_id(
+            &mut transmitter,
+            &options.payload,
+            kind,
+            options.priority,
+            transfer_id,
+        )
+        .map_err(|err| err.to_string())?;
+        transfer_id.advance();
+    }
+
+    Ok(())
+}
+
+fn subscribe<const MTU: usize>(options: &Options, subject_id: SubjectId) -> Result<(), String> {
+    let mut socket = receiver::<MTU>(options, Filter::messages(subject_id))?;
+    let mut network = Network::<MTU>::default();
+    let (mut producer, mut consumer) = network.split();
+    let mut printed = 0;
+
+    while printed < options.count.unwrap_or(u64::MAX) {
+        match socket.read().map_err(|err| err.to_string())? {
+            Some(Event::Received(frame)) => {
+                // Malformed frames are of no interest to the subscriber.
+                let _ = producer.receive(frame);
+            }
+            Some(Event::Transmitted(_)) => {}
+            None => thread::sleep(Duration::from_millis(1)),
+        }
+
+        for transfer in &mut consumer {
+            if let SessionKind::Message { source_node_id, .. } = transfer.kind {
+                print_transfer(&transfer, source_node_id);
+                printed += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn call<const MTU: usize>(
+    options: &Options,
+    server_node_id: NodeId,
+    service_id: ServiceId,
+) -> Result<(), String> {
+    let node_id = local_node_id(options)?;
+    let mut socket = receiver::<MTU>(options, Filter::responses(service_id, node_id))?;
+    let mut network = Network::<MTU>::default();
+    let (mut producer, mut consumer) = network.split();
+
+    let transfer_id = TransferId::new();
+    send_with_transfer_id(
+        &mut transmitter::<MTU>(options)?,
+        &options.payload,
+        SessionKind::Request(Request::new(node_id, server_node_id, service_id)),
+        options.priority,
+        transfer_id,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // The response is addressed back to this node by the server.
+    let expected = SessionKind::Response(Request::new(node_id, server_node_id, service_id));
+    let deadline = Instant::now() + options.timeout;
+    while Instant::now() < deadline {
+        match socket.read().map_err(|err| err.to_string())? {
+            Some(Event::Received(frame)) => {
+                let _ = producer.receive(frame);
+            }
+            Some(Event::Transmitted(_)) => {}
+            None => thread::sleep(Duration::from_millis(1)),
+        }
+
+        if let Some(response) = consumer
+            .find(|transfer| transfer.kind == expected && transfer.transfer_id == transfer_id)
+        {
+            print_transfer(&response, server_node_id);
+            return Ok(());
+        }
+    }
+
+    Err("no response before the timeout".to_string())
+}
+
+fn run<const MTU: usize>(options: &Options) -> Result<(), String> {
+    match options.command {
+        Command::Publish(subject_id) => publish::<MTU>(options, subject_id),
+        Command::Subscribe(subject_id) => subscribe::<MTU>(options, subject_id),
+        Command::Call(node_id, service_id) => call::<MTU>(options, node_id, service_id),
+    }
+}
+
+fn main() {
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("error: {}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let result = if options.is_fd {
+        run::<EXTENDED_MTU>(&options)
+    } else {
+        run::<CLASSIC_MTU>(&options)
+    };
+
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,242 @@
+use std::{convert::TryFrom, fs, str::FromStr, time::Duration};
+
+use uavcan::session_id::{NodeId, ServiceId, SubjectId, TransferPriority};
+
+pub const USAGE: &str = "\
+Usage: uavcan-cli [OPTIONS] <COMMAND>
+
+Commands:
+    publish <SUBJECT>        Publishes the payload on a subject
+    subscribe <SUBJECT>      Prints the transfers published on a subject
+    call <NODE> <SERVICE>    Sends the payload as a request and prints the response
+
+Options:
+    --interface <NAME>    The SocketCAN interface to use [default: vcan0]
+    --node-id <ID>        The node ID of this node, needed to publish and to call
+    --priority <NAME>     The priority of the sent transfers, such as High [default: Nominal]
+    --fd                  Use CAN FD frames instead of classic ones
+    --hex <HEX>           The payload to send, as hexadecimal digits
+    --file <PATH>         The payload to send, read from a file
+    --rate <HZ>           How many transfers to publish per second
+    --count <N>           How many transfers to publish, or to print before exiting
+    --timeout <MS>        How long to wait for a response [default: 1000]";
+
+pub enum Command {
+    Publish(SubjectId),
+    Subscribe(SubjectId),
+    Call(NodeId, ServiceId),
+}
+
+pub struct Options {
+    pub command: Command,
+    pub interface: String,
+    pub node_id: Option<NodeId>,
+    pub priority: TransferPriority,
+    pub is_fd: bool,
+    pub payload: Vec<u8>,
+    /// The period between published transfers, or `None` to publish as fast
+    /// as possible.
+    pub period: Option<Duration>,
+    pub count: Option<u64>,
+    pub timeout: Duration,
+}
+
+fn number<T: FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, not {}", name, value))
+}
+
+fn subject_id(value: &str) -> Result<SubjectId, String> {
+    SubjectId::try_from(number::<u16>(value, "SUBJECT")?)
+        .map_err(|_| "a subject ID is lower than 8192".to_string())
+}
+
+fn node_id(value: &str, name: &str) -> Result<NodeId, String> {
+    NodeId::try_from(number::<u8>(value, name)?)
+        .map_err(|_| "a node ID is lower than 128".to_string())
+}
+
+fn service_id(value: &str) -> Result<ServiceId, String> {
+    ServiceId::try_from(number::<u16>(value, "SERVICE")?)
+        .map_err(|_| "a service ID is lower than 512".to_string())
+}
+
+fn hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<_> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("--hex expects two digits per byte".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| "--hex expects hexadecimal digits".to_string())
+        })
+        .collect()
+}
+
+pub fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut positional = Vec::new();
+    let mut interface = "vcan0".to_string();
+    let mut node = None;
+    let mut priority = TransferPriority::Nominal;
+    let mut is_fd = false;
+    let mut payload = Vec::new();
+    let mut period = None;
+    let mut count = None;
+    let mut timeout = Duration::from_millis(1000);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{} expects a value", arg))
+        };
+
+        match arg.as_str() {
+            "--interface" => interface = value()?,
+            "--node-id" => node = Some(node_id(&value()?, "--node-id")?),
+            "--priority" => {
+                let name = value()?;
+                priority = TransferPriority::from_str(&name)
+                    .map_err(|_| format!("unknown priority {}", name))?
+            }
+            "--fd" => is_fd = true,
+            "--hex" => payload = hex(&value()?)?,
+            "--file" => {
+                let path = value()?;
+                payload = fs::read(&path).map_err(|err| format!("{}: {}", path, err))?
+            }
+            "--rate" => {
+                let rate: f64 = number(&value()?, "--rate")?;
+                if !rate.is_finite() || rate <= 0.0 {
+                    return Err("--rate expects a positive number".to_string());
+                }
+                period = Some(
+                    Duration::try_from_secs_f64(1.0 / rate)
+                        .map_err(|_| format!("--rate {} is too low", rate))?,
+                )
+            }
+            "--count" => count = Some(number(&value()?, "--count")?),
+            "--timeout" => timeout = Duration::from_millis(number(&value()?, "--timeout")?),
+            _ if arg.starts_with("--") => return Err(format!("unexpected option {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = match positional
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["publish", subject] => Command::Publish(subject_id(subject)?),
+        ["subscribe", subject] => Command::Subscribe(subject_id(subject)?),
+        ["call", node, service] => Command::Call(node_id(node, "NODE")?, service_id(service)?),
+        [] => return Err("a command is required".to_string()),
+        _ => return Err(format!("unexpected command {}", positional.join(" "))),
+    };
+
+    Ok(Options {
+        command,
+        interface,
+        node_id: node,
+        priority,
+        is_fd,
+        payload,
+        period,
+        count,
+        timeout,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Options, String> {
+        parse_options(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn hexadecimal_digits_are_decoded_two_by_two() {
+        assert_eq!(hex("00 1f\tA0ff"), Ok(vec![0x00, 0x1F, 0xA0, 0xFF]));
+        assert_eq!(hex(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn an_odd_number_of_digits_or_a_non_hexadecimal_digit_is_rejected() {
+        assert!(hex("123").is_err());
+        assert!(hex("0g").is_err());
+        assert!(hex("é0").is_err());
+    }
+
+    #[test]
+    fn a_publish_command_is_parsed_with_its_options() {
+        let options =
+            parse("publish 100 --node-id 42 --priority High --fd --hex 0102 --rate 4 --count 3")
+                .unwrap();
+
+        assert!(
+            matches!(options.command, Command::Publish(subject) if subject == SubjectId::try_from(100).unwrap())
+        );
+        assert_eq!(options.node_id, Some(NodeId::try_from(42).unwrap()));
+        assert_eq!(options.priority, TransferPriority::High);
+        assert!(options.is_fd);
+        assert_eq!(options.payload, [1, 2]);
+        assert_eq!(options.period, Some(Duration::from_millis(250)));
+        assert_eq!(options.count, Some(3));
+    }
+
+    #[test]
+    fn the_defaults_are_used_for_the_options_that_are_not_given() {
+        let options = parse("call 12 430").unwrap();
+
+        assert!(matches!(
+            options.command,
+            Command::Call(node, service)
+                if node == NodeId::try_from(12).unwrap() && service == ServiceId::try_from(430).unwrap()
+        ));
+        assert_eq!(options.interface, "vcan0");
+        assert_eq!(options.node_id, None);
+        assert_eq!(options.priority, TransferPriority::Nominal);
+        assert!(options.payload.is_empty());
+        assert_eq!(options.period, None);
+        assert_eq!(options.timeout, Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn a_rate_that_is_not_a_positive_number_is_rejected() {
+        for rate in ["0", "-1", "nan", "inf", "1e-320", "fast"] {
+            assert!(
+                parse(&format!("publish 100 --rate {}", rate)).is_err(),
+                "{}",
+                rate
+            );
+        }
+    }
+
+    #[test]
+    fn identifiers_out_of_range_are_rejected() {
+        assert!(parse("publish 8192").is_err());
+        assert!(parse("call 128 1").is_err());
+        assert!(parse("call 1 512").is_err());
+        assert!(parse("subscribe 1 --node-id 200").is_err());
+    }
+
+    #[test]
+    fn a_missing_command_or_value_and_unknown_arguments_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("publish").is_err());
+        assert!(parse("listen 100").is_err());
+        assert!(parse("publish 100 --verbose").is_err());
+        assert!(parse("publish 100 --rate").is_err());
+        assert!(parse("publish 100 --priority Urgent").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Watches a Cyphal/CAN bus, live or from a log, and prints a table of the
+//! nodes and sessions seen on it, or every transfer with `--raw`.
+//!
+//! ```text
+//! uavcan-monitor --interface vcan0
+//! uavcan-monitor --raw --candump capture.log
+//! uavcan-monitor --fd --pcap capture.pcapng
+//! ```
+
+mod source;
+mod table;
+
+use std::{
+    env,
+    io::{self, Write},
+    process, thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use source::{Poll, Source};
+use table::Table;
+use uavcan::candump::LoggedFrame;
+use uavcan::rx::{rx_network::RxNetwork, transfer::Transfer};
+use uavcan::session_id::{can_id_for_session_kind, SessionId};
+use uavcan::timestamp::Timestamp;
+use uavcan::{CanFrame, CLASSIC_MTU, EXTENDED_MTU};
+
+const USAGE: &str = "\
+Usage: uavcan-monitor [OPTIONS] (--interface <NAME> | --candump <FILE> | --pcap <FILE>)
+
+Options:
+    --fd               Receive CAN FD frames instead of classic ones
+    --raw              Print every transfer instead of a table
+    --interval <MS>    How often the table of a live interface is refreshed [default: 1000]";
+
+/// The transfers that can wait to be printed.
+const QUEUE_CAPACITY: usize = 16;
+/// The largest transfer that is reassembled.
+const TRANSFER_CAPACITY: usize = 2048;
+
+enum SourceKind {
+    Interface,
+    Candump,
+    Pcap,
+}
+
+struct Options {
+    source: (SourceKind, String),
+    is_fd: bool,
+    is_raw: bool,
+    interval: Duration,
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut source = None;
+    let mut is_fd = false;
+    let mut is_raw = false;
+    let mut interval = Duration::from_millis(1000);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{} expects a value", arg))
+        };
+
+        match arg.as_str() {
+            "--fd" => is_fd = true,
+            "--raw" => is_raw = true,
+            "--interval" => {
+                interval = value()?
+                    .parse()
+                    .map(Duration::from_millis)
+                    .map_err(|_| "--interval expects a number of milliseconds".to_string())?
+            }
+            "--interface" => source = Some((SourceKind::Interface, value()?)),
+            "--candump" => source = Some((SourceKind::Candump, value()?)),
+            "--pcap" => source = Some((SourceKind::Pcap, value()?)),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    Ok(Options {
+        source: source.ok_or("a source is required")?,
+        is_fd,
+        is_raw,
+        interval,
+    })
+}
+
+fn print_transfer<const CAPACITY: usize>(
+    out: &mut impl Write,
+    transfer: &Transfer<CAPACITY>,
+    table: &Table,
+) -> io::Result<()> {
+    let micros = transfer.timestamp.unwrap_or_default().as_micros();
+    let session_id = SessionId::from(can_id_for_session_kind(
+        transfer.kind,
+        table.priority(transfer.kind),
+    ));
+
+    write!(
+        out,
+        "({}.{:06}) {} tid={} len={}",
+        micros / 1_000_000,
+        micros % 1_000_000,
+        session_id,
+        u8::from_le_bytes(transfer.transfer_id.into_bytes()),
+        transfer.payload.len()
+    )?;
+    if !transfer.payload.is_empty() {
+        write!(out, " ")?;
+    }
+    for byte in &transfer.payload {
+        write!(out, "{:02X}", byte)?;
+    }
+    writeln!(out)
+}
+
+fn monitor<const MTU: usize>(mut source: Source<MTU>, options: &Options) -> io::Result<()> {
+    let mut network =
+        RxNetwork::<LoggedFrame<MTU>, QUEUE_CAPACITY, TRANSFER_CAPACITY, MTU>::default();
+    let (mut producer, mut consumer) = network.split();
+    let mut table = Table::default();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut last_refresh = Instant::now();
+
+    loop {
+        match source.poll()? {
+            Poll::Frame(frame) => {
+                table.record_frame(frame.id(), frame.timestamp());
+                // Malformed frames are only counted in the statistics.
+                let _ = producer.receive_with_sessions(frame, &mut table.sessions);
+
+                for transfer in &mut consumer {
+                    table.record_transfer(&transfer);
+                    if options.is_raw {
+                        print_transfer(&mut out, &transfer, &table)?;
+                    }
+                }
+            }
+            Poll::Idle => thread::sleep(Duration::from_millis(1)),
+            Poll::End => break,
+        }
+
+        if !options.is_raw && source.is_live() && last_refresh.elapsed() >= options.interval {
+            // The frames of an interface are timestamped with the time of
+            // day, which keeps going while the bus is quiet.
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            table.tick(Timestamp::from_micros(now.as_micros() as u64));
+
+            // Clears the terminal before drawing the table again.
+            write!(out, "\x1b[2J\x1b[H")?;
+            table.print(&mut out, producer.statistics())?;
+            out.flush()?;
+            last_refresh = Instant::now();
+        }
+    }
+
+    if !options.is_raw {
+        table.print(&mut out, producer.statistics())?;
+    }
+
+    Ok(())
+}
+
+fn open_and_monitor<const MTU: usize>(options: &Options) -> io::Result<()> {
+    let (kind, name) = &options.source;
+    let source = match kind {
+        SourceKind::Interface => Source::interface(name)?,
+        SourceKind::Candump => Source::candump(name)?,
+        SourceKind::Pcap => Source::pcap(name)?,
+    };
+
+    monitor::<MTU>(source, options)
+}
+
+fn main() {
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("error: {}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let result = if options.is_fd {
+        open_and_monitor::<EXTENDED_MTU>(&options)
+    } else {
+        open_and_monitor::<CLASSIC_MTU>(&options)
+    };
+
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Options, String> {
+        parse_options(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn a_source_is_parsed_with_its_options() {
+        let options = parse("--fd --raw --interval 250 --candump capture.log").unwrap();
+
+        assert!(matches!(options.source, (SourceKind::Candump, ref name) if name == "capture.log"));
+        assert!(options.is_fd);
+        assert!(options.is_raw);
+        assert_eq!(options.interval, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn the_defaults_are_used_for_the_options_that_are_not_given() {
+        let options = parse("--interface vcan0").unwrap();
+
+        assert!(matches!(options.source, (SourceKind::Interface, ref name) if name == "vcan0"));
+        assert!(!options.is_fd);
+        assert!(!options.is_raw);
+        assert_eq!(options.interval, Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn the_last_source_given_is_used() {
+        let options = parse("--candump capture.log --pcap capture.pcapng").unwrap();
+
+        assert!(matches!(options.source, (SourceKind::Pcap, ref name) if name == "capture.pcapng"));
+    }
+
+    #[test]
+    fn a_missing_source_or_value_and_unknown_arguments_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("--raw").is_err());
+        assert!(parse("--interface").is_err());
+        assert!(parse("--interface vcan0 --interval soon").is_err());
+        assert!(parse("--interface vcan0 --verbose").is_err());
+        assert!(parse("--interface vcan0 capture.log").is_err());
+    }
+}
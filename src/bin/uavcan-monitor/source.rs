@@ -0,0 +1,87 @@
+use std::{fs::File, io, io::BufReader};
+
+use uavcan::candump::{self, CandumpReader, LoggedFrame};
+use uavcan::pcap::{self, PcapReader};
+use uavcan::socket_can::{Event, SocketCan};
+use uavcan::CanFrame;
+
+/// What a [Source] yielded when polled.
+pub enum Poll<const MTU: usize> {
+    Frame(LoggedFrame<MTU>),
+    /// Nothing to read for now, as on a quiet bus.
+    Idle,
+    /// The end of a log.
+    End,
+}
+
+/// Where the frames come from: a live interface, or a log that is replayed
+/// as fast as it can be read.
+pub enum Source<const MTU: usize> {
+    Interface(SocketCan<MTU>),
+    Candump(CandumpReader<BufReader<File>, MTU>),
+    Pcap(PcapReader<BufReader<File>, MTU>),
+}
+
+impl<const MTU: usize> Source<MTU> {
+    pub fn interface(name: &str) -> io::Result<Self> {
+        SocketCan::open(name).map(Source::Interface)
+    }
+
+    pub fn candump(path: &str) -> io::Result<Self> {
+        Ok(Source::Candump(CandumpReader::new(BufReader::new(
+            File::open(path)?,
+        ))))
+    }
+
+    pub fn pcap(path: &str) -> io::Result<Self> {
+        PcapReader::new(BufReader::new(File::open(path)?))
+            .map(Source::Pcap)
+            .map_err(|err| match err {
+                pcap::ReadError::Io(err) => err,
+                err => io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err)),
+            })
+    }
+
+    pub fn is_live(&self) -> bool {
+        matches!(self, Source::Interface(_))
+    }
+
+    /// Reads the next frame, skipping the lines and packets of a log that do
+    /// not hold a Cyphal frame.
+    pub fn poll(&mut self) -> io::Result<Poll<MTU>> {
+        match self {
+            Source::Interface(socket) => Ok(match socket.read()? {
+                Some(Event::Received(frame)) | Some(Event::Transmitted(frame)) => {
+                    let (data, len) = frame.payload();
+                    Poll::Frame(
+                        LoggedFrame::from((frame.id(), *data, len))
+                            .with_timestamp(frame.timestamp()),
+                    )
+                }
+                None => Poll::Idle,
+            }),
+            Source::Candump(reader) => loop {
+                match reader.next() {
+                    Some(Ok(record)) => return Ok(Poll::Frame(record.frame)),
+                    Some(Err(candump::ReadError::Parse { .. })) => continue,
+                    Some(Err(candump::ReadError::Io(err))) => return Err(err),
+                    None => return Ok(Poll::End),
+                }
+            },
+            Source::Pcap(reader) => loop {
+                match reader.next() {
+                    Some(Ok(frame)) => return Ok(Poll::Frame(frame)),
+                    Some(Err(pcap::ReadError::Packet { .. })) => continue,
+                    Some(Err(pcap::ReadError::Io(err))) => return Err(err),
+                    Some(Err(err)) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{:?}", err),
+                        ))
+                    }
+                    None => return Ok(Poll::End),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+};
+
+use uavcan::rx::transfer::Transfer;
+use uavcan::session_id::{SessionId, SessionKind, TransferPriority};
+use uavcan::statistics::{RxStatistics, SessionStatistics};
+use uavcan::timestamp::Timestamp;
+
+/// The sessions that are tracked, beyond which new sessions are ignored.
+pub const MAX_SESSIONS: usize = 256;
+
+/// The span of time, in microseconds, over which the rate of a session is
+/// computed, so that the rate of a node that stops transmitting drops to zero.
+const RATE_WINDOW: u64 = 5_000_000;
+
+/// What is known of a session besides its counters.
+struct Activity {
+    kind: SessionKind,
+    priority: TransferPriority,
+    first_transfer: Option<Timestamp>,
+    /// The timestamps of the transfers of the last [RATE_WINDOW].
+    recent_transfers: VecDeque<Timestamp>,
+}
+
+impl Activity {
+    /// The transfers per second over the [RATE_WINDOW] that ends at `now`, or
+    /// since the first transfer of the session when it is more recent.
+    fn rate(&self, now: Timestamp) -> Option<f64> {
+        let window = now
+            .saturating_duration_since(self.first_transfer?)
+            .min(RATE_WINDOW);
+        let start = now.as_micros() - window;
+        let transfers = self
+            .recent_transfers
+            .iter()
+            .filter(|timestamp| timestamp.as_micros() > start)
+            .count();
+
+        (window > 0).then(|| transfers as f64 * 1_000_000.0 / window as f64)
+    }
+}
+
+/// The nodes and sessions seen on the bus.
+#[derive(Default)]
+pub struct Table {
+    pub sessions: SessionStatistics<MAX_SESSIONS>,
+    activities: Vec<Activity>,
+    /// The latest time seen on the bus.
+    now: Option<Timestamp>,
+}
+
+fn node(id: uavcan::session_id::NodeId) -> u8 {
+    u8::from_le_bytes(id.into_bytes())
+}
+
+fn describe(kind: SessionKind) -> (&'static str, u16, u8, Option<u8>) {
+    match kind {
+        SessionKind::Message {
+            source_node_id,
+            subject_id,
+        } => (
+            "MSG",
+            u16::from_le_bytes(subject_id.into_bytes()),
+            node(source_node_id),
+            None,
+        ),
+        SessionKind::Request(request) => (
+            "REQ",
+            u16::from_le_bytes(request.service_id().into_bytes()),
+            node(request.source_node_id()),
+            Some(node(request.destination_node_id())),
+        ),
+        // A response is sent by the node that the request was sent to.
+        SessionKind::Response(request) => (
+            "RSP",
+            u16::from_le_bytes(request.service_id().into_bytes()),
+            node(request.destination_node_id()),
+            Some(node(request.source_node_id())),
+        ),
+    }
+}
+
+impl Table {
+    fn activity(&mut self, kind: SessionKind) -> Option<&mut Activity> {
+        self.activities
+            .iter_mut()
+            .find(|activity| activity.kind == kind)
+    }
+
+    /// Moves the clock of the table to `now`, for the rates of the sessions to
+    /// drop while the bus is quiet.
+    pub fn tick(&mut self, now: Timestamp) {
+        self.now = self.now.max(Some(now));
+    }
+
+    /// Records the priority of the session of the frame with `can_id`,
+    /// received at `timestamp`.
+    pub fn record_frame(&mut self, can_id: u32, timestamp: Option<Timestamp>) {
+        if let Some(timestamp) = timestamp {
+            self.tick(timestamp);
+        }
+
+        let session_id = SessionId::from(can_id);
+        if !session_id.is_valid() {
+            return;
+        }
+
+        let kind = SessionKind::from(session_id);
+        if let Some(activity) = self.activity(kind) {
+            activity.priority = session_id.priority();
+        } else if self.activities.len() < MAX_SESSIONS {
+            self.activities.push(Activity {
+                kind,
+                priority: session_id.priority(),
+                first_transfer: None,
+                recent_transfers: VecDeque::new(),
+            });
+        }
+    }
+
+    pub fn record_transfer<const CAPACITY: usize>(&mut self, transfer: &Transfer<CAPACITY>) {
+        let (activity, timestamp) = match (self.activity(transfer.kind), transfer.timestamp) {
+            (Some(activity), Some(timestamp)) => (activity, timestamp),
+            _ => return,
+        };
+
+        activity.first_transfer = activity.first_transfer.or(Some(timestamp));
+        activity.recent_transfers.push_back(timestamp);
+        while let Some(oldest) = activity.recent_transfers.front() {
+            if timestamp.saturating_duration_since(*oldest) < RATE_WINDOW {
+                break;
+            }
+            activity.recent_transfers.pop_front();
+        }
+    }
+
+    /// The last priority seen in the session of `kind`.
+    pub fn priority(&self, kind: SessionKind) -> TransferPriority {
+        self.activities
+            .iter()
+            .find(|activity| activity.kind == kind)
+            .map_or(TransferPriority::Nominal, |activity| activity.priority)
+    }
+
+    pub fn print(&self, out: &mut impl Write, interface: &RxStatistics) -> io::Result<()> {
+        writeln!(
+            out,
+            "frames {}  transfers {}  errors {} (crc {}, missing {}, toggle {}, overflow {}, timeout {}, malformed {})",
+            interface.frames,
+            interface.transfers,
+            interface.errors(),
+            interface.crc_errors,
+            interface.missing_frames,
+            interface.toggle_errors,
+            interface.overflows,
+            interface.timeouts,
+            interface.malformed_frames
+        )?;
+
+        let mut nodes: Vec<(u8, u64, u64)> = Vec::new();
+        for (kind, statistics) in self.sessions.iter() {
+            let (_, _, source, _) = describe(kind);
+            match nodes.iter_mut().find(|(node, ..)| *node == source) {
+                Some((_, transfers, errors)) => {
+                    *transfers += statistics.transfers;
+                    *errors += statistics.errors();
+                }
+                None => nodes.push((source, statistics.transfers, statistics.errors())),
+            }
+        }
+        nodes.sort_unstable();
+
+        writeln!(out, "\n{:>5} {:>10} {:>8}", "NODE", "TRANSFERS", "ERRORS")?;
+        for (node, transfers, errors) in nodes {
+            writeln!(out, "{:>5} {:>10} {:>8}", node, transfers, errors)?;
+        }
+
+        writeln!(
+            out,
+            "\n{:<4} {:>5} {:>4} {:>4} {:<12} {:>9} {:>10} {:>8}",
+            "KIND", "PORT", "SRC", "DST", "PRIORITY", "RATE", "TRANSFERS", "ERRORS"
+        )?;
+        let mut sessions: Vec<_> = self.sessions.iter().collect();
+        sessions.sort_unstable_by_key(|(kind, _)| describe(*kind));
+        for (kind, statistics) in sessions {
+            let (name, port, source, destination) = describe(kind);
+            let rate = self
+                .activities
+                .iter()
+                .find(|activity| activity.kind == kind)
+                .zip(self.now)
+                .and_then(|(activity, now)| activity.rate(now))
+                .map_or_else(|| "-".to_string(), |rate| format!("{:.1}/s", rate));
+
+            writeln!(
+                out,
+                "{:<4} {:>5} {:>4} {:>4} {:<12} {:>9} {:>10} {:>8}",
+                name,
+                port,
+                source,
+                destination.map_or_else(|| "-".to_string(), |node| node.to_string()),
+                self.priority(kind).to_string(),
+                rate,
+                statistics.transfers,
+                statistics.errors()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use uavcan::session_id::{can_id_for_session_kind, NodeId, SubjectId};
+    use uavcan::tail_byte::TransferId;
+
+    fn message() -> SessionKind {
+        SessionKind::Message {
+            source_node_id: NodeId::try_from(42).unwrap(),
+            subject_id: SubjectId::try_from(100).unwrap(),
+        }
+    }
+
+    /// Records a transfer of the session of [message] every 100 ms, for
+    /// `seconds` seconds.
+    fn record_transfers(table: &mut Table, seconds: u64) {
+        let can_id = can_id_for_session_kind(message(), TransferPriority::Nominal);
+
+        for n in 0..seconds * 10 {
+            let timestamp = Timestamp::from_micros(n * 100_000);
+            table.record_frame(can_id, Some(timestamp));
+            table.record_transfer(&Transfer::<8>::new(
+                heapless::Vec::new(),
+                message(),
+                TransferId::new(),
+                Some(timestamp),
+            ));
+        }
+    }
+
+    fn rate(table: &Table) -> Option<f64> {
+        table.activities[0].rate(table.now?)
+    }
+
+    #[test]
+    fn the_rate_of_a_session_is_its_recent_transfers_per_second() {
+        let mut table = Table::default();
+
+        record_transfers(&mut table, 20);
+
+        assert_eq!(rate(&table), Some(10.0));
+    }
+
+    #[test]
+    fn the_rate_of_a_session_drops_once_it_stops_transmitting() {
+        let mut table = Table::default();
+        record_transfers(&mut table, 20);
+
+        table.tick(Timestamp::from_micros(22_400_000));
+        assert_eq!(rate(&table), Some(5.0));
+
+        table.tick(Timestamp::from_micros(30_000_000));
+        assert_eq!(rate(&table), Some(0.0));
+    }
+}
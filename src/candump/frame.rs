@@ -0,0 +1,68 @@
+use crate::{timestamp::Timestamp, CanFrame};
+
+/// A frame read from a candump log or a pcap capture, carrying the time at
+/// which it was recorded.
+///
+/// `MTU` is either [crate::CLASSIC_MTU] for classic CAN or
+/// [crate::EXTENDED_MTU] for CAN FD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedFrame<const MTU: usize> {
+    id: u32,
+    data: [u8; MTU],
+    len: usize,
+    timestamp: Option<Timestamp>,
+}
+
+impl<const MTU: usize> LoggedFrame<MTU> {
+    pub fn with_timestamp(mut self, timestamp: Option<Timestamp>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+/// A length beyond the MTU is clamped to the MTU, as the frame cannot hold
+/// more data than `data`.
+impl<const MTU: usize> From<(u32, [u8; MTU], usize)> for LoggedFrame<MTU> {
+    fn from((id, data, len): (u32, [u8; MTU], usize)) -> Self {
+        Self {
+            id,
+            data,
+            len: len.min(MTU),
+            timestamp: None,
+        }
+    }
+}
+
+impl<const MTU: usize> CanFrame<MTU> for LoggedFrame<MTU> {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn payload(&self) -> (&[u8; MTU], usize) {
+        (&self.data, self.len)
+    }
+
+    /// The time at which the frame was recorded, in microseconds since the
+    /// Unix epoch.
+    fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CLASSIC_MTU;
+
+    #[test]
+    fn a_length_beyond_the_mtu_is_clamped_to_the_mtu() {
+        let frame = LoggedFrame::from((0, [1; CLASSIC_MTU], CLASSIC_MTU + 1));
+
+        assert_eq!(frame.data(), [1; CLASSIC_MTU]);
+        assert_eq!(frame.payload().1, CLASSIC_MTU);
+    }
+}
@@ -0,0 +1,26 @@
+//! Reading and writing the logs recorded by `candump -l`, enabled by the
+//! `std` feature.
+//!
+//! Each line of a log holds a frame along with the time at which it was
+//! received and the name of its interface:
+//!
+//! ```text
+//! (1700000000.000100) can0 107D552A#00000000000000E0
+//! (1700000000.000200) can1 107D552A##100000000000000E0
+//! ```
+//!
+//! where `##` introduces a CAN FD frame, followed by its flags. The frames of
+//! a log can be fed to an [RxProducer] to rebuild the recorded transfers
+//! offline.
+//!
+//! [RxProducer]: crate::rx::rx_network::RxProducer
+
+pub mod frame;
+pub mod reader;
+pub mod record;
+pub mod writer;
+
+pub use frame::LoggedFrame;
+pub use reader::{CandumpReader, ReadError};
+pub use record::{ParseError, Record};
+pub use writer::CandumpWriter;
@@ -0,0 +1,129 @@
+use core::str::FromStr;
+use std::{
+    io::{self, BufRead},
+    string::String,
+};
+
+use super::record::{ParseError, Record};
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// The line with the 1-based number `line` could not be parsed.
+    Parse {
+        line: usize,
+        error: ParseError,
+    },
+}
+
+/// Reads the records of a candump log, one line at a time.
+///
+/// Blank lines are skipped, and reading goes on after a line that could not
+/// be parsed, so that the frames that are not used by Cyphal can be filtered
+/// out of a capture of a shared bus.
+pub struct CandumpReader<R: BufRead, const MTU: usize> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead, const MTU: usize> CandumpReader<R, MTU> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead, const MTU: usize> Iterator for CandumpReader<R, MTU> {
+    type Item = Result<Record<MTU>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line = String::new();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+                Err(err) => return Some(Err(ReadError::Io(err))),
+            }
+
+            if !self.line.trim().is_empty() {
+                return Some(
+                    Record::from_str(&self.line).map_err(|error| ReadError::Parse {
+                        line: self.line_number,
+                        error,
+                    }),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::{NodeId, SessionKind, SubjectId};
+    use crate::timestamp::Timestamp;
+    use crate::CLASSIC_MTU;
+    use core::convert::TryFrom;
+
+    /// Three heartbeats of node 42, captured on a bus that also carries a
+    /// frame with a standard ID.
+    const CAPTURE: &str = "\
+(1700000000.000100) can0 107D552A#00000000000000E0
+(1700000000.500000) can0 123#DEADBEEF
+
+(1700000001.000100) can0 107D552A#01000000000000E1
+(1700000002.000100) can0 107D552A#02000000000000E2
+";
+
+    #[test]
+    fn a_captured_log_is_replayed_through_an_rx_producer() {
+        let mut rx_network = RxNetwork::<_, 8, 64, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = rx_network.split();
+
+        let records: std::vec::Vec<_> = CandumpReader::<_, CLASSIC_MTU>::new(CAPTURE.as_bytes())
+            .filter_map(Result::ok)
+            .collect();
+        for record in records {
+            producer.receive(record.frame).unwrap();
+        }
+
+        let transfers: std::vec::Vec<_> = consumer.collect();
+        assert_eq!(transfers.len(), 3);
+        assert_eq!(
+            transfers[0].kind,
+            SessionKind::Message {
+                source_node_id: NodeId::try_from(42).unwrap(),
+                subject_id: SubjectId::try_from(7509).unwrap(),
+            }
+        );
+        assert_eq!(transfers[1].payload[..1], [1]);
+        assert_eq!(
+            transfers[2].timestamp,
+            Some(Timestamp::from_micros(1_700_000_002_000_100))
+        );
+    }
+
+    #[test]
+    fn a_line_that_cannot_be_parsed_is_reported_with_its_number() {
+        let mut reader = CandumpReader::<_, CLASSIC_MTU>::new(CAPTURE.as_bytes());
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ReadError::Parse {
+                line: 2,
+                error: ParseError::StandardFrame
+            }))
+        ));
+        assert!(reader.next().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+use core::{fmt, str::FromStr};
+use std::string::{String, ToString};
+
+use super::frame::LoggedFrame;
+use crate::{timestamp::Timestamp, CanFrame};
+
+/// The largest ID of an extended frame, which is the only kind of frame
+/// used by Cyphal.
+const MAX_EXTENDED_ID: u32 = 0x1FFF_FFFF;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingTimestamp,
+    InvalidTimestamp,
+    MissingInterface,
+    MissingFrame,
+    InvalidId,
+    /// The frame has an 11-bit ID, which Cyphal does not use.
+    StandardFrame,
+    RemoteFrame,
+    InvalidFlags,
+    InvalidData,
+    /// The frame carries more bytes than the MTU, as with a CAN FD frame read
+    /// as a classic one.
+    TooLong(usize),
+}
+
+/// A line of a candump log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<const MTU: usize> {
+    pub interface: String,
+    pub frame: LoggedFrame<MTU>,
+    /// The flags of a CAN FD frame, or `None` for a classic frame.
+    pub fd_flags: Option<u8>,
+}
+
+impl<const MTU: usize> Record<MTU> {
+    /// Records `frame` as received on `interface`, as a CAN FD frame when
+    /// `MTU` exceeds the one of classic CAN.
+    pub fn new<Frame: CanFrame<MTU>>(interface: &str, frame: &Frame) -> Self {
+        let (data, len) = frame.payload();
+
+        Self {
+            interface: interface.to_string(),
+            frame: LoggedFrame::from((frame.id(), *data, len)).with_timestamp(frame.timestamp()),
+            fd_flags: (MTU > crate::CLASSIC_MTU).then_some(0),
+        }
+    }
+}
+
+fn parse_timestamp(text: &str) -> Result<Timestamp, ParseError> {
+    let (seconds, fraction) = text.split_once('.').ok_or(ParseError::InvalidTimestamp)?;
+    (fraction.len() <= 6 && fraction.bytes().all(|byte| byte.is_ascii_digit()))
+        .then_some(())
+        .ok_or(ParseError::InvalidTimestamp)?;
+
+    let seconds = u64::from_str(seconds).map_err(|_| ParseError::InvalidTimestamp)?;
+    let microseconds = fraction
+        .bytes()
+        .chain(core::iter::repeat(b'0'))
+        .take(6)
+        .fold(0, |micros, digit| micros * 10 + u64::from(digit - b'0'));
+
+    seconds
+        .checked_mul(1_000_000)
+        .and_then(|micros| micros.checked_add(microseconds))
+        .map(Timestamp::from_micros)
+        .ok_or(ParseError::InvalidTimestamp)
+}
+
+fn parse_id(text: &str) -> Result<u32, ParseError> {
+    match text.len() {
+        3 => Err(ParseError::StandardFrame),
+        8 => u32::from_str_radix(text, 16)
+            .ok()
+            .filter(|id| *id <= MAX_EXTENDED_ID)
+            .ok_or(ParseError::InvalidId),
+        _ => Err(ParseError::InvalidId),
+    }
+}
+
+fn parse_data<const MTU: usize>(text: &str) -> Result<([u8; MTU], usize), ParseError> {
+    (text.len().is_multiple_of(2) && text.is_ascii())
+        .then_some(())
+        .ok_or(ParseError::InvalidData)?;
+
+    let len = text.len() / 2;
+    let mut data = [0; MTU];
+    for (index, byte) in data
+        .get_mut(..len)
+        .ok_or(ParseError::TooLong(len))?
+        .iter_mut()
+        .enumerate()
+    {
+        *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16)
+            .map_err(|_| ParseError::InvalidData)?;
+    }
+
+    Ok((data, len))
+}
+
+impl<const MTU: usize> FromStr for Record<MTU> {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (timestamp, rest) = line
+            .trim()
+            .strip_prefix('(')
+            .and_then(|line| line.split_once(')'))
+            .ok_or(ParseError::MissingTimestamp)?;
+        let timestamp = parse_timestamp(timestamp)?;
+
+        let mut fields = rest.split_whitespace();
+        let interface = fields.next().ok_or(ParseError::MissingInterface)?;
+        let (id, data) = fields
+            .next()
+            .and_then(|frame| frame.split_once('#'))
+            .ok_or(ParseError::MissingFrame)?;
+        let id = parse_id(id)?;
+
+        let (fd_flags, data) = match data.strip_prefix('#') {
+            Some(data) => {
+                let flags = data.get(..1).ok_or(ParseError::InvalidFlags)?;
+                let flags = u8::from_str_radix(flags, 16).map_err(|_| ParseError::InvalidFlags)?;
+
+                (Some(flags), &data[1..])
+            }
+            None if data.starts_with('R') => return Err(ParseError::RemoteFrame),
+            None => (None, data),
+        };
+        let (data, len) = parse_data::<MTU>(data)?;
+
+        Ok(Self {
+            interface: interface.to_string(),
+            frame: LoggedFrame::from((id, data, len)).with_timestamp(Some(timestamp)),
+            fd_flags,
+        })
+    }
+}
+
+impl<const MTU: usize> fmt::Display for Record<MTU> {
+    /// Formats the record as a line of a log, without the line break.
+    ///
+    /// A frame without a timestamp is written as recorded at the epoch.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let micros = self.frame.timestamp().unwrap_or_default().as_micros();
+
+        write!(
+            f,
+            "({}.{:06}) {} {:08X}#",
+            micros / 1_000_000,
+            micros % 1_000_000,
+            self.interface,
+            self.frame.id()
+        )?;
+        if let Some(flags) = self.fd_flags {
+            write!(f, "#{:X}", flags)?;
+        }
+        self.frame
+            .data()
+            .iter()
+            .try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_formatted_record_is_parsed_back_unchanged(micros in 0..u64::MAX / 2, id in 0..=MAX_EXTENDED_ID, data in vec(proptest::num::u8::ANY, 0..=EXTENDED_MTU), flags in proptest::option::of(0..16u8)) {
+            let mut payload = [0; EXTENDED_MTU];
+            payload[..data.len()].copy_from_slice(&data);
+            let record = Record::<EXTENDED_MTU> {
+                interface: "can0".to_string(),
+                frame: LoggedFrame::from((id, payload, data.len())).with_timestamp(Some(Timestamp::from_micros(micros))),
+                fd_flags: flags,
+            };
+
+            prop_assert_eq!(Record::from_str(&record.to_string()), Ok(record));
+        }
+    }
+
+    #[test]
+    fn a_classic_line_is_parsed_into_a_timestamped_frame() {
+        let record =
+            Record::<CLASSIC_MTU>::from_str("(1700000000.000100) can0 107D552A#0102E0").unwrap();
+
+        assert_eq!(record.interface, "can0");
+        assert_eq!(record.fd_flags, None);
+        assert_eq!(record.frame.id(), 0x107D552A);
+        assert_eq!(record.frame.data(), [0x01, 0x02, 0xE0]);
+        assert_eq!(
+            record.frame.timestamp(),
+            Some(Timestamp::from_micros(1_700_000_000_000_100))
+        );
+    }
+
+    #[test]
+    fn an_fd_line_is_parsed_with_its_flags() {
+        let record =
+            Record::<EXTENDED_MTU>::from_str("(1.5) vcan1 107D552A##3000102030405060708090A0B0CE0")
+                .unwrap();
+
+        assert_eq!(record.fd_flags, Some(3));
+        assert_eq!(record.frame.data().len(), 14);
+        assert_eq!(
+            record.frame.timestamp(),
+            Some(Timestamp::from_micros(1_500_000))
+        );
+    }
+
+    #[test]
+    fn lines_that_do_not_hold_a_cyphal_frame_are_errors() {
+        assert_eq!(
+            Record::<CLASSIC_MTU>::from_str("can0 107D552A#00"),
+            Err(ParseError::MissingTimestamp)
+        );
+        assert_eq!(
+            Record::<CLASSIC_MTU>::from_str("(0.0) can0 123#00"),
+            Err(ParseError::StandardFrame)
+        );
+        assert_eq!(
+            Record::<CLASSIC_MTU>::from_str("(0.0) can0 107D552A#R"),
+            Err(ParseError::RemoteFrame)
+        );
+        assert_eq!(
+            Record::<CLASSIC_MTU>::from_str("(0.0) can0 107D552A##1000102030405060708"),
+            Err(ParseError::TooLong(9))
+        );
+    }
+}
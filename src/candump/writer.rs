@@ -0,0 +1,83 @@
+use std::io::{self, Write};
+
+use super::record::Record;
+use crate::CanFrame;
+
+/// Writes frames to a candump log, one line each.
+pub struct CandumpWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CandumpWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn write_record<const MTU: usize>(&mut self, record: &Record<MTU>) -> io::Result<()> {
+        writeln!(self.writer, "{}", record)
+    }
+
+    /// Records `frame` as received on `interface` at the time of its
+    /// timestamp.
+    pub fn write<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        interface: &str,
+        frame: &Frame,
+    ) -> io::Result<()> {
+        self.write_record(&Record::new(interface, frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candump::{CandumpReader, LoggedFrame};
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{logged_frames_of, round_trip, FrameLog};
+    use crate::timestamp::Timestamp;
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    struct Candump;
+
+    impl FrameLog for Candump {
+        fn write<const MTU: usize>(frames: &[LoggedFrame<MTU>]) -> Vec<u8> {
+            let mut writer = CandumpWriter::new(Vec::new());
+            for frame in frames {
+                writer.write("can0", frame).unwrap();
+            }
+
+            writer.into_inner()
+        }
+
+        fn read<const MTU: usize>(log: &[u8]) -> Vec<LoggedFrame<MTU>> {
+            CandumpReader::<_, MTU>::new(log)
+                .map(|record| record.unwrap().frame)
+                .collect()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn classic_frames_written_to_a_log_are_read_back_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let frames = logged_frames_of::<CLASSIC_MTU>(&payload, kind, Timestamp::default());
+
+            prop_assert_eq!(round_trip::<Candump, _>(&frames), frames);
+        }
+
+        #[test]
+        fn fd_frames_written_to_a_log_are_read_back_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..300)) {
+            let frames = logged_frames_of::<EXTENDED_MTU>(&payload, kind, Timestamp::default());
+
+            prop_assert_eq!(round_trip::<Candump, _>(&frames), frames);
+        }
+    }
+}
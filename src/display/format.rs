@@ -0,0 +1,175 @@
+use core::fmt;
+
+use crate::{
+    session_id::{SessionId, TransferPriority},
+    tail_byte::{PayloadKind, TailByte},
+    CanFrame,
+};
+
+impl fmt::Display for TransferPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// The bit of the CAN ID of a message published by an anonymous node.
+pub(super) const ANONYMOUS_BIT: u32 = 1 << 24;
+
+/// The reserved bits of the CAN ID of a message, and their value in a valid
+/// CAN ID.
+pub(super) const MESSAGE_RESERVED_BITS: (u32, u32) =
+    (1 << 7 | 1 << 21 | 1 << 22 | 1 << 23, 1 << 21 | 1 << 22);
+
+/// The reserved bits of the CAN ID of a service, and their value in a valid
+/// CAN ID.
+pub(super) const SERVICE_RESERVED_BITS: (u32, u32) = (1 << 23, 0);
+
+/// The session is followed by `anon` for the messages of anonymous nodes,
+/// and by the reserved bits of the CAN ID, as `rsv=0x...`, when they do not
+/// have their usual value.
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (can_id, (reserved, usual)) = match self {
+            SessionId::Message(message) => {
+                write!(
+                    f,
+                    "MSG prio={} subj={} src={}",
+                    self.priority(),
+                    u16::from_le_bytes(message.subject_id().into_bytes()),
+                    u8::from_le_bytes(message.source_node_id().into_bytes())
+                )?;
+
+                let can_id = u32::from(*message);
+                if can_id & ANONYMOUS_BIT != 0 {
+                    f.write_str(" anon")?;
+                }
+
+                (can_id, MESSAGE_RESERVED_BITS)
+            }
+            SessionId::Rpc(service) => {
+                write!(
+                    f,
+                    "{} prio={} svc={} src={} dst={}",
+                    if service.is_request() { "REQ" } else { "RSP" },
+                    self.priority(),
+                    u16::from_le_bytes(service.service_id().into_bytes()),
+                    u8::from_le_bytes(service.source_node_id().into_bytes()),
+                    u8::from_le_bytes(service.destination_node_id().into_bytes())
+                )?;
+
+                (u32::from(*service), SERVICE_RESERVED_BITS)
+            }
+        };
+
+        match can_id & reserved {
+            bits if bits == usual => Ok(()),
+            bits => write!(f, " rsv={:#X}", bits),
+        }
+    }
+}
+
+impl fmt::Display for TailByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tid={}",
+            u8::from_le_bytes(self.get_transfer_id().into_bytes())
+        )?;
+        match self.payload_kind() {
+            PayloadKind::SingleFrame => f.write_str(" SOT EOT")?,
+            PayloadKind::StartOfMultiFrame => f.write_str(" SOT")?,
+            PayloadKind::EndOfMultiFrame => f.write_str(" EOT")?,
+            PayloadKind::MiddleOfMultiFrame => {}
+        }
+        write!(f, " T={}", (self.into_u8() >> 5) & 1)
+    }
+}
+
+/// Displays the session and the tail byte of a frame, as returned by
+/// [FrameDisplay::new].
+///
+/// A frame without data has no tail byte, so only its session is displayed.
+pub struct FrameDisplay<'a, Frame: CanFrame<MTU>, const MTU: usize> {
+    frame: &'a Frame,
+}
+
+impl<'a, Frame: CanFrame<MTU>, const MTU: usize> FrameDisplay<'a, Frame, MTU> {
+    pub fn new(frame: &'a Frame) -> Self {
+        Self { frame }
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> fmt::Display for FrameDisplay<'_, Frame, MTU> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", SessionId::from(self.frame.id()))?;
+
+        match self.frame.payload() {
+            (_, 0) => Ok(()),
+            (data, len) => write!(f, " {}", TailByte::from_u8(data[len - 1])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tail_byte::TransferId;
+    use crate::tests::ClassicFrame;
+    use crate::CLASSIC_MTU;
+    use core::convert::TryFrom;
+
+    extern crate std;
+    use std::string::ToString;
+
+    #[test]
+    fn a_heartbeat_frame_is_displayed_with_its_session_and_tail_byte() {
+        let mut data = [0; CLASSIC_MTU];
+        data[7] = TailByte::single_frame(TransferId::try_from(3).unwrap()).into_u8();
+        let frame = ClassicFrame::from((0x0C7D552A, data, 8));
+
+        assert_eq!(
+            FrameDisplay::new(&frame).to_string(),
+            "MSG prio=High subj=7509 src=42 tid=3 SOT EOT T=1"
+        );
+    }
+
+    #[test]
+    fn a_service_session_is_displayed_with_both_of_its_nodes() {
+        // uavcan.node.GetInfo requested by node 42 from node 10.
+        let session_id = SessionId::from(0x136B852Au32);
+
+        assert_eq!(
+            session_id.to_string(),
+            "REQ prio=Nominal svc=430 src=42 dst=10"
+        );
+    }
+
+    #[test]
+    fn an_anonymous_message_is_marked_as_such() {
+        // A heartbeat of an anonymous node, with a pseudo-ID of 42.
+        let session_id = SessionId::from(0x117D552Au32);
+
+        assert_eq!(
+            session_id.to_string(),
+            "MSG prio=Nominal subj=7509 src=42 anon"
+        );
+    }
+
+    #[test]
+    fn reserved_bits_without_their_usual_value_are_displayed() {
+        let session_id = SessionId::from(0x107D552Au32 | 1 << 23);
+
+        assert_eq!(
+            session_id.to_string(),
+            "MSG prio=Nominal subj=7509 src=42 rsv=0xE00000"
+        );
+    }
+
+    #[test]
+    fn a_middle_frame_has_neither_a_start_nor_an_end_of_transfer() {
+        let mut tail_byte = TailByte::start_of_multi_frame(TransferId::try_from(31).unwrap());
+        tail_byte.advance();
+
+        assert_eq!(tail_byte.to_string(), "tid=31 T=0");
+    }
+}
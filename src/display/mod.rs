@@ -0,0 +1,19 @@
+//! Human-readable forms of CAN IDs and tail bytes, for reading bus dumps.
+//!
+//! A frame is written as its session followed by its tail byte:
+//!
+//! ```text
+//! MSG prio=High subj=7509 src=42 tid=3 SOT EOT T=1
+//! REQ prio=Nominal svc=430 src=42 dst=10 tid=0 SOT T=1
+//! ```
+//!
+//! where `SOT` and `EOT` mark the start and the end of a transfer, and
+//! `anon` follows the session of a message of an anonymous node. Only
+//! `core::fmt` is used, so that frames can be printed over RTT or a serial
+//! console, and the same text is parsed back by [parse_frame].
+
+pub mod format;
+pub mod parse;
+
+pub use format::FrameDisplay;
+pub use parse::{parse_frame, ParseError};
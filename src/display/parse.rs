@@ -0,0 +1,335 @@
+use core::{convert::TryFrom, str::FromStr};
+
+use crate::{
+    session_id::{
+        message::MessageSessionId, service::ServiceSessionId, NodeId, ServiceId, SessionId,
+        SubjectId, TransferPriority,
+    },
+    tail_byte::{TailByte, TransferId},
+};
+
+use super::format::{ANONYMOUS_BIT, MESSAGE_RESERVED_BITS, SERVICE_RESERVED_BITS};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The session starts with neither `MSG`, `REQ` nor `RSP`.
+    MissingKind,
+    /// A token is not part of the form being parsed.
+    UnknownField,
+    MissingField(&'static str),
+    /// The value of the field is not a number, or is out of its range.
+    InvalidValue(&'static str),
+}
+
+/// The tokens of a displayed session or tail byte, keyed by name.
+#[derive(Default)]
+struct Fields<'t> {
+    kind: Option<&'t str>,
+    priority: Option<&'t str>,
+    subject_id: Option<&'t str>,
+    service_id: Option<&'t str>,
+    source: Option<&'t str>,
+    destination: Option<&'t str>,
+    reserved: Option<&'t str>,
+    is_anonymous: bool,
+    transfer_id: Option<&'t str>,
+    toggle: Option<&'t str>,
+    is_start_of_transfer: bool,
+    is_end_of_transfer: bool,
+}
+
+impl<'t> Fields<'t> {
+    fn parse(text: &'t str) -> Result<Self, ParseError> {
+        let mut fields = Fields::default();
+
+        for token in text.split_whitespace() {
+            match token {
+                "MSG" | "REQ" | "RSP" => fields.kind = Some(token),
+                "anon" => fields.is_anonymous = true,
+                "SOT" => fields.is_start_of_transfer = true,
+                "EOT" => fields.is_end_of_transfer = true,
+                _ => {
+                    let (key, value) = token.split_once('=').ok_or(ParseError::UnknownField)?;
+                    let field = match key {
+                        "prio" => &mut fields.priority,
+                        "subj" => &mut fields.subject_id,
+                        "svc" => &mut fields.service_id,
+                        "src" => &mut fields.source,
+                        "dst" => &mut fields.destination,
+                        "rsv" => &mut fields.reserved,
+                        "tid" => &mut fields.transfer_id,
+                        "T" => &mut fields.toggle,
+                        _ => return Err(ParseError::UnknownField),
+                    };
+                    *field = Some(value);
+                }
+            }
+        }
+
+        Ok(fields)
+    }
+
+    fn has_session(&self) -> bool {
+        self.kind.is_some()
+            || self.priority.is_some()
+            || self.subject_id.is_some()
+            || self.service_id.is_some()
+            || self.source.is_some()
+            || self.destination.is_some()
+            || self.reserved.is_some()
+            || self.is_anonymous
+    }
+
+    fn has_tail_byte(&self) -> bool {
+        self.transfer_id.is_some()
+            || self.toggle.is_some()
+            || self.is_start_of_transfer
+            || self.is_end_of_transfer
+    }
+
+    fn session_id(&self) -> Result<SessionId, ParseError> {
+        let priority =
+            TransferPriority::from_str(self.priority.ok_or(ParseError::MissingField("prio"))?)?;
+        let source = node_id(self.source, "src")?;
+
+        match self.kind.ok_or(ParseError::MissingKind)? {
+            "MSG" => {
+                let subject_id = SubjectId::try_from(number::<u16>(self.subject_id, "subj")?)
+                    .map_err(|_| ParseError::InvalidValue("subj"))?;
+
+                let can_id = u32::from(MessageSessionId::from_base_parts(
+                    source, subject_id, priority,
+                ));
+                let can_id = if self.is_anonymous {
+                    can_id | ANONYMOUS_BIT
+                } else {
+                    can_id
+                };
+
+                Ok(SessionId::from(
+                    self.with_reserved_bits(can_id, MESSAGE_RESERVED_BITS.0)?,
+                ))
+            }
+            _ if self.is_anonymous => Err(ParseError::UnknownField),
+            kind => {
+                let service_id = ServiceId::try_from(number::<u16>(self.service_id, "svc")?)
+                    .map_err(|_| ParseError::InvalidValue("svc"))?;
+                let destination = node_id(self.destination, "dst")?;
+                let service = if kind == "REQ" {
+                    ServiceSessionId::request_from_base_parts
+                } else {
+                    ServiceSessionId::response_from_base_parts
+                };
+
+                let can_id = u32::from(service(source, destination, service_id, priority));
+
+                Ok(SessionId::from(
+                    self.with_reserved_bits(can_id, SERVICE_RESERVED_BITS.0)?,
+                ))
+            }
+        }
+    }
+
+    /// Replaces the `reserved` bits of `can_id` by those of the `rsv` field,
+    /// if any.
+    fn with_reserved_bits(&self, can_id: u32, reserved: u32) -> Result<u32, ParseError> {
+        let bits = match self.reserved {
+            Some(value) => value
+                .strip_prefix("0x")
+                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                .filter(|bits| bits & !reserved == 0)
+                .ok_or(ParseError::InvalidValue("rsv"))?,
+            None => return Ok(can_id),
+        };
+
+        Ok(can_id & !reserved | bits)
+    }
+
+    fn tail_byte(&self) -> Result<TailByte, ParseError> {
+        let transfer_id = number::<u8>(self.transfer_id, "tid")?;
+        TransferId::try_from(transfer_id).map_err(|_| ParseError::InvalidValue("tid"))?;
+        let toggle = match self.toggle.ok_or(ParseError::MissingField("T"))? {
+            "0" => 0,
+            "1" => 1,
+            _ => return Err(ParseError::InvalidValue("T")),
+        };
+
+        Ok(TailByte::from_u8(
+            transfer_id
+                | toggle << 5
+                | u8::from(self.is_end_of_transfer) << 6
+                | u8::from(self.is_start_of_transfer) << 7,
+        ))
+    }
+}
+
+fn number<T: FromStr>(value: Option<&str>, name: &'static str) -> Result<T, ParseError> {
+    value
+        .ok_or(ParseError::MissingField(name))?
+        .parse()
+        .map_err(|_| ParseError::InvalidValue(name))
+}
+
+fn node_id(value: Option<&str>, name: &'static str) -> Result<NodeId, ParseError> {
+    NodeId::try_from(number::<u8>(value, name)?).map_err(|_| ParseError::InvalidValue(name))
+}
+
+impl FromStr for TransferPriority {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "Exceptional" => Ok(TransferPriority::Exceptional),
+            "Immediate" => Ok(TransferPriority::Immediate),
+            "Fast" => Ok(TransferPriority::Fast),
+            "High" => Ok(TransferPriority::High),
+            "Nominal" => Ok(TransferPriority::Nominal),
+            "Low" => Ok(TransferPriority::Low),
+            "Slow" => Ok(TransferPriority::Slow),
+            "Optional" => Ok(TransferPriority::Optional),
+            _ => Err(ParseError::InvalidValue("prio")),
+        }
+    }
+}
+
+impl FromStr for SessionId {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let fields = Fields::parse(text)?;
+        if fields.has_tail_byte() {
+            return Err(ParseError::UnknownField);
+        }
+
+        fields.session_id()
+    }
+}
+
+impl FromStr for TailByte {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let fields = Fields::parse(text)?;
+        if fields.has_session() {
+            return Err(ParseError::UnknownField);
+        }
+
+        fields.tail_byte()
+    }
+}
+
+/// Parses a frame displayed by a [FrameDisplay] back into its CAN ID and its
+/// tail byte, which is `None` for a frame without data.
+///
+/// [FrameDisplay]: super::FrameDisplay
+pub fn parse_frame(text: &str) -> Result<(u32, Option<TailByte>), ParseError> {
+    let fields = Fields::parse(text)?;
+    let can_id = match fields.session_id()? {
+        SessionId::Message(message) => u32::from(message),
+        SessionId::Rpc(service) => u32::from(service),
+    };
+    let tail_byte = if fields.has_tail_byte() {
+        Some(fields.tail_byte()?)
+    } else {
+        None
+    };
+
+    Ok((can_id, tail_byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::FrameDisplay;
+    use crate::session_id::{
+        message::strategy::{anonymous_message_session_id_as_u32, message_session_id_as_u32},
+        service::strategy::{request_session_id_as_u32, response_session_id_as_u32},
+    };
+    use crate::tests::ClassicFrame;
+    use crate::CLASSIC_MTU;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, string::ToString};
+
+    proptest! {
+        #[test]
+        fn a_displayed_frame_is_parsed_back_into_its_can_id_and_tail_byte(
+            can_id in prop_oneof![message_session_id_as_u32(), anonymous_message_session_id_as_u32(), request_session_id_as_u32(), response_session_id_as_u32()],
+            reserved_bits in 0u32..16,
+            tail_byte in proptest::num::u8::ANY,
+        ) {
+            // Flips any of the reserved bits, among bit 7, bit 21, bit 22 and
+            // bit 23 for a message and bit 23 only for a service.
+            let reserved = match SessionId::from(can_id) {
+                SessionId::Message(_) => MESSAGE_RESERVED_BITS.0,
+                SessionId::Rpc(_) => SERVICE_RESERVED_BITS.0,
+            };
+            let flipped = (reserved_bits & 1) << 7 | (reserved_bits >> 1) << 21;
+            let can_id = can_id ^ (flipped & reserved);
+
+            let frame = ClassicFrame::from((can_id, [tail_byte; CLASSIC_MTU], 1));
+
+            prop_assert_eq!(
+                parse_frame(&FrameDisplay::new(&frame).to_string()),
+                Ok((can_id, Some(TailByte::from_u8(tail_byte))))
+            );
+        }
+    }
+
+    #[test]
+    fn a_frame_without_data_is_parsed_without_a_tail_byte() {
+        assert_eq!(
+            parse_frame("MSG prio=High subj=7509 src=42"),
+            Ok((0x0C7D552A, None))
+        );
+    }
+
+    #[test]
+    fn an_anonymous_message_is_parsed_back_as_such() {
+        assert_eq!(
+            parse_frame("MSG prio=Nominal subj=7509 src=42 anon"),
+            Ok((0x117D552A, None))
+        );
+        assert_eq!(
+            parse_frame("REQ prio=Nominal svc=430 src=42 dst=10 anon"),
+            Err(ParseError::UnknownField)
+        );
+    }
+
+    #[test]
+    fn a_tail_byte_is_parsed_on_its_own() {
+        assert_eq!(
+            TailByte::from_str("tid=3 SOT EOT T=1"),
+            Ok(TailByte::single_frame(TransferId::try_from(3).unwrap()))
+        );
+    }
+
+    #[test]
+    fn text_that_is_not_a_displayed_frame_is_an_error() {
+        assert_eq!(
+            parse_frame("prio=High subj=7509 src=42"),
+            Err(ParseError::MissingKind)
+        );
+        assert_eq!(
+            parse_frame("MSG prio=High subj=8192 src=42"),
+            Err(ParseError::InvalidValue("subj"))
+        );
+        assert_eq!(
+            parse_frame("REQ prio=High svc=430 src=42"),
+            Err(ParseError::MissingField("dst"))
+        );
+        assert_eq!(
+            parse_frame("MSG prio=High subj=7509 src=42 len=8"),
+            Err(ParseError::UnknownField)
+        );
+        assert_eq!(
+            parse_frame("MSG prio=High subj=7509 src=42 rsv=0x1"),
+            Err(ParseError::InvalidValue("rsv"))
+        );
+        assert_eq!(
+            SessionId::from_str("MSG prio=High subj=7509 src=42 tid=3 T=1"),
+            Err(ParseError::UnknownField)
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use super::{
+    bit_writer::standard_bit_length_for,
+    error::DeserializationError,
+    float16::f16_bits_to_f32,
+    serialize::{DataType, Deserialize},
+};
+
+/// Reads values from a byte buffer following the bit-level, little-endian
+/// layout mandated by DSDL.
+///
+/// Reading past the end of the buffer is not an error. As required by the
+/// implicit zero extension rule of DSDL, the missing bits are read as zeros.
+pub struct BitReader<'a> {
+    buffer: &'a [u8],
+    bit_offset: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self {
+            buffer,
+            bit_offset: 0,
+        }
+    }
+
+    pub fn bit_offset(&self) -> usize {
+        self.bit_offset
+    }
+
+    /// Returns the amount of bits that can be read before reaching the end of
+    /// the buffer.
+    pub fn remaining_bits(&self) -> usize {
+        (self.buffer.len() * 8).saturating_sub(self.bit_offset)
+    }
+
+    pub fn read_bits(&mut self, bits: u8) -> u64 {
+        debug_assert!(bits <= 64);
+
+        let mut value = 0u64;
+        let mut read_bits = 0usize;
+        while read_bits < bits as usize {
+            let byte_index = self.bit_offset / 8;
+            let bit_index = self.bit_offset % 8;
+            let chunk_bits = (8 - bit_index).min(bits as usize - read_bits);
+
+            let byte = self.buffer.get(byte_index).copied().unwrap_or(0);
+            let chunk = ((byte >> bit_index) as u64) & ((1 << chunk_bits) - 1);
+
+            value |= chunk << read_bits;
+            read_bits += chunk_bits;
+            self.bit_offset += chunk_bits;
+        }
+
+        value
+    }
+
+    pub fn read_bool(&mut self) -> bool {
+        self.read_bits(1) != 0
+    }
+
+    /// Reads a two's complement integer of `bits` bits, extending its sign.
+    pub fn read_signed(&mut self, bits: u8) -> i64 {
+        let value = self.read_bits(bits);
+
+        if bits == 0 || bits >= 64 {
+            value as i64
+        } else {
+            let shift = 64 - bits as u32;
+            ((value << shift) as i64) >> shift
+        }
+    }
+
+    pub fn read_f16(&mut self) -> f32 {
+        f16_bits_to_f32(self.read_bits(16) as u16)
+    }
+
+    pub fn read_f32(&mut self) -> f32 {
+        f32::from_bits(self.read_bits(32) as u32)
+    }
+
+    pub fn read_f64(&mut self) -> f64 {
+        f64::from_bits(self.read_bits(64))
+    }
+
+    pub fn read_bytes(&mut self, bytes: &mut [u8]) {
+        bytes
+            .iter_mut()
+            .for_each(|byte| *byte = self.read_bits(8) as u8);
+    }
+
+    /// Skips the bits up to the next byte boundary.
+    pub fn align_to_byte(&mut self) {
+        self.bit_offset += (8 - self.bit_offset % 8) % 8;
+    }
+
+    /// Reads the implicit length prefix of a variable-length array that can
+    /// hold at most `capacity` elements.
+    pub fn read_array_length(&mut self, capacity: usize) -> Result<usize, DeserializationError> {
+        let length = self.read_bits(standard_bit_length_for(capacity as u64));
+
+        (length <= capacity as u64)
+            .then_some(length as usize)
+            .ok_or(DeserializationError::ArrayLengthOutOfBounds(
+                length as usize,
+            ))
+    }
+
+    /// Reads the implicit tag of a union that has `variant_count` variants.
+    pub fn read_union_tag(&mut self, variant_count: usize) -> Result<u64, DeserializationError> {
+        let tag = self.read_bits(standard_bit_length_for(variant_count as u64 - 1));
+
+        (tag < variant_count as u64)
+            .then_some(tag)
+            .ok_or(DeserializationError::UnionTagOutOfBounds(tag))
+    }
+
+    /// Reads a delimited value, as written by
+    /// [BitWriter::write_delimited](super::bit_writer::BitWriter::write_delimited).
+    ///
+    /// `read_content` is provided with a reader that is limited to the
+    /// delimited data. Data that `read_content` does not consume is skipped,
+    /// so that values written by newer versions of a type can be read by older
+    /// versions of it.
+    pub fn read_delimited<T, F>(&mut self, read_content: F) -> Result<T, DeserializationError>
+    where
+        F: FnOnce(&mut BitReader<'a>) -> Result<T, DeserializationError>,
+    {
+        self.align_to_byte();
+
+        let content_length = self.read_bits(32) as u32;
+        if content_length as usize * 8 > self.remaining_bits() {
+            return Err(DeserializationError::DelimiterHeaderOutOfBounds(
+                content_length,
+            ));
+        }
+
+        // The header itself may have been zero-extended past the end of the
+        // buffer, in which case the content is zero-extended as well.
+        let start = (self.bit_offset / 8).min(self.buffer.len());
+        let end = (start + content_length as usize).min(self.buffer.len());
+        let mut content_reader = BitReader::new(&self.buffer[start..end]);
+        self.bit_offset += content_length as usize * 8;
+
+        read_content(&mut content_reader)
+    }
+
+    /// Reads a nested composite value, as written by
+    /// [BitWriter::write_composite](super::bit_writer::BitWriter::write_composite).
+    pub fn read_composite<T: Deserialize + DataType>(&mut self) -> Result<T, DeserializationError> {
+        if T::SEALED {
+            self.align_to_byte();
+            let value = T::deserialize(self)?;
+            self.align_to_byte();
+
+            Ok(value)
+        } else {
+            self.read_delimited(|reader| T::deserialize(reader))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bit_writer::BitWriter;
+    use super::*;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn a_delimited_value_of_a_truncated_buffer_is_zero_extended() {
+        for buffer in [&[][..], &[0][..], &[0, 0, 0][..]] {
+            let mut reader = BitReader::new(buffer);
+
+            assert_eq!(
+                reader.read_delimited(|content| Ok(content.read_bits(8))),
+                Ok(0)
+            );
+        }
+    }
+
+    #[test]
+    fn reading_past_the_end_of_the_buffer_yields_zeros() {
+        let mut reader = BitReader::new(&[0xff]);
+
+        assert_eq!(reader.read_bits(4), 0xf);
+        assert_eq!(reader.read_bits(16), 0xf);
+        assert_eq!(reader.read_bits(64), 0);
+    }
+
+    #[test]
+    fn an_array_length_greater_than_the_capacity_is_an_error() {
+        let mut reader = BitReader::new(&[5]);
+
+        assert_eq!(
+            reader.read_array_length(4),
+            Err(DeserializationError::ArrayLengthOutOfBounds(5))
+        );
+    }
+
+    #[test]
+    fn a_union_tag_greater_than_the_amount_of_variants_is_an_error() {
+        let mut reader = BitReader::new(&[3]);
+
+        assert_eq!(
+            reader.read_union_tag(3),
+            Err(DeserializationError::UnionTagOutOfBounds(3))
+        );
+    }
+
+    #[test]
+    fn a_delimiter_header_longer_than_the_remaining_data_is_an_error() {
+        let mut reader = BitReader::new(&[5, 0, 0, 0, 1, 2]);
+
+        assert_eq!(
+            reader.read_delimited(|reader| Ok(reader.read_bits(8))),
+            Err(DeserializationError::DelimiterHeaderOutOfBounds(5))
+        );
+    }
+
+    #[test]
+    fn the_unread_content_of_a_delimited_value_is_skipped() {
+        let mut reader = BitReader::new(&[2, 0, 0, 0, 1, 2, 3]);
+
+        assert_eq!(
+            reader.read_delimited(|reader| Ok(reader.read_bits(8))),
+            Ok(1)
+        );
+        assert_eq!(reader.read_bits(8), 3);
+    }
+
+    #[test]
+    fn the_content_of_a_delimited_value_is_zero_extended_to_the_length_of_its_header() {
+        let mut reader = BitReader::new(&[1, 0, 0, 0, 0xff, 0xff]);
+
+        assert_eq!(
+            reader.read_delimited(|reader| Ok(reader.read_bits(16))),
+            Ok(0xff)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn reading_back_an_unsigned_value_written_with_any_width_preserves_it(value in any::<u64>(), bits in 1..=64u8, offset in 0..8u8) {
+            let value = if bits == 64 { value } else { value & ((1 << bits) - 1) };
+
+            let mut buffer = [0u8; 10];
+            let mut writer = BitWriter::new(&mut buffer);
+            writer.write_bits(0, offset).unwrap();
+            writer.write_bits(value, bits).unwrap();
+
+            let mut reader = BitReader::new(&buffer);
+            reader.read_bits(offset);
+
+            prop_assert_eq!(reader.read_bits(bits), value);
+        }
+
+        #[test]
+        fn reading_back_a_signed_value_written_with_any_width_preserves_it(value in any::<i64>(), bits in 1..=64u8) {
+            let value = if bits == 64 { value } else { (value << (64 - bits)) >> (64 - bits) };
+
+            let mut buffer = [0u8; 8];
+            BitWriter::new(&mut buffer).write_signed(value, bits).unwrap();
+
+            prop_assert_eq!(BitReader::new(&buffer).read_signed(bits), value);
+        }
+
+        #[test]
+        fn reading_back_a_delimited_value_preserves_it(value in any::<u32>(), bits in 1..=32u8) {
+            let value = value as u64 & ((1 << bits) - 1);
+
+            let mut buffer = [0u8; 9];
+            BitWriter::new(&mut buffer).write_delimited(|writer| writer.write_bits(value, bits)).unwrap();
+
+            prop_assert_eq!(BitReader::new(&buffer).read_delimited(|reader| Ok(reader.read_bits(bits))), Ok(value));
+        }
+    }
+}
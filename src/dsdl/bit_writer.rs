@@ -0,0 +1,316 @@
+use super::{
+    error::SerializationError,
+    float16::{f32_to_f16_bits, saturate_f16},
+    serialize::{DataType, Serialize},
+};
+
+/// Returns the bit length of the smallest standard unsigned integer ( 8, 16,
+/// 32 or 64 bits ) that can represent `max_value`.
+///
+/// DSDL uses this width both for the implicit length prefix of variable-length
+/// arrays, where `max_value` is the capacity of the array, and for the implicit
+/// tag of unions, where `max_value` is the index of the last variant.
+pub const fn standard_bit_length_for(max_value: u64) -> u8 {
+    if max_value <= u8::MAX as u64 {
+        8
+    } else if max_value <= u16::MAX as u64 {
+        16
+    } else if max_value <= u32::MAX as u64 {
+        32
+    } else {
+        64
+    }
+}
+
+/// Clamps `value` to the range of an unsigned integer of `bits` bits, as
+/// required by the `saturated` cast mode of DSDL.
+pub fn saturate_unsigned(value: u64, bits: u8) -> u64 {
+    if bits >= 64 {
+        value
+    } else {
+        value.min((1 << bits) - 1)
+    }
+}
+
+/// Clamps `value` to the range of a two's complement integer of `bits` bits,
+/// as required by the `saturated` cast mode of DSDL.
+pub fn saturate_signed(value: i64, bits: u8) -> i64 {
+    if bits >= 64 {
+        value
+    } else {
+        value.clamp(-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+    }
+}
+
+/// Writes values into a byte buffer following the bit-level, little-endian
+/// layout mandated by DSDL.
+///
+/// Bits are written starting from the least significant bit of each byte,
+/// with the least significant bits of a value written first. Values are not
+/// implicitly aligned to byte boundaries, apart from composite types, which
+/// are always byte-aligned.
+///
+/// The buffer does not need to be zeroed before writing, as the writer
+/// overwrites every bit it touches.
+pub struct BitWriter<'a> {
+    buffer: &'a mut [u8],
+    bit_offset: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            bit_offset: 0,
+        }
+    }
+
+    pub fn bit_offset(&self) -> usize {
+        self.bit_offset
+    }
+
+    /// Returns the amount of bytes that were, even partially, written to.
+    pub fn bytes_written(&self) -> usize {
+        self.bit_offset.div_ceil(8)
+    }
+
+    /// Writes the `bits` least significant bits of `value`, discarding the
+    /// others as required by the `truncated` cast mode of DSDL.
+    pub fn write_bits(&mut self, value: u64, bits: u8) -> Result<(), SerializationError> {
+        debug_assert!(bits <= 64);
+
+        if self.bit_offset + bits as usize > self.buffer.len() * 8 {
+            return Err(SerializationError::OutOfSpace);
+        }
+
+        let mut value = value;
+        let mut remaining_bits = bits as usize;
+        while remaining_bits > 0 {
+            let byte_index = self.bit_offset / 8;
+            let bit_index = self.bit_offset % 8;
+            let chunk_bits = (8 - bit_index).min(remaining_bits);
+            let mask = (((1u16 << chunk_bits) - 1) as u8) << bit_index;
+
+            self.buffer[byte_index] =
+                (self.buffer[byte_index] & !mask) | (((value as u8) << bit_index) & mask);
+
+            value = value.checked_shr(chunk_bits as u32).unwrap_or(0);
+            remaining_bits -= chunk_bits;
+            self.bit_offset += chunk_bits;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_bool(&mut self, value: bool) -> Result<(), SerializationError> {
+        self.write_bits(value as u64, 1)
+    }
+
+    pub fn write_unsigned_saturated(
+        &mut self,
+        value: u64,
+        bits: u8,
+    ) -> Result<(), SerializationError> {
+        self.write_bits(saturate_unsigned(value, bits), bits)
+    }
+
+    /// Writes the `bits` least significant bits of the two's complement
+    /// representation of `value`.
+    pub fn write_signed(&mut self, value: i64, bits: u8) -> Result<(), SerializationError> {
+        self.write_bits(value as u64, bits)
+    }
+
+    pub fn write_signed_saturated(
+        &mut self,
+        value: i64,
+        bits: u8,
+    ) -> Result<(), SerializationError> {
+        self.write_signed(saturate_signed(value, bits), bits)
+    }
+
+    pub fn write_f16(&mut self, value: f32) -> Result<(), SerializationError> {
+        self.write_bits(f32_to_f16_bits(value) as u64, 16)
+    }
+
+    pub fn write_f16_saturated(&mut self, value: f32) -> Result<(), SerializationError> {
+        self.write_f16(saturate_f16(value))
+    }
+
+    pub fn write_f32(&mut self, value: f32) -> Result<(), SerializationError> {
+        self.write_bits(value.to_bits() as u64, 32)
+    }
+
+    pub fn write_f64(&mut self, value: f64) -> Result<(), SerializationError> {
+        self.write_bits(value.to_bits(), 64)
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerializationError> {
+        bytes
+            .iter()
+            .try_for_each(|byte| self.write_bits(*byte as u64, 8))
+    }
+
+    /// Writes zero bits until the next byte boundary.
+    pub fn align_to_byte(&mut self) -> Result<(), SerializationError> {
+        let padding = (8 - self.bit_offset % 8) % 8;
+        self.write_bits(0, padding as u8)
+    }
+
+    /// Writes the implicit length prefix of a variable-length array that can
+    /// hold at most `capacity` elements.
+    pub fn write_array_length(
+        &mut self,
+        length: usize,
+        capacity: usize,
+    ) -> Result<(), SerializationError> {
+        debug_assert!(length <= capacity);
+
+        self.write_bits(length as u64, standard_bit_length_for(capacity as u64))
+    }
+
+    /// Writes the implicit tag of a union that has `variant_count` variants.
+    pub fn write_union_tag(
+        &mut self,
+        tag: u64,
+        variant_count: usize,
+    ) -> Result<(), SerializationError> {
+        debug_assert!(tag < variant_count as u64);
+
+        self.write_bits(tag, standard_bit_length_for(variant_count as u64 - 1))
+    }
+
+    /// Writes the data produced by `write_content` prefixed by a delimiter
+    /// header; that is, a 32 bits length, in bytes, of the data that follows.
+    ///
+    /// Both the header and the content are aligned to a byte boundary.
+    pub fn write_delimited<F>(&mut self, write_content: F) -> Result<(), SerializationError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), SerializationError>,
+    {
+        self.align_to_byte()?;
+
+        let header_offset = self.bit_offset;
+        self.write_bits(0, 32)?;
+
+        write_content(self)?;
+        self.align_to_byte()?;
+
+        let content_length = (self.bit_offset - header_offset) / 8 - 4;
+        let end_offset = self.bit_offset;
+
+        self.bit_offset = header_offset;
+        self.write_bits(content_length as u64, 32)?;
+        self.bit_offset = end_offset;
+
+        Ok(())
+    }
+
+    /// Writes a nested composite value.
+    ///
+    /// Composite values are always byte-aligned. Values whose type is not
+    /// sealed are delimited, so that they can be evolved without breaking
+    /// compatibility with the types that nest them.
+    pub fn write_composite<T: Serialize + DataType>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializationError> {
+        if T::SEALED {
+            self.align_to_byte()?;
+            value.serialize(self)?;
+            self.align_to_byte()
+        } else {
+            self.write_delimited(|writer| value.serialize(writer))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn bits_are_written_starting_from_the_least_significant_bit_of_a_byte() {
+        let mut buffer = [0u8; 2];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bits(0b101, 3).unwrap();
+        writer.write_bits(0b11111, 5).unwrap();
+        writer.write_bits(0b1, 1).unwrap();
+
+        assert_eq!(buffer, [0b1111_1101, 0b0000_0001]);
+    }
+
+    #[test]
+    fn multi_byte_values_are_written_in_little_endian_order() {
+        let mut buffer = [0u8; 2];
+        BitWriter::new(&mut buffer).write_bits(0x1234, 16).unwrap();
+
+        assert_eq!(buffer, [0x34, 0x12]);
+    }
+
+    #[test]
+    fn writing_past_the_end_of_the_buffer_is_an_error() {
+        let mut buffer = [0u8; 1];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bits(0, 7).unwrap();
+
+        assert_eq!(writer.write_bits(0, 2), Err(SerializationError::OutOfSpace));
+    }
+
+    #[test]
+    fn writing_overwrites_the_previous_content_of_the_buffer() {
+        let mut buffer = [0xffu8; 1];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bits(0, 4).unwrap();
+        writer.align_to_byte().unwrap();
+
+        assert_eq!(buffer, [0]);
+    }
+
+    #[test]
+    fn a_delimited_value_is_prefixed_by_its_length_in_bytes() {
+        let mut buffer = [0u8; 8];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer
+            .write_delimited(|writer| writer.write_bits(0x01_0203, 24))
+            .unwrap();
+
+        assert_eq!(writer.bytes_written(), 7);
+        assert_eq!(buffer[..7], [3, 0, 0, 0, 0x03, 0x02, 0x01]);
+    }
+
+    #[test]
+    fn the_length_prefix_of_an_array_uses_the_smallest_standard_width_for_its_capacity() {
+        assert_eq!(standard_bit_length_for(255), 8);
+        assert_eq!(standard_bit_length_for(256), 16);
+        assert_eq!(standard_bit_length_for(65536), 32);
+        assert_eq!(standard_bit_length_for(u64::MAX), 64);
+    }
+
+    proptest! {
+        #[test]
+        fn a_saturated_unsigned_value_never_exceeds_the_range_of_its_width(value in any::<u64>(), bits in 1..64u8) {
+            prop_assert!(saturate_unsigned(value, bits) < (1 << bits));
+        }
+
+        #[test]
+        fn a_saturated_signed_value_never_exceeds_the_range_of_its_width(value in any::<i64>(), bits in 1..64u8) {
+            let saturated = saturate_signed(value, bits);
+
+            prop_assert!(saturated >= -(1 << (bits - 1)));
+            prop_assert!(saturated < (1 << (bits - 1)));
+        }
+
+        #[test]
+        fn a_value_that_fits_its_width_is_not_changed_by_saturation(value in 0..128u64) {
+            prop_assert_eq!(saturate_unsigned(value, 7), value);
+        }
+    }
+}
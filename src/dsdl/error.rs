@@ -0,0 +1,11 @@
+#[derive(Debug, PartialEq, Eq)]
+pub enum SerializationError {
+    OutOfSpace,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeserializationError {
+    ArrayLengthOutOfBounds(usize),
+    UnionTagOutOfBounds(u64),
+    DelimiterHeaderOutOfBounds(u32),
+}
@@ -0,0 +1,150 @@
+/// The largest finite value that can be represented by an IEEE 754 binary16.
+pub const F16_MAX: f32 = 65504.0;
+
+/// Converts an `f32` to the bit representation of the nearest IEEE 754
+/// binary16, rounding ties to even.
+///
+/// Finite values that are too large to be represented become infinities, as
+/// required by the `truncated float16` cast mode of DSDL. Use
+/// [saturate_f16] before the conversion to obtain the `saturated` behavior.
+pub fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits & 0x8000_0000) >> 16;
+    let exponent = (bits & 0x7f80_0000) >> 23;
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        let nan_bit = if mantissa == 0 { 0 } else { 0x0200 };
+        return (sign | 0x7c00 | nan_bit | (mantissa >> 13)) as u16;
+    }
+
+    let half_exponent = exponent as i32 - 127 + 15;
+
+    if half_exponent >= 0x1f {
+        return (sign | 0x7c00) as u16;
+    }
+
+    if half_exponent <= 0 {
+        if 14 - half_exponent > 24 {
+            return sign as u16;
+        }
+
+        let mantissa = mantissa | 0x0080_0000;
+        let mut half_mantissa = mantissa >> (14 - half_exponent);
+        let round_bit = 1 << (13 - half_exponent);
+        if (mantissa & round_bit) != 0 && (mantissa & (3 * round_bit - 1)) != 0 {
+            half_mantissa += 1;
+        }
+
+        return (sign | half_mantissa) as u16;
+    }
+
+    let half = sign | ((half_exponent as u32) << 10) | (mantissa >> 13);
+    let round_bit = 0x0000_1000;
+    if (mantissa & round_bit) != 0 && (mantissa & (3 * round_bit - 1)) != 0 {
+        (half + 1) as u16
+    } else {
+        half as u16
+    }
+}
+
+/// Converts the bit representation of an IEEE 754 binary16 to an `f32`.
+///
+/// The conversion is always exact.
+pub fn f16_bits_to_f32(half: u16) -> f32 {
+    let half = half as u32;
+    let sign = (half & 0x8000) << 16;
+    let exponent = half & 0x7c00;
+    let mantissa = half & 0x03ff;
+
+    if half & 0x7fff == 0 {
+        return f32::from_bits(sign);
+    }
+
+    if exponent == 0x7c00 {
+        return if mantissa == 0 {
+            f32::from_bits(sign | 0x7f80_0000)
+        } else {
+            f32::from_bits(sign | 0x7fc0_0000 | (mantissa << 13))
+        };
+    }
+
+    if exponent == 0 {
+        let shift = (mantissa as u16).leading_zeros() - 6;
+        let exponent = (127 - 15 - shift) << 23;
+        let mantissa = (mantissa << (14 + shift)) & 0x007f_ffff;
+
+        return f32::from_bits(sign | exponent | mantissa);
+    }
+
+    let exponent = ((exponent >> 10) + 127 - 15) << 23;
+    f32::from_bits(sign | exponent | (mantissa << 13))
+}
+
+/// Clamps finite values to the range representable by an IEEE 754 binary16,
+/// leaving infinities and NaNs untouched, as required by the `saturated
+/// float16` cast mode of DSDL.
+pub fn saturate_f16(value: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(-F16_MAX, F16_MAX)
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn one_is_represented_as_0x3c00() {
+        assert_eq!(f32_to_f16_bits(1.0), 0x3c00);
+    }
+
+    #[test]
+    fn the_largest_finite_half_is_represented_as_0x7bff() {
+        assert_eq!(f32_to_f16_bits(F16_MAX), 0x7bff);
+    }
+
+    #[test]
+    fn a_value_too_large_for_a_half_becomes_an_infinity() {
+        assert_eq!(f32_to_f16_bits(1.0e6), 0x7c00);
+        assert_eq!(f32_to_f16_bits(-1.0e6), 0xfc00);
+    }
+
+    #[test]
+    fn a_saturated_value_too_large_for_a_half_becomes_the_largest_finite_half() {
+        assert_eq!(f32_to_f16_bits(saturate_f16(1.0e6)), 0x7bff);
+        assert_eq!(f32_to_f16_bits(saturate_f16(-1.0e6)), 0xfbff);
+    }
+
+    #[test]
+    fn saturation_preserves_infinities() {
+        assert_eq!(saturate_f16(f32::INFINITY), f32::INFINITY);
+        assert_eq!(saturate_f16(f32::NEG_INFINITY), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn a_nan_is_preserved_trough_the_conversion() {
+        assert!(f16_bits_to_f32(f32_to_f16_bits(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn the_smallest_subnormal_half_is_converted_exactly() {
+        assert_eq!(f16_bits_to_f32(0x0001), f32::from_bits(0x3380_0000));
+        assert_eq!(f32_to_f16_bits(f32::from_bits(0x3380_0000)), 0x0001);
+    }
+
+    proptest! {
+        #[test]
+        fn converting_a_non_nan_half_to_f32_and_back_preserves_it(half in 0..u16::MAX) {
+            prop_assume!(half & 0x7c00 != 0x7c00 || half & 0x03ff == 0);
+
+            prop_assert_eq!(f32_to_f16_bits(f16_bits_to_f32(half)), half);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+pub mod bit_reader;
+pub mod bit_writer;
+pub mod error;
+pub mod float16;
+pub mod serialize;
+
+pub use bit_reader::BitReader;
+pub use bit_writer::BitWriter;
+pub use error::{DeserializationError, SerializationError};
+pub use serialize::{deserialize_from_slice, serialize_to_slice, DataType, Deserialize, Serialize};
@@ -0,0 +1,235 @@
+use heapless::Vec;
+
+use super::{
+    bit_reader::BitReader,
+    bit_writer::BitWriter,
+    error::{DeserializationError, SerializationError},
+};
+
+pub trait Serialize {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError>;
+}
+
+pub trait Deserialize: Sized {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError>;
+}
+
+/// Describes the properties of a composite DSDL type that affect how it is
+/// nested into other types.
+pub trait DataType {
+    /// The maximum size, in bytes, of the serialized representation of any
+    /// present or future version of the type.
+    const EXTENT_BYTES: usize;
+    /// Whether the type is `@sealed`, and thus nested without a delimiter
+    /// header.
+    const SEALED: bool;
+}
+
+/// Serializes `value` at the start of `buffer`, returning the amount of bytes
+/// that were written.
+pub fn serialize_to_slice<T: Serialize>(
+    value: &T,
+    buffer: &mut [u8],
+) -> Result<usize, SerializationError> {
+    let mut writer = BitWriter::new(buffer);
+    value.serialize(&mut writer)?;
+
+    Ok(writer.bytes_written())
+}
+
+/// Deserializes a value from `bytes`.
+///
+/// As mandated by DSDL, missing data is read as zeros and data that is not
+/// needed to build the value is ignored.
+pub fn deserialize_from_slice<T: Deserialize>(bytes: &[u8]) -> Result<T, DeserializationError> {
+    T::deserialize(&mut BitReader::new(bytes))
+}
+
+impl Serialize for bool {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+        writer.write_bool(*self)
+    }
+}
+
+impl Deserialize for bool {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+        Ok(reader.read_bool())
+    }
+}
+
+macro_rules! impl_serialization_for_integer {
+    ($($unsigned:ty, $signed:ty, $bits:expr;)*) => {
+        $(
+            impl Serialize for $unsigned {
+                fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+                    writer.write_bits(*self as u64, $bits)
+                }
+            }
+
+            impl Deserialize for $unsigned {
+                fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+                    Ok(reader.read_bits($bits) as $unsigned)
+                }
+            }
+
+            impl Serialize for $signed {
+                fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+                    writer.write_signed(*self as i64, $bits)
+                }
+            }
+
+            impl Deserialize for $signed {
+                fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+                    Ok(reader.read_signed($bits) as $signed)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialization_for_integer! {
+    u8, i8, 8;
+    u16, i16, 16;
+    u32, i32, 32;
+    u64, i64, 64;
+}
+
+impl Serialize for f32 {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+        writer.write_f32(*self)
+    }
+}
+
+impl Deserialize for f32 {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+        Ok(reader.read_f32())
+    }
+}
+
+impl Serialize for f64 {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+        writer.write_f64(*self)
+    }
+}
+
+impl Deserialize for f64 {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+        Ok(reader.read_f64())
+    }
+}
+
+/// Fixed-length arrays are serialized as their elements, one after the other,
+/// with no length prefix.
+impl<T: Serialize, const N: usize> Serialize for [T; N] {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+        self.iter()
+            .try_for_each(|element| element.serialize(writer))
+    }
+}
+
+impl<T: Deserialize, const N: usize> Deserialize for [T; N] {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+        let mut error = None;
+        let elements: [Option<T>; N] = core::array::from_fn(|_| match error {
+            Some(_) => None,
+            None => T::deserialize(reader).map_err(|err| error = Some(err)).ok(),
+        });
+
+        match error {
+            Some(err) => Err(err),
+            // Every element was deserialized when there was no error.
+            None => Ok(elements.map(Option::unwrap)),
+        }
+    }
+}
+
+/// Variable-length arrays are serialized as their elements prefixed by their
+/// length, using the smallest standard unsigned integer that can represent
+/// the capacity of the array.
+impl<T: Serialize, const N: usize> Serialize for Vec<T, N> {
+    fn serialize(&self, writer: &mut BitWriter<'_>) -> Result<(), SerializationError> {
+        writer.write_array_length(self.len(), self.capacity())?;
+        self.iter()
+            .try_for_each(|element| element.serialize(writer))
+    }
+}
+
+impl<T: Deserialize, const N: usize> Deserialize for Vec<T, N> {
+    fn deserialize(reader: &mut BitReader<'_>) -> Result<Self, DeserializationError> {
+        let mut vec = Vec::new();
+        let length = reader.read_array_length(vec.capacity())?;
+
+        for _ in 0..length {
+            // The length was checked against the capacity of the vector.
+            let _ = vec.push(T::deserialize(reader)?);
+        }
+
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn a_variable_length_array_with_a_capacity_over_255_has_a_16_bits_length_prefix() {
+        let mut array = Vec::<u8, 300>::new();
+        array.extend_from_slice(&[7, 8]).unwrap();
+
+        let mut buffer = [0u8; 8];
+        let written = serialize_to_slice(&array, &mut buffer).unwrap();
+
+        assert_eq!(buffer[..written], [2, 0, 7, 8]);
+    }
+
+    #[test]
+    fn deserializing_from_an_empty_slice_yields_zero_values() {
+        assert_eq!(deserialize_from_slice::<[u32; 2]>(&[]), Ok([0, 0]));
+    }
+
+    proptest! {
+        #[test]
+        fn deserializing_a_serialized_integer_preserves_it(a in any::<u8>(), b in any::<i16>(), c in any::<u32>(), d in any::<i64>()) {
+            let mut buffer = [0u8; 15];
+            let mut writer = BitWriter::new(&mut buffer);
+            a.serialize(&mut writer).unwrap();
+            b.serialize(&mut writer).unwrap();
+            c.serialize(&mut writer).unwrap();
+            d.serialize(&mut writer).unwrap();
+
+            let mut reader = BitReader::new(&buffer);
+            prop_assert_eq!(u8::deserialize(&mut reader), Ok(a));
+            prop_assert_eq!(i16::deserialize(&mut reader), Ok(b));
+            prop_assert_eq!(u32::deserialize(&mut reader), Ok(c));
+            prop_assert_eq!(i64::deserialize(&mut reader), Ok(d));
+        }
+
+        #[test]
+        fn deserializing_a_serialized_float_preserves_it(a in any::<f32>(), b in any::<f64>()) {
+            let mut buffer = [0u8; 12];
+            let mut writer = BitWriter::new(&mut buffer);
+            a.serialize(&mut writer).unwrap();
+            b.serialize(&mut writer).unwrap();
+
+            let mut reader = BitReader::new(&buffer);
+            prop_assert_eq!(f32::deserialize(&mut reader).unwrap().to_bits(), a.to_bits());
+            prop_assert_eq!(f64::deserialize(&mut reader).unwrap().to_bits(), b.to_bits());
+        }
+
+        #[test]
+        fn deserializing_a_serialized_variable_length_array_preserves_it(elements in vec(any::<u16>(), 0..300)) {
+            let array = Vec::<u16, 300>::from_slice(&elements).unwrap();
+
+            let mut buffer = [0u8; 602];
+            let written = serialize_to_slice(&array, &mut buffer).unwrap();
+
+            prop_assert_eq!(deserialize_from_slice::<Vec<u16, 300>>(&buffer[..written]), Ok(array));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use embedded_can::{ExtendedId, Frame, Id};
+
+use crate::{CanFrame, CLASSIC_MTU};
+
+/// The reasons for which a frame coming from a HAL cannot be part of a
+/// transfer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RejectedFrame {
+    StandardId,
+    Remote,
+    /// The frame carries more data than a classic CAN frame.
+    TooLong,
+}
+
+/// Wraps any `embedded_can::Frame` with an extended ID so that it can be used
+/// as a [CanFrame].
+#[derive(Debug)]
+pub struct HalFrame<F: Frame> {
+    frame: F,
+    data: [u8; CLASSIC_MTU],
+}
+
+impl<F: Frame> HalFrame<F> {
+    /// Wraps a frame received from a HAL, rejecting the frames that cannot be
+    /// part of a transfer.
+    pub fn new(frame: F) -> Result<Self, RejectedFrame> {
+        if frame.is_remote_frame() {
+            return Err(RejectedFrame::Remote);
+        }
+
+        if !frame.is_extended() {
+            return Err(RejectedFrame::StandardId);
+        }
+
+        if frame.data().len() > CLASSIC_MTU {
+            return Err(RejectedFrame::TooLong);
+        }
+
+        let mut data = [0u8; CLASSIC_MTU];
+        data[..frame.data().len()].copy_from_slice(frame.data());
+
+        Ok(Self { frame, data })
+    }
+
+    pub fn into_inner(self) -> F {
+        self.frame
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.frame
+    }
+}
+
+impl<F: Frame> From<(u32, [u8; CLASSIC_MTU], usize)> for HalFrame<F> {
+    /// Builds the frame through `embedded_can::Frame::new`.
+    ///
+    /// Panics if the HAL refuses to build a data frame with an extended ID,
+    /// which is never the case for the IDs and payloads produced by this
+    /// crate.
+    fn from((id, data, len): (u32, [u8; CLASSIC_MTU], usize)) -> Self {
+        let frame = ExtendedId::new(id)
+            .and_then(|id| F::new(id, &data[..len]))
+            .expect("the HAL should be able to build a data frame with an extended id");
+
+        Self { frame, data }
+    }
+}
+
+impl<F: Frame + core::fmt::Debug> CanFrame<CLASSIC_MTU> for HalFrame<F> {
+    fn id(&self) -> u32 {
+        match self.frame.id() {
+            Id::Extended(id) => id.as_raw(),
+            Id::Standard(id) => u32::from(id.as_raw()),
+        }
+    }
+
+    fn payload(&self) -> (&[u8; CLASSIC_MTU], usize) {
+        (&self.data, self.frame.data().len())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use embedded_can::StandardId;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct TestFrame {
+        pub(crate) id: Id,
+        /// Room for the data of a CAN FD frame, which some HALs may hand out.
+        pub(crate) data: heapless::Vec<u8, 64>,
+        pub(crate) is_remote: bool,
+    }
+
+    impl Frame for TestFrame {
+        fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+            Some(Self {
+                id: id.into(),
+                data: heapless::Vec::from_slice(data).ok()?,
+                is_remote: false,
+            })
+        }
+
+        fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+            let mut frame = Self::new(id, &[0; 8][..dlc])?;
+            frame.is_remote = true;
+            Some(frame)
+        }
+
+        fn is_extended(&self) -> bool {
+            matches!(self.id, Id::Extended(_))
+        }
+
+        fn is_remote_frame(&self) -> bool {
+            self.is_remote
+        }
+
+        fn id(&self) -> Id {
+            self.id
+        }
+
+        fn dlc(&self) -> usize {
+            self.data.len()
+        }
+
+        fn data(&self) -> &[u8] {
+            &self.data
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn a_hal_frame_preserves_the_id_and_payload_it_was_built_with(id in 0..0x2000_0000u32, data in vec(proptest::num::u8::ANY, 0..=8)) {
+            let mut payload = [0u8; CLASSIC_MTU];
+            payload[..data.len()].copy_from_slice(&data);
+
+            let frame = HalFrame::<TestFrame>::from((id, payload, data.len()));
+
+            prop_assert_eq!(frame.id(), id);
+            prop_assert_eq!(frame.payload(), (&payload, data.len()));
+            prop_assert_eq!(frame.inner().data(), data.as_slice());
+        }
+    }
+
+    #[test]
+    fn frames_with_a_standard_id_are_rejected() {
+        let frame = TestFrame::new(StandardId::new(1).unwrap(), &[1]).unwrap();
+
+        assert_eq!(HalFrame::new(frame).unwrap_err(), RejectedFrame::StandardId);
+    }
+
+    #[test]
+    fn frames_longer_than_a_classic_frame_are_rejected() {
+        let frame = TestFrame::new(ExtendedId::new(1).unwrap(), &[1; 12]).unwrap();
+
+        assert_eq!(HalFrame::new(frame).unwrap_err(), RejectedFrame::TooLong);
+    }
+
+    #[test]
+    fn remote_frames_are_rejected() {
+        let frame = TestFrame::new_remote(ExtendedId::new(1).unwrap(), 1).unwrap();
+
+        assert_eq!(HalFrame::new(frame).unwrap_err(), RejectedFrame::Remote);
+    }
+}
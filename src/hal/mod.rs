@@ -0,0 +1,10 @@
+//! Adapters between this crate and the `embedded-can` traits implemented by
+//! HAL crates, enabled by the `hal` feature.
+
+pub mod hal_frame;
+pub mod receive;
+pub mod writer;
+
+pub use hal_frame::{HalFrame, RejectedFrame};
+pub use receive::{receive_blocking, receive_nb, ReceiveError};
+pub use writer::{BlockingCanWriter, NbCanWriter};
@@ -0,0 +1,145 @@
+use embedded_can::{blocking, nb as nonblocking};
+
+use super::hal_frame::{HalFrame, RejectedFrame};
+use crate::{
+    rx::rx_network::{RxError, RxProducer},
+    CLASSIC_MTU,
+};
+
+#[derive(Debug)]
+pub enum ReceiveError<E, F: embedded_can::Frame + core::fmt::Debug> {
+    Can(E),
+    Rejected(RejectedFrame),
+    Rx(RxError<HalFrame<F>, CLASSIC_MTU>),
+}
+
+fn forward<
+    E,
+    F: embedded_can::Frame + core::fmt::Debug,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
+>(
+    frame: F,
+    producer: &mut RxProducer<'_, HalFrame<F>, CAPACITY, TRANSFER_CAPACITY, CLASSIC_MTU>,
+) -> Result<(), ReceiveError<E, F>> {
+    let frame = HalFrame::new(frame).map_err(ReceiveError::Rejected)?;
+
+    producer.receive(frame).map_err(ReceiveError::Rx)
+}
+
+/// Pulls one frame from `can`, if one is available, and passes it to
+/// `producer`.
+///
+/// Frames with a standard ID and remote frames cannot be part of a transfer
+/// and are reported as [ReceiveError::Rejected].
+pub fn receive_nb<C: nonblocking::Can, const CAPACITY: usize, const TRANSFER_CAPACITY: usize>(
+    can: &mut C,
+    producer: &mut RxProducer<'_, HalFrame<C::Frame>, CAPACITY, TRANSFER_CAPACITY, CLASSIC_MTU>,
+) -> nb::Result<(), ReceiveError<C::Error, C::Frame>>
+where
+    C::Frame: core::fmt::Debug,
+{
+    let frame = can
+        .receive()
+        .map_err(|error| error.map(ReceiveError::Can))?;
+
+    forward(frame, producer).map_err(nb::Error::Other)
+}
+
+/// Waits for a frame from `can` and passes it to `producer`.
+///
+/// Frames with a standard ID and remote frames cannot be part of a transfer
+/// and are reported as [ReceiveError::Rejected].
+pub fn receive_blocking<C: blocking::Can, const CAPACITY: usize, const TRANSFER_CAPACITY: usize>(
+    can: &mut C,
+    producer: &mut RxProducer<'_, HalFrame<C::Frame>, CAPACITY, TRANSFER_CAPACITY, CLASSIC_MTU>,
+) -> Result<(), ReceiveError<C::Error, C::Frame>>
+where
+    C::Frame: core::fmt::Debug,
+{
+    let frame = can.receive().map_err(ReceiveError::Can)?;
+
+    forward(frame, producer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::hal_frame::tests::TestFrame;
+    use crate::hal::writer::NbCanWriter;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::session_id::TransferPriority;
+    use crate::tx::{stream_transmitter::StreamTransmitter, transmitter::send};
+    use embedded_can::{ErrorKind, Frame, StandardId};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::collections::VecDeque;
+
+    /// A driver whose transmitted frames are received back, as on a bus with
+    /// a single node in loopback mode.
+    #[derive(Default)]
+    struct LoopbackCan {
+        frames: VecDeque<TestFrame>,
+    }
+
+    impl nonblocking::Can for LoopbackCan {
+        type Frame = TestFrame;
+        type Error = ErrorKind;
+
+        fn transmit(
+            &mut self,
+            frame: &Self::Frame,
+        ) -> nb::Result<Option<Self::Frame>, Self::Error> {
+            self.frames.push_back(frame.clone());
+            Ok(None)
+        }
+
+        fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+            self.frames.pop_front().ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn frames_written_through_a_hal_driver_are_received_back_as_the_original_transfer(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut transmitter = StreamTransmitter::<_, HalFrame<TestFrame>, CLASSIC_MTU>::new(NbCanWriter(LoopbackCan::default()));
+            send(&mut transmitter, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            let mut can = transmitter.into_writer().0;
+            let mut network = RxNetwork::<HalFrame<TestFrame>, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, mut consumer) = network.split();
+
+            loop {
+                match receive_nb(&mut can, &mut producer) {
+                    Ok(()) => continue,
+                    Err(nb::Error::WouldBlock) => break,
+                    Err(nb::Error::Other(error)) => panic!("{:?}", error),
+                }
+            }
+
+            let transfer = consumer.next().unwrap();
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(AsRef::<[u8]>::as_ref(&transfer.payload), payload.as_slice());
+        }
+    }
+
+    #[test]
+    fn receiving_a_frame_with_a_standard_id_is_an_error() {
+        let mut can = LoopbackCan::default();
+        can.frames
+            .push_back(TestFrame::new(StandardId::new(1).unwrap(), &[0xE0]).unwrap());
+
+        let mut network = RxNetwork::<HalFrame<TestFrame>, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = network.split();
+
+        assert!(matches!(
+            receive_nb(&mut can, &mut producer),
+            Err(nb::Error::Other(ReceiveError::Rejected(
+                RejectedFrame::StandardId
+            )))
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use embedded_can::{blocking, nb as nonblocking};
+
+use super::hal_frame::HalFrame;
+use crate::{tx::stream_transmitter::CanWriter, CLASSIC_MTU};
+
+/// A [CanWriter] for the drivers implementing `embedded_can::nb::Can`.
+///
+/// Writing a frame waits for the driver to accept it. When the driver makes
+/// room for the frame by replacing a lower priority pending frame, the
+/// replaced frame is written again, so that no frame is lost.
+pub struct NbCanWriter<C: nonblocking::Can>(pub C);
+
+impl<C: nonblocking::Can> CanWriter<HalFrame<C::Frame>, CLASSIC_MTU> for NbCanWriter<C>
+where
+    C::Frame: core::fmt::Debug,
+{
+    type Error = C::Error;
+
+    fn write_frame(&mut self, frame: HalFrame<C::Frame>) -> Result<(), Self::Error> {
+        let mut pending = frame.into_inner();
+
+        while let Some(replaced) = nb::block!(self.0.transmit(&pending))? {
+            pending = replaced;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [CanWriter] for the drivers implementing `embedded_can::blocking::Can`.
+pub struct BlockingCanWriter<C: blocking::Can>(pub C);
+
+impl<C: blocking::Can> CanWriter<HalFrame<C::Frame>, CLASSIC_MTU> for BlockingCanWriter<C>
+where
+    C::Frame: core::fmt::Debug,
+{
+    type Error = C::Error;
+
+    fn write_frame(&mut self, frame: HalFrame<C::Frame>) -> Result<(), Self::Error> {
+        self.0.transmit(frame.inner())
+    }
+}
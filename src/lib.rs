@@ -12,17 +12,67 @@
 #![no_std]
 #![feature(cfg_eval)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+pub mod candump;
+pub mod display;
+pub mod dsdl;
+#[cfg(feature = "hal")]
+pub mod hal;
+#[cfg(feature = "std")]
+pub mod pcap;
+pub mod presentation;
 pub mod rx;
+#[cfg(feature = "serial")]
+pub mod serial;
 pub mod session_id;
+#[cfg(feature = "std")]
+pub mod sim;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod socket_can;
+pub mod statistics;
 pub mod tail_byte;
+pub mod timestamp;
+pub mod transport;
 pub mod tx;
+pub mod udp;
+
+/// Re-exported for the code generated from DSDL definitions, which refers to
+/// the `heapless` collections through this crate.
+pub use heapless;
 
 pub const CLASSIC_MTU: usize = 8;
 pub const EXTENDED_MTU: usize = 64;
 
+/// The lengths that the data of a CAN FD frame can have, as encoded by its
+/// DLC.
+const CAN_FD_LENGTHS: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// The length of the shortest CAN FD frame that can carry `length` bytes of
+/// data.
+///
+/// Frames longer than 8 bytes must be padded to one of the lengths that their
+/// DLC can encode. As mandated by Cyphal/CAN, the padding is made of zeros
+/// placed before the tail byte.
+pub fn padded_frame_length(length: usize) -> usize {
+    CAN_FD_LENGTHS
+        .iter()
+        .copied()
+        .find(|valid| *valid >= length)
+        .unwrap_or(length)
+}
+
 pub trait CanFrame<const MTU: usize>: From<(u32, [u8; MTU], usize)> + core::fmt::Debug {
     fn id(&self) -> u32;
     fn payload(&self) -> (&[u8; MTU], usize);
+
+    /// The instant at which the frame was received, for drivers that are able
+    /// to provide it.
+    fn timestamp(&self) -> Option<timestamp::Timestamp> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -32,41 +82,48 @@ mod tests {
 
     use super::*;
     use super::{
-        rx::{
-            rx_network::{RxError, RxNetwork, RxProducer},
-            transfer::Transfer,
-        },
+        rx::rx_network::{RxError, RxNetwork, RxProducer},
         session_id::{
             session_kind::strategy::session_kind, NodeId, SessionKind, SubjectId, TransferPriority,
         },
+        timestamp::Timestamp,
         tx::{
             stream_transmitter::{CanWriter, StreamTransmitter},
             transmitter::send,
+            tx_queue::TxQueue,
         },
         CLASSIC_MTU,
     };
 
-    use heapless::{
-        consts::{U512, U64},
-        ArrayLength,
-    };
-
     use proptest::collection::vec;
     use proptest::prelude::*;
 
     extern crate std;
-    use std::format;
+    use std::{format, vec::Vec};
 
     #[derive(Debug)]
     pub(super) struct ClassicFrame {
         data: [u8; 8],
         id: u32,
         len: usize,
+        timestamp: Option<timestamp::Timestamp>,
+    }
+
+    impl ClassicFrame {
+        pub(super) fn with_timestamp(mut self, timestamp: timestamp::Timestamp) -> Self {
+            self.timestamp = Some(timestamp);
+            self
+        }
     }
 
     impl From<(u32, [u8; 8], usize)> for ClassicFrame {
         fn from((id, data, len): (u32, [u8; 8], usize)) -> Self {
-            Self { data, id, len }
+            Self {
+                data,
+                id,
+                len,
+                timestamp: None,
+            }
         }
     }
 
@@ -78,24 +135,51 @@ mod tests {
         fn payload(&self) -> (&[u8; CLASSIC_MTU], usize) {
             (&self.data, self.len)
         }
+
+        fn timestamp(&self) -> Option<timestamp::Timestamp> {
+            self.timestamp
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct FdFrame {
+        data: [u8; EXTENDED_MTU],
+        id: u32,
+        len: usize,
+    }
+
+    impl From<(u32, [u8; EXTENDED_MTU], usize)> for FdFrame {
+        fn from((id, data, len): (u32, [u8; EXTENDED_MTU], usize)) -> Self {
+            Self { data, id, len }
+        }
+    }
+
+    impl CanFrame<EXTENDED_MTU> for FdFrame {
+        fn id(&self) -> u32 {
+            self.id
+        }
+
+        fn payload(&self) -> (&[u8; EXTENDED_MTU], usize) {
+            (&self.data, self.len)
+        }
     }
 
     pub(super) struct TxRxGlue<
         'a,
         Frame: CanFrame<MTU>,
-        Capacity: ArrayLength<Transfer<TransferCapacity>>,
-        TransferCapacity: ArrayLength<u8>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
         const MTU: usize,
     > {
-        pub(super) rx_producer: RxProducer<'a, Frame, Capacity, TransferCapacity, MTU>,
+        pub(super) rx_producer: RxProducer<'a, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
     }
 
     impl<
             Frame: CanFrame<MTU>,
-            Capacity: ArrayLength<Transfer<TransferCapacity>>,
-            TransferCapacity: ArrayLength<u8>,
+            const CAPACITY: usize,
+            const TRANSFER_CAPACITY: usize,
             const MTU: usize,
-        > CanWriter<Frame, MTU> for TxRxGlue<'_, Frame, Capacity, TransferCapacity, MTU>
+        > CanWriter<Frame, MTU> for TxRxGlue<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
     {
         type Error = RxError<Frame, MTU>;
 
@@ -104,13 +188,68 @@ mod tests {
         }
     }
 
+    /// A message of node 0 on subject 0.
+    pub(super) fn message() -> SessionKind {
+        SessionKind::Message {
+            source_node_id: NodeId::new(),
+            subject_id: SubjectId::new(),
+        }
+    }
+
+    /// The frames of `payload` sent as a transfer of `kind`, in the order of
+    /// transmission.
+    pub(super) fn frames_of<Frame: CanFrame<MTU>, const MTU: usize>(
+        payload: &[u8],
+        kind: SessionKind,
+    ) -> Vec<Frame> {
+        let mut queue = TxQueue::<Frame, 64, MTU>::default();
+        send(&mut queue, payload, kind, TransferPriority::Nominal).unwrap();
+
+        core::iter::from_fn(|| queue.pop_ready(Timestamp::default())).collect()
+    }
+
+    /// The frames of `payload` sent as a transfer of `kind`, logged 100µs
+    /// apart from `start`.
+    #[cfg(feature = "std")]
+    pub(super) fn logged_frames_of<const MTU: usize>(
+        payload: &[u8],
+        kind: SessionKind,
+        start: Timestamp,
+    ) -> Vec<candump::LoggedFrame<MTU>> {
+        frames_of::<candump::LoggedFrame<MTU>, MTU>(payload, kind)
+            .into_iter()
+            .enumerate()
+            .map(|(index, frame)| {
+                frame.with_timestamp(Some(Timestamp::from_micros(
+                    start.as_micros() + index as u64 * 100,
+                )))
+            })
+            .collect()
+    }
+
+    /// A log format that frames can be written to and read back from.
+    #[cfg(feature = "std")]
+    pub(super) trait FrameLog {
+        fn write<const MTU: usize>(frames: &[candump::LoggedFrame<MTU>]) -> Vec<u8>;
+
+        fn read<const MTU: usize>(log: &[u8]) -> Vec<candump::LoggedFrame<MTU>>;
+    }
+
+    /// Writes `frames` to a log in the format of `Log` and reads them back.
+    #[cfg(feature = "std")]
+    pub(super) fn round_trip<Log: FrameLog, const MTU: usize>(
+        frames: &[candump::LoggedFrame<MTU>],
+    ) -> Vec<candump::LoggedFrame<MTU>> {
+        Log::read(&Log::write(frames))
+    }
+
     proptest! {
         #[test]
         fn receiving_the_frames_of_a_transmission_rebuilds_the_original_payload(payload in vec(proptest::num::u8::ANY, 1..100)) {
-            let mut rx_network = RxNetwork::<ClassicFrame, U64, U512, CLASSIC_MTU>::default();
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
             let (rx_producer, mut rx_consumer) = rx_network.split();
 
-            let mut transmitter = StreamTransmitter::<TxRxGlue<ClassicFrame, U64, U512, CLASSIC_MTU>, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue{ rx_producer });
+            let mut transmitter = StreamTransmitter::<TxRxGlue<ClassicFrame, 64, 512, CLASSIC_MTU>, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue{ rx_producer });
 
             let node_id = NodeId::new();
             send(
@@ -134,10 +273,10 @@ mod tests {
     proptest! {
         #[test]
         fn receiving_the_frames_of_a_transmission_rebuilds_the_original_session_kind(payload in vec(proptest::num::u8::ANY, 1..100), kind in session_kind()) {
-            let mut rx_network = RxNetwork::<ClassicFrame, U64, U512, CLASSIC_MTU>::default();
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
             let (rx_producer, mut rx_consumer) = rx_network.split();
 
-            let mut transmitter = StreamTransmitter::<TxRxGlue<ClassicFrame, U64, U512, CLASSIC_MTU>, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue{ rx_producer });
+            let mut transmitter = StreamTransmitter::<TxRxGlue<ClassicFrame, 64, 512, CLASSIC_MTU>, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue{ rx_producer });
 
             send(
                 &mut transmitter,
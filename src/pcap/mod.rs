@@ -0,0 +1,23 @@
+//! Exporting frames to pcapng captures and replaying pcap captures, enabled
+//! by the `std` feature.
+//!
+//! Frames are stored with the `LINKTYPE_CAN_SOCKETCAN` link type, which is
+//! the one used by Wireshark for SocketCAN captures, so that the captures
+//! can be opened with its Cyphal dissector. Each packet holds the 8 bytes of
+//! the header of a SocketCAN frame followed by the data of the frame.
+//!
+//! A [PcapTee] records the frames written by a transmitter, and the frames
+//! read by a [PcapReader] can be fed to an [RxProducer] to rebuild the
+//! captured transfers offline.
+//!
+//! [RxProducer]: crate::rx::rx_network::RxProducer
+
+pub mod packet;
+pub mod reader;
+pub mod tee;
+pub mod writer;
+
+pub use packet::PacketError;
+pub use reader::{PcapReader, ReadError};
+pub use tee::PcapTee;
+pub use writer::PcapngWriter;
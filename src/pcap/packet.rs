@@ -0,0 +1,133 @@
+use std::vec::Vec;
+
+use crate::{candump::LoggedFrame, CanFrame};
+
+/// The link type of the packets holding SocketCAN frames.
+pub const LINKTYPE_CAN_SOCKETCAN: u16 = 227;
+
+/// The size of the header of a SocketCAN frame, which precedes its data.
+pub const HEADER_SIZE: usize = 8;
+
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+
+/// The flag telling a CAN FD frame apart from a classic one.
+const CANFD_FDF: u8 = 0x04;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    /// The packet is shorter than the header, or than the length announced by
+    /// the header.
+    Truncated,
+    /// The frame has an 11-bit ID, which Cyphal does not use.
+    StandardFrame,
+    RemoteFrame,
+    ErrorFrame,
+    /// The frame carries more bytes than the MTU, as with a CAN FD frame read
+    /// as a classic one.
+    TooLong(usize),
+}
+
+/// Encodes `frame` as a SocketCAN frame, as a CAN FD frame when `MTU`
+/// exceeds the one of classic CAN.
+///
+/// As in the captures made by Linux, the ID is stored in network byte order.
+pub fn encode<Frame: CanFrame<MTU>, const MTU: usize>(frame: &Frame) -> Vec<u8> {
+    let (data, len) = frame.payload();
+    let fd_flags = if MTU > crate::CLASSIC_MTU {
+        CANFD_FDF
+    } else {
+        0
+    };
+
+    let mut packet = Vec::with_capacity(HEADER_SIZE + len);
+    packet.extend_from_slice(&(frame.id() | CAN_EFF_FLAG).to_be_bytes());
+    packet.extend_from_slice(&[len as u8, fd_flags, 0, 0]);
+    packet.extend_from_slice(&data[..len]);
+
+    packet
+}
+
+/// Decodes the SocketCAN frame held by `packet`.
+pub fn decode<const MTU: usize>(packet: &[u8]) -> Result<LoggedFrame<MTU>, PacketError> {
+    let header = packet.get(..HEADER_SIZE).ok_or(PacketError::Truncated)?;
+    let can_id = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+
+    if can_id & CAN_ERR_FLAG != 0 {
+        return Err(PacketError::ErrorFrame);
+    }
+    if can_id & CAN_RTR_FLAG != 0 {
+        return Err(PacketError::RemoteFrame);
+    }
+    if can_id & CAN_EFF_FLAG == 0 {
+        return Err(PacketError::StandardFrame);
+    }
+
+    let len = usize::from(header[4]);
+    let payload = packet
+        .get(HEADER_SIZE..HEADER_SIZE + len)
+        .ok_or(PacketError::Truncated)?;
+    let mut data = [0; MTU];
+    data.get_mut(..len)
+        .ok_or(PacketError::TooLong(len))?
+        .copy_from_slice(payload);
+
+    Ok(LoggedFrame::from((can_id & CAN_EFF_MASK, data, len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn an_encoded_frame_is_decoded_back_unchanged(id in 0..=CAN_EFF_MASK, data in vec(proptest::num::u8::ANY, 0..=EXTENDED_MTU)) {
+            let mut payload = [0; EXTENDED_MTU];
+            payload[..data.len()].copy_from_slice(&data);
+            let frame = LoggedFrame::<EXTENDED_MTU>::from((id, payload, data.len()));
+
+            prop_assert_eq!(decode(&encode(&frame)), Ok(frame));
+        }
+    }
+
+    #[test]
+    fn the_id_of_a_frame_is_stored_in_network_byte_order_with_the_extended_flag() {
+        let frame = LoggedFrame::<CLASSIC_MTU>::from((0x107D552A, [0xE0, 0, 0, 0, 0, 0, 0, 0], 1));
+
+        assert_eq!(encode(&frame), [0x90, 0x7D, 0x55, 0x2A, 1, 0, 0, 0, 0xE0]);
+    }
+
+    #[test]
+    fn packets_that_do_not_hold_a_cyphal_frame_are_errors() {
+        assert_eq!(
+            decode::<CLASSIC_MTU>(&[0x00, 0x00, 0x01, 0x23, 1, 0, 0, 0, 0]),
+            Err(PacketError::StandardFrame)
+        );
+        assert_eq!(
+            decode::<CLASSIC_MTU>(&[0xC0, 0x00, 0x01, 0x23, 0, 0, 0, 0]),
+            Err(PacketError::RemoteFrame)
+        );
+        assert_eq!(
+            decode::<CLASSIC_MTU>(&[0xA0, 0x00, 0x00, 0x04, 8, 0, 0, 0]),
+            Err(PacketError::ErrorFrame)
+        );
+        assert_eq!(
+            decode::<CLASSIC_MTU>(&[0x90, 0x7D, 0x55, 0x2A, 2, 0, 0, 0, 0xE0]),
+            Err(PacketError::Truncated)
+        );
+        assert_eq!(
+            decode::<CLASSIC_MTU>(
+                &[[0x90, 0x7D, 0x55, 0x2A, 12, 4, 0, 0].as_ref(), &[0; 12]].concat()
+            ),
+            Err(PacketError::TooLong(12))
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use std::{
+    io::{self, Read},
+    vec::Vec,
+};
+
+use super::{
+    packet::{self, PacketError, LINKTYPE_CAN_SOCKETCAN},
+    writer::{
+        BYTE_ORDER_MAGIC, ENHANCED_PACKET_BLOCK, INTERFACE_DESCRIPTION_BLOCK, SECTION_HEADER_BLOCK,
+    },
+};
+use crate::{candump::LoggedFrame, timestamp::Timestamp};
+
+const PCAP_MAGIC: u32 = 0xA1B2_C3D4;
+const PCAP_NANOSECOND_MAGIC: u32 = 0xA1B2_3C4D;
+
+/// The option of an interface description holding its timestamp resolution.
+const IF_TSRESOL: u16 = 9;
+
+/// Blocks are read whole, so their length is bounded to keep a corrupted
+/// capture from exhausting the memory.
+const MAX_BLOCK_LENGTH: usize = 1 << 20;
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// The capture starts with neither the magic number of a pcap file nor a
+    /// pcapng section header.
+    UnknownFormat(u32),
+    /// The packets are not SocketCAN frames.
+    UnsupportedLinkType(u16),
+    /// The capture ends in the middle of a record or a block.
+    Truncated,
+    /// A block has a length that is not a multiple of 32 bits, or is too large.
+    InvalidBlockLength(u32),
+    /// A packet refers to an interface that was not described.
+    UnknownInterface(u32),
+    /// The packet with the 1-based number `packet` does not hold a Cyphal frame.
+    Packet {
+        packet: usize,
+        error: PacketError,
+    },
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => ReadError::Truncated,
+            _ => ReadError::Io(err),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Interface {
+    link_type: u16,
+    ticks_per_second: u64,
+}
+
+#[derive(Debug)]
+enum Format {
+    Pcap { interface: Interface },
+    Pcapng { interfaces: Vec<Interface> },
+}
+
+/// Reads the frames of a pcap or pcapng capture of SocketCAN frames, such as
+/// one saved by Wireshark or written by a [PcapngWriter].
+///
+/// Captures in either byte order are supported, and the timestamps are
+/// converted to microseconds since the Unix epoch. As with a candump log,
+/// reading goes on after a packet that does not hold a Cyphal frame, but
+/// stops after any other error.
+///
+/// [PcapngWriter]: super::PcapngWriter
+pub struct PcapReader<R: Read, const MTU: usize> {
+    reader: R,
+    format: Format,
+    is_big_endian: bool,
+    packet_number: usize,
+    is_done: bool,
+}
+
+impl<R: Read, const MTU: usize> PcapReader<R, MTU> {
+    /// Reads the header of the capture, which tells its format.
+    pub fn new(mut reader: R) -> Result<Self, ReadError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+
+        let mut capture = Self {
+            reader,
+            format: Format::Pcapng {
+                interfaces: Vec::new(),
+            },
+            is_big_endian: false,
+            packet_number: 0,
+            is_done: false,
+        };
+
+        if u32::from_le_bytes(magic) == SECTION_HEADER_BLOCK {
+            capture.read_section_header()?;
+            return Ok(capture);
+        }
+
+        let ticks_per_second = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (PCAP_MAGIC, _) | (_, PCAP_MAGIC) => 1_000_000,
+            (PCAP_NANOSECOND_MAGIC, _) | (_, PCAP_NANOSECOND_MAGIC) => 1_000_000_000,
+            _ => return Err(ReadError::UnknownFormat(u32::from_be_bytes(magic))),
+        };
+        capture.is_big_endian = u32::from_be_bytes(magic) & 0xFFFF_0000 == 0xA1B2_0000;
+
+        // The version, the time zone, the accuracy of the timestamps and the
+        // length limit of the packets precede the link type.
+        let mut header = [0; 20];
+        capture.reader.read_exact(&mut header)?;
+        capture.format = Format::Pcap {
+            interface: Interface {
+                link_type: capture.u32_at(&header[16..]) as u16,
+                ticks_per_second,
+            },
+        };
+
+        Ok(capture)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn u16_at(&self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        if self.is_big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }
+    }
+
+    fn u32_at(&self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if self.is_big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    /// Fills `buffer`, returning `false` if the capture ended before its first
+    /// byte.
+    fn read_or_end(&mut self, buffer: &mut [u8]) -> Result<bool, ReadError> {
+        let mut len = 0;
+        while len < buffer.len() {
+            match self.reader.read(&mut buffer[len..]) {
+                Ok(0) if len == 0 => return Ok(false),
+                Ok(0) => return Err(ReadError::Truncated),
+                Ok(count) => len += count,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Reads the rest of a section header whose block type was just read,
+    /// starting a new section with no interface.
+    fn read_section_header(&mut self) -> Result<(), ReadError> {
+        let mut header = [0; 8];
+        self.reader.read_exact(&mut header)?;
+        self.is_big_endian = match u32::from_le_bytes([header[4], header[5], header[6], header[7]])
+        {
+            BYTE_ORDER_MAGIC => false,
+            magic if magic.swap_bytes() == BYTE_ORDER_MAGIC => true,
+            _ => return Err(ReadError::UnknownFormat(SECTION_HEADER_BLOCK)),
+        };
+
+        // The byte order magic was already read along with the length.
+        self.read_block_body(self.u32_at(&header), 12)?;
+        self.format = Format::Pcapng {
+            interfaces: Vec::new(),
+        };
+
+        Ok(())
+    }
+
+    /// Reads the rest of a block of `total_length` bytes, of which `read`
+    /// bytes were already read, leaving out its trailing length.
+    fn read_block_body(&mut self, total_length: u32, read: usize) -> Result<Vec<u8>, ReadError> {
+        let length = total_length as usize;
+        if length < read + 4 || length > MAX_BLOCK_LENGTH || !length.is_multiple_of(4) {
+            return Err(ReadError::InvalidBlockLength(total_length));
+        }
+
+        let mut body = std::vec![0; length - read];
+        self.reader.read_exact(&mut body)?;
+        body.truncate(length - read - 4);
+
+        Ok(body)
+    }
+
+    /// Reads the next packet, along with the interface that captured it and
+    /// the timestamp in ticks of that interface.
+    fn read_packet(&mut self) -> Result<Option<(Interface, u64, Vec<u8>)>, ReadError> {
+        match &self.format {
+            Format::Pcap { interface } => {
+                let interface = *interface;
+                let mut header = [0; 16];
+                if !self.read_or_end(&mut header)? {
+                    return Ok(None);
+                }
+
+                let ticks = u64::from(self.u32_at(&header)) * interface.ticks_per_second
+                    + u64::from(self.u32_at(&header[4..]));
+                let captured_len = self.u32_at(&header[8..]) as usize;
+                if captured_len > MAX_BLOCK_LENGTH {
+                    return Err(ReadError::InvalidBlockLength(captured_len as u32));
+                }
+                let mut packet = std::vec![0; captured_len];
+                self.reader.read_exact(&mut packet)?;
+
+                Ok(Some((interface, ticks, packet)))
+            }
+            Format::Pcapng { .. } => loop {
+                let mut block_type = [0; 4];
+                if !self.read_or_end(&mut block_type)? {
+                    return Ok(None);
+                }
+                if u32::from_le_bytes(block_type) == SECTION_HEADER_BLOCK {
+                    self.read_section_header()?;
+                    continue;
+                }
+
+                let mut length = [0; 4];
+                self.reader.read_exact(&mut length)?;
+                let body = self.read_block_body(self.u32_at(&length), 8)?;
+
+                match self.u32_at(&block_type) {
+                    INTERFACE_DESCRIPTION_BLOCK => {
+                        let interface = self.parse_interface(&body)?;
+                        if let Format::Pcapng { interfaces } = &mut self.format {
+                            interfaces.push(interface);
+                        }
+                    }
+                    ENHANCED_PACKET_BLOCK => {
+                        if body.len() < 20 {
+                            return Err(ReadError::Truncated);
+                        }
+                        let interface_id = self.u32_at(&body);
+                        let interface = match &self.format {
+                            Format::Pcapng { interfaces } => interfaces
+                                .get(interface_id as usize)
+                                .copied()
+                                .ok_or(ReadError::UnknownInterface(interface_id))?,
+                            Format::Pcap { interface } => *interface,
+                        };
+                        let ticks = u64::from(self.u32_at(&body[4..])) << 32
+                            | u64::from(self.u32_at(&body[8..]));
+                        let captured_len = self.u32_at(&body[12..]) as usize;
+                        let packet = body
+                            .get(20..20 + captured_len)
+                            .ok_or(ReadError::Truncated)?
+                            .to_vec();
+
+                        return Ok(Some((interface, ticks, packet)));
+                    }
+                    // Statistics, name resolution and the other blocks hold no
+                    // frame.
+                    _ => {}
+                }
+            },
+        }
+    }
+
+    fn parse_interface(&self, body: &[u8]) -> Result<Interface, ReadError> {
+        if body.len() < 8 {
+            return Err(ReadError::Truncated);
+        }
+
+        let mut interface = Interface {
+            link_type: self.u16_at(body),
+            ticks_per_second: 1_000_000,
+        };
+
+        let mut options = &body[8..];
+        while options.len() >= 4 {
+            let code = self.u16_at(options);
+            let len = usize::from(self.u16_at(&options[2..]));
+            let value = options.get(4..4 + len).ok_or(ReadError::Truncated)?;
+
+            if code == IF_TSRESOL && len == 1 {
+                // The resolution is a negative power of 10, or of 2 when the
+                // most significant bit is set.
+                let exponent = u32::from(value[0] & 0x7F);
+                let base: u64 = if value[0] & 0x80 == 0 { 10 } else { 2 };
+                interface.ticks_per_second = base.checked_pow(exponent).unwrap_or(u64::MAX);
+            }
+
+            options = options
+                .get(4 + len.next_multiple_of(4)..)
+                .unwrap_or_default();
+        }
+
+        Ok(interface)
+    }
+}
+
+impl<R: Read, const MTU: usize> Iterator for PcapReader<R, MTU> {
+    type Item = Result<LoggedFrame<MTU>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        let (interface, ticks, packet) = match self.read_packet() {
+            Ok(Some(packet)) => packet,
+            Ok(None) => {
+                self.is_done = true;
+                return None;
+            }
+            Err(err) => {
+                self.is_done = true;
+                return Some(Err(err));
+            }
+        };
+        self.packet_number += 1;
+
+        if interface.link_type != LINKTYPE_CAN_SOCKETCAN {
+            self.is_done = true;
+            return Some(Err(ReadError::UnsupportedLinkType(interface.link_type)));
+        }
+
+        let micros = u128::from(ticks) * 1_000_000 / u128::from(interface.ticks_per_second.max(1));
+        Some(
+            packet::decode(&packet)
+                .map(|frame| frame.with_timestamp(Some(Timestamp::from_micros(micros as u64))))
+                .map_err(|error| ReadError::Packet {
+                    packet: self.packet_number,
+                    error,
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CanFrame, CLASSIC_MTU};
+
+    extern crate std;
+    use std::vec;
+
+    /// A big-endian pcap file with nanosecond timestamps, holding a heartbeat
+    /// frame and a frame with an 11-bit ID.
+    fn nanosecond_pcap() -> Vec<u8> {
+        let mut capture = vec![0xA1, 0xB2, 0x3C, 0x4D, 0, 2, 0, 4];
+        capture.extend_from_slice(&[0; 8]);
+        capture.extend_from_slice(&[0, 0, 0, 72, 0, 0, 0, 227]);
+
+        for (seconds, nanos, packet) in [
+            (
+                1u32,
+                500_000_000u32,
+                &[0x90, 0x7D, 0x55, 0x2A, 2, 0, 0, 0, 0x01, 0xE0][..],
+            ),
+            (2, 0, &[0x00, 0x00, 0x01, 0x23, 1, 0, 0, 0, 0x00][..]),
+        ] {
+            capture.extend_from_slice(&seconds.to_be_bytes());
+            capture.extend_from_slice(&nanos.to_be_bytes());
+            capture.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+            capture.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+            capture.extend_from_slice(packet);
+        }
+
+        capture
+    }
+
+    #[test]
+    fn a_pcap_file_is_read_in_its_byte_order_and_resolution() {
+        let frames: Vec<_> = PcapReader::<_, CLASSIC_MTU>::new(&nanosecond_pcap()[..])
+            .unwrap()
+            .collect();
+
+        assert_eq!(frames.len(), 2);
+        let heartbeat = frames[0].as_ref().unwrap();
+        assert_eq!(heartbeat.id(), 0x107D552A);
+        assert_eq!(heartbeat.data(), [0x01, 0xE0]);
+        assert_eq!(
+            heartbeat.timestamp(),
+            Some(Timestamp::from_micros(1_500_000))
+        );
+        assert!(matches!(
+            frames[1],
+            Err(ReadError::Packet {
+                packet: 2,
+                error: PacketError::StandardFrame
+            })
+        ));
+    }
+
+    #[test]
+    fn the_timestamp_resolution_of_an_interface_is_applied() {
+        let mut capture = vec![];
+        for block in [
+            &[
+                0x0A, 0x0D, 0x0D, 0x0A, 28, 0, 0, 0, 0x4D, 0x3C, 0x2B, 0x1A, 1, 0, 0, 0, 0xFF,
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 28, 0, 0, 0,
+            ][..],
+            // A SocketCAN interface with a resolution of milliseconds.
+            &[
+                1, 0, 0, 0, 32, 0, 0, 0, 227, 0, 0, 0, 0, 0, 0, 0, 9, 0, 1, 0, 3, 0, 0, 0, 0, 0, 0,
+                0, 32, 0, 0, 0,
+            ][..],
+            &[
+                6, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xE8, 0x03, 0, 0, 9, 0, 0, 0, 9,
+                0, 0, 0, 0x90, 0x7D, 0x55, 0x2A, 1, 0, 0, 0, 0xE0, 0, 0, 0, 44, 0, 0, 0,
+            ][..],
+        ] {
+            capture.extend_from_slice(block);
+        }
+
+        let mut reader = PcapReader::<_, CLASSIC_MTU>::new(&capture[..]).unwrap();
+        let frame = reader.next().unwrap().unwrap();
+
+        assert_eq!(frame.timestamp(), Some(Timestamp::from_micros(1_000_000)));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn a_capture_of_another_link_type_is_an_error() {
+        let mut capture = nanosecond_pcap();
+        capture[23] = 1;
+
+        let mut reader = PcapReader::<_, CLASSIC_MTU>::new(&capture[..]).unwrap();
+
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ReadError::UnsupportedLinkType(1)))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn a_file_that_is_not_a_capture_is_an_error() {
+        assert!(matches!(
+            PcapReader::<_, CLASSIC_MTU>::new(&b"(0.0) can0 107D552A#E0"[..]),
+            Err(ReadError::UnknownFormat(_))
+        ));
+    }
+}
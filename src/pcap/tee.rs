@@ -0,0 +1,202 @@
+use core::marker::PhantomData;
+use std::{
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{packet, writer::PcapngWriter};
+use crate::{timestamp::Timestamp, tx::stream_transmitter::CanWriter, CanFrame};
+
+/// A [CanWriter] that records every frame accepted by the writer it wraps to
+/// a pcapng capture, to keep a trace of what the node sent.
+///
+/// Frames without a timestamp are recorded at the current time.
+///
+/// Failing to record a frame does not fail the write, as the frame already
+/// reached the bus. The capture stops at the first such error instead, which
+/// is kept for [PcapTee::capture_error].
+pub struct PcapTee<
+    Writer: CanWriter<Frame, MTU>,
+    Out: Write,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+> {
+    writer: Writer,
+    capture: PcapngWriter<Out>,
+    capture_error: Option<io::Error>,
+    _frame_marker: PhantomData<Frame>,
+}
+
+impl<Writer: CanWriter<Frame, MTU>, Out: Write, Frame: CanFrame<MTU>, const MTU: usize>
+    PcapTee<Writer, Out, Frame, MTU>
+{
+    pub fn new(writer: Writer, capture: PcapngWriter<Out>) -> Self {
+        Self {
+            writer,
+            capture,
+            capture_error: None,
+            _frame_marker: PhantomData,
+        }
+    }
+
+    /// The error that stopped the capture, if any.
+    pub fn capture_error(&self) -> Option<&io::Error> {
+        self.capture_error.as_ref()
+    }
+
+    pub fn into_inner(self) -> (Writer, PcapngWriter<Out>) {
+        (self.writer, self.capture)
+    }
+}
+
+impl<Writer: CanWriter<Frame, MTU>, Out: Write, Frame: CanFrame<MTU>, const MTU: usize>
+    CanWriter<Frame, MTU> for PcapTee<Writer, Out, Frame, MTU>
+{
+    type Error = Writer::Error;
+
+    fn write_frame(&mut self, frame: Frame) -> Result<(), Self::Error> {
+        if self.capture_error.is_some() {
+            return self.writer.write_frame(frame);
+        }
+
+        let timestamp = frame.timestamp().unwrap_or_else(|| {
+            let elapsed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Timestamp::from_micros(elapsed.as_micros() as u64)
+        });
+        // The frame is moved into the writer, so it is encoded beforehand.
+        let packet = packet::encode(&frame);
+
+        self.writer.write_frame(frame)?;
+        if let Err(error) = self.capture.write_packet(&packet, timestamp) {
+            self.capture_error = Some(error);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candump::LoggedFrame;
+    use crate::pcap::PcapReader;
+    use crate::session_id::{session_kind::strategy::session_kind, TransferPriority};
+    use crate::tests::message;
+    use crate::tx::{stream_transmitter::StreamTransmitter, transmitter::send};
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    #[derive(Default)]
+    struct RecordingWriter {
+        frames: Vec<LoggedFrame<CLASSIC_MTU>>,
+        is_broken: bool,
+    }
+
+    impl CanWriter<LoggedFrame<CLASSIC_MTU>, CLASSIC_MTU> for RecordingWriter {
+        type Error = ();
+
+        fn write_frame(&mut self, frame: LoggedFrame<CLASSIC_MTU>) -> Result<(), Self::Error> {
+            if self.is_broken {
+                return Err(());
+            }
+
+            self.frames.push(frame);
+            Ok(())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn the_capture_holds_the_frames_that_were_written(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let tee = PcapTee::new(RecordingWriter::default(), PcapngWriter::new(Vec::new()).unwrap());
+            let mut transmitter = StreamTransmitter::new(tee);
+
+            send(&mut transmitter, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            let (writer, capture) = transmitter.into_writer().into_inner();
+            let captured: Vec<_> = PcapReader::<_, CLASSIC_MTU>::new(&capture.into_inner()[..])
+                .unwrap()
+                .map(|frame| frame.unwrap().with_timestamp(None))
+                .collect();
+
+            prop_assert_eq!(captured, writer.frames);
+        }
+    }
+
+    #[test]
+    fn a_frame_that_could_not_be_written_is_not_recorded() {
+        let mut tee = PcapTee::new(
+            RecordingWriter {
+                is_broken: true,
+                ..Default::default()
+            },
+            PcapngWriter::new(Vec::new()).unwrap(),
+        );
+
+        assert!(matches!(
+            tee.write_frame(LoggedFrame::from((0, [0; CLASSIC_MTU], 1))),
+            Err(())
+        ));
+
+        let (_, capture) = tee.into_inner();
+        assert_eq!(
+            PcapReader::<_, CLASSIC_MTU>::new(&capture.into_inner()[..])
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    /// A disk that fills up once `room` bytes were written to it.
+    struct SmallDisk {
+        room: usize,
+    }
+
+    impl Write for SmallDisk {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.room == 0 {
+                return Err(io::ErrorKind::StorageFull.into());
+            }
+
+            let written = buf.len().min(self.room);
+            self.room -= written;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_capture_error_does_not_prevent_the_frames_from_being_written() {
+        // The disk fills up right after the header of the capture.
+        let room = PcapngWriter::new(Vec::new()).unwrap().into_inner().len();
+        let tee = PcapTee::new(
+            RecordingWriter::default(),
+            PcapngWriter::new(SmallDisk { room }).unwrap(),
+        );
+        let mut transmitter = StreamTransmitter::new(tee);
+
+        send(
+            &mut transmitter,
+            &[1; 20],
+            message(),
+            TransferPriority::Nominal,
+        )
+        .unwrap();
+
+        let tee = transmitter.into_writer();
+        assert_eq!(
+            tee.capture_error().map(io::Error::kind),
+            Some(io::ErrorKind::StorageFull)
+        );
+        assert_eq!(tee.into_inner().0.frames.len(), 4);
+    }
+}
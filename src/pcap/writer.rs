@@ -0,0 +1,207 @@
+use std::io::{self, Write};
+
+use super::packet::{self, LINKTYPE_CAN_SOCKETCAN};
+use crate::{
+    candump::LoggedFrame,
+    rx::transfer::Transfer,
+    session_id::{can_id_for_session_kind, TransferPriority},
+    timestamp::Timestamp,
+    tx::breakdown::Breakdown,
+    CanFrame,
+};
+
+pub(super) const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+pub(super) const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+pub(super) const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+pub(super) const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// Writes frames to a pcapng capture holding a single SocketCAN interface.
+///
+/// The blocks are written in the byte order of the host, as allowed by the
+/// format, and the timestamps are in microseconds since the Unix epoch, which
+/// is the default resolution of an interface.
+pub struct PcapngWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Starts the capture by writing its section header and the description of
+    /// its interface.
+    pub fn new(writer: W) -> io::Result<Self> {
+        let mut capture = Self { writer };
+
+        let mut section_header = BYTE_ORDER_MAGIC.to_ne_bytes().to_vec();
+        section_header.extend_from_slice(&1u16.to_ne_bytes());
+        section_header.extend_from_slice(&0u16.to_ne_bytes());
+        // The length of the section is not known in advance.
+        section_header.extend_from_slice(&(-1i64).to_ne_bytes());
+        capture.write_block(SECTION_HEADER_BLOCK, &section_header)?;
+
+        let mut interface = LINKTYPE_CAN_SOCKETCAN.to_ne_bytes().to_vec();
+        interface.extend_from_slice(&0u16.to_ne_bytes());
+        // No limit on the length of the packets.
+        interface.extend_from_slice(&0u32.to_ne_bytes());
+        capture.write_block(INTERFACE_DESCRIPTION_BLOCK, &interface)?;
+
+        Ok(capture)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Records `frame` as captured at the time of its timestamp.
+    ///
+    /// A frame without a timestamp is recorded as captured at the epoch.
+    pub fn write_frame<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        frame: &Frame,
+    ) -> io::Result<()> {
+        self.write_frame_at(frame, frame.timestamp().unwrap_or_default())
+    }
+
+    /// Records `frame` as captured at `timestamp`.
+    pub fn write_frame_at<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        frame: &Frame,
+        timestamp: Timestamp,
+    ) -> io::Result<()> {
+        self.write_packet(&packet::encode(frame), timestamp)
+    }
+
+    /// Records the frames that `transfer` is made of, breaking it down again
+    /// with its own transfer ID, so that a dissector can reassemble it.
+    ///
+    /// Every frame is recorded at the timestamp of the transfer. The priority
+    /// is not kept by a received transfer, so it has to be given.
+    pub fn write_transfer<const CAPACITY: usize, const MTU: usize>(
+        &mut self,
+        transfer: &Transfer<CAPACITY>,
+        priority: TransferPriority,
+    ) -> io::Result<()> {
+        let can_id = can_id_for_session_kind(transfer.kind, priority);
+        let timestamp = transfer.timestamp.unwrap_or_default();
+
+        Breakdown::<LoggedFrame<MTU>, MTU>::with_transfer_id(
+            &transfer.payload,
+            can_id,
+            transfer.transfer_id,
+        )
+        .try_for_each(|frame| self.write_frame_at(&frame, timestamp))
+    }
+
+    pub(super) fn write_packet(&mut self, packet: &[u8], timestamp: Timestamp) -> io::Result<()> {
+        let micros = timestamp.as_micros();
+
+        let mut block = 0u32.to_ne_bytes().to_vec();
+        block.extend_from_slice(&((micros >> 32) as u32).to_ne_bytes());
+        block.extend_from_slice(&(micros as u32).to_ne_bytes());
+        block.extend_from_slice(&(packet.len() as u32).to_ne_bytes());
+        block.extend_from_slice(&(packet.len() as u32).to_ne_bytes());
+        block.extend_from_slice(packet);
+
+        self.write_block(ENHANCED_PACKET_BLOCK, &block)
+    }
+
+    /// Writes a block made of `body`, padded to 32 bits, between its type and
+    /// its length.
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> io::Result<()> {
+        let padding = body.len().next_multiple_of(4) - body.len();
+        let total_length = (12 + body.len() + padding) as u32;
+
+        self.writer.write_all(&block_type.to_ne_bytes())?;
+        self.writer.write_all(&total_length.to_ne_bytes())?;
+        self.writer.write_all(body)?;
+        self.writer.write_all(&[0; 3][..padding])?;
+        self.writer.write_all(&total_length.to_ne_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcap::PcapReader;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{logged_frames_of, round_trip, FrameLog};
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    /// The time at which the frames of the tests are logged.
+    const START: Timestamp = Timestamp::from_micros(1_700_000_000_000_000);
+
+    struct Pcapng;
+
+    impl FrameLog for Pcapng {
+        fn write<const MTU: usize>(frames: &[LoggedFrame<MTU>]) -> Vec<u8> {
+            let mut writer = PcapngWriter::new(Vec::new()).unwrap();
+            for frame in frames {
+                writer.write_frame(frame).unwrap();
+            }
+
+            writer.into_inner()
+        }
+
+        fn read<const MTU: usize>(capture: &[u8]) -> Vec<LoggedFrame<MTU>> {
+            PcapReader::<_, MTU>::new(capture)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn classic_frames_written_to_a_capture_are_read_back_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let frames = logged_frames_of::<CLASSIC_MTU>(&payload, kind, START);
+
+            prop_assert_eq!(round_trip::<Pcapng, _>(&frames), frames);
+        }
+
+        #[test]
+        fn fd_frames_written_to_a_capture_are_read_back_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..300)) {
+            let frames = logged_frames_of::<EXTENDED_MTU>(&payload, kind, START);
+
+            prop_assert_eq!(round_trip::<Pcapng, _>(&frames), frames);
+        }
+
+        #[test]
+        fn a_transfer_is_written_as_the_frames_it_was_received_from(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let frames = logged_frames_of::<CLASSIC_MTU>(&payload, kind, START);
+            let transfer = Transfer::<128>::new(
+                heapless::Vec::from_slice(&payload).unwrap(),
+                kind,
+                crate::tail_byte::TransferId::new(),
+                frames[0].timestamp(),
+            );
+
+            let mut writer = PcapngWriter::new(Vec::new()).unwrap();
+            writer.write_transfer::<128, CLASSIC_MTU>(&transfer, TransferPriority::Nominal).unwrap();
+            let capture = writer.into_inner();
+
+            let written: Vec<_> = PcapReader::<_, CLASSIC_MTU>::new(&capture[..])
+                .unwrap()
+                .map(|frame| frame.unwrap().with_timestamp(None))
+                .collect();
+            let expected: Vec<_> = frames.into_iter().map(|frame| frame.with_timestamp(None)).collect();
+
+            prop_assert_eq!(written, expected);
+        }
+    }
+
+    #[test]
+    fn every_block_of_a_capture_is_aligned_to_32_bits() {
+        let mut writer = PcapngWriter::new(Vec::new()).unwrap();
+        writer
+            .write_frame(&LoggedFrame::<CLASSIC_MTU>::from((0, [0; 8], 1)))
+            .unwrap();
+        let capture = writer.into_inner();
+
+        // A section header, an interface description, and a packet of 9
+        // bytes padded to 12.
+        assert_eq!(capture.len(), 28 + 20 + 32 + 12);
+    }
+}
@@ -0,0 +1,89 @@
+use core::marker::PhantomData;
+
+use super::{
+    error::ServiceError,
+    service::{Metadata, Service},
+};
+use crate::{
+    dsdl::{deserialize_from_slice, serialize_to_slice, DeserializationError},
+    rx::transfer::Transfer,
+    session_id::{session_kind::Request, NodeId, SessionKind, TransferPriority},
+    tail_byte::TransferId,
+    tx::transmitter::{send_with_transfer_id, Transmitter},
+    CanFrame,
+};
+
+/// Calls the service `S` on other nodes.
+///
+/// Requests are serialized into a stack buffer of `CAPACITY` bytes, which
+/// should be at least as big as the extent of `S::Request`.
+pub struct Client<S: Service, const CAPACITY: usize> {
+    node_id: NodeId,
+    priority: TransferPriority,
+    transfer_id: TransferId,
+    _marker: PhantomData<S>,
+}
+
+impl<S: Service, const CAPACITY: usize> Client<S, CAPACITY> {
+    pub fn new(node_id: NodeId, priority: TransferPriority) -> Self {
+        Self {
+            node_id,
+            priority,
+            transfer_id: TransferId::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sends `request` to `server_node_id`, returning the transfer ID that
+    /// the response will carry.
+    pub fn call<Tx: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        transmitter: &mut Tx,
+        server_node_id: NodeId,
+        request: &S::Request,
+    ) -> Result<TransferId, ServiceError<Tx::Error>> {
+        let mut buffer = [0u8; CAPACITY];
+        let length =
+            serialize_to_slice(request, &mut buffer).map_err(ServiceError::Serialization)?;
+
+        let transfer_id = self.transfer_id;
+        send_with_transfer_id(
+            transmitter,
+            &buffer[..length],
+            SessionKind::Request(Request::new(self.node_id, server_node_id, S::SERVICE_ID)),
+            self.priority,
+            transfer_id,
+        )
+        .map_err(ServiceError::Transmission)?;
+
+        self.transfer_id.advance();
+
+        Ok(transfer_id)
+    }
+
+    /// Deserializes `transfer` if it is a response of `S` addressed to this
+    /// client, returning `None` for any other transfer.
+    pub fn accept<const TRANSFER_CAPACITY: usize>(
+        &self,
+        transfer: &Transfer<TRANSFER_CAPACITY>,
+    ) -> Option<Result<(S::Response, Metadata), DeserializationError>> {
+        match transfer.kind {
+            SessionKind::Response(request)
+                if request.source_node_id() == self.node_id
+                    && request.service_id() == S::SERVICE_ID =>
+            {
+                Some(deserialize_from_slice(&transfer.payload).map(|response| {
+                    (
+                        response,
+                        Metadata {
+                            remote_node_id: request.destination_node_id(),
+                            transfer_id: transfer.transfer_id,
+                            timestamp: transfer.timestamp,
+                        },
+                    )
+                }))
+            }
+            _ => None,
+        }
+    }
+}
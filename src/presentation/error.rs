@@ -0,0 +1,14 @@
+use crate::dsdl::{DeserializationError, SerializationError};
+
+#[derive(Debug)]
+pub enum PublishError<E> {
+    Serialization(SerializationError),
+    Transmission(E),
+}
+
+#[derive(Debug)]
+pub enum ServiceError<E> {
+    Serialization(SerializationError),
+    Deserialization(DeserializationError),
+    Transmission(E),
+}
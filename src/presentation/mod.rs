@@ -0,0 +1,13 @@
+pub mod client;
+pub mod error;
+pub mod publisher;
+pub mod server;
+pub mod service;
+pub mod subscriber;
+
+pub use client::Client;
+pub use error::{PublishError, ServiceError};
+pub use publisher::Publisher;
+pub use server::Server;
+pub use service::{Metadata, Service};
+pub use subscriber::{Message, Subscriber};
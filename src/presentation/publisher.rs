@@ -0,0 +1,124 @@
+use core::marker::PhantomData;
+
+use super::error::PublishError;
+use crate::{
+    dsdl::{serialize_to_slice, Serialize},
+    session_id::{NodeId, SessionKind, SubjectId, TransferPriority},
+    tail_byte::TransferId,
+    tx::transmitter::{send_with_transfer_id, Transmitter},
+    CanFrame,
+};
+
+/// Publishes values of type `T` on a subject.
+///
+/// Each value is serialized into a stack buffer of `CAPACITY` bytes, which
+/// should be at least as big as the extent of `T`, and sent with the next
+/// transfer ID of the subject.
+pub struct Publisher<T: Serialize, const CAPACITY: usize> {
+    source_node_id: NodeId,
+    subject_id: SubjectId,
+    priority: TransferPriority,
+    transfer_id: TransferId,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize, const CAPACITY: usize> Publisher<T, CAPACITY> {
+    pub fn new(source_node_id: NodeId, subject_id: SubjectId, priority: TransferPriority) -> Self {
+        Self {
+            source_node_id,
+            subject_id,
+            priority,
+            transfer_id: TransferId::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn subject_id(&self) -> SubjectId {
+        self.subject_id
+    }
+
+    /// The transfer ID that will be used by the next publication.
+    pub fn transfer_id(&self) -> TransferId {
+        self.transfer_id
+    }
+
+    pub fn publish<Tx: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        transmitter: &mut Tx,
+        value: &T,
+    ) -> Result<(), PublishError<Tx::Error>> {
+        let mut buffer = [0u8; CAPACITY];
+        let length = serialize_to_slice(value, &mut buffer).map_err(PublishError::Serialization)?;
+
+        send_with_transfer_id(
+            transmitter,
+            &buffer[..length],
+            SessionKind::Message {
+                source_node_id: self.source_node_id,
+                subject_id: self.subject_id,
+            },
+            self.priority,
+            self.transfer_id,
+        )
+        .map_err(PublishError::Transmission)?;
+
+        self.transfer_id.advance();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsdl::SerializationError;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::node_id::strategy::node_id;
+    use crate::session_id::subject_id::strategy::subject_id;
+    use crate::tests::{ClassicFrame, TxRxGlue};
+    use crate::tx::stream_transmitter::StreamTransmitter;
+    use crate::CLASSIC_MTU;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn each_publication_uses_the_transfer_id_following_the_one_of_the_previous_publication(source in node_id(), subject in subject_id(), publications in 1..64usize) {
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (rx_producer, mut rx_consumer) = rx_network.split();
+            let mut transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+
+            let mut publisher = Publisher::<u32, 4>::new(source, subject, TransferPriority::Nominal);
+            let mut expected_transfer_id = TransferId::new();
+
+            for value in 0..publications as u32 {
+                publisher.publish(&mut transmitter, &value).unwrap();
+
+                let transfer = rx_consumer.next().unwrap();
+                prop_assert_eq!(transfer.transfer_id, expected_transfer_id);
+                prop_assert_eq!(transfer.kind, SessionKind::Message { source_node_id: source, subject_id: subject });
+
+                expected_transfer_id.advance();
+            }
+        }
+    }
+
+    #[test]
+    fn publishing_a_value_that_does_not_fit_the_buffer_is_an_error() {
+        let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (rx_producer, _) = rx_network.split();
+        let mut transmitter =
+            StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+
+        let mut publisher =
+            Publisher::<u64, 4>::new(NodeId::new(), SubjectId::new(), TransferPriority::Nominal);
+
+        assert!(matches!(
+            publisher.publish(&mut transmitter, &u64::MAX),
+            Err(PublishError::Serialization(SerializationError::OutOfSpace))
+        ));
+        assert_eq!(publisher.transfer_id(), TransferId::new());
+    }
+}
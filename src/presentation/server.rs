@@ -0,0 +1,211 @@
+use core::marker::PhantomData;
+
+use super::{
+    error::ServiceError,
+    service::{Metadata, Service},
+};
+use crate::{
+    dsdl::{deserialize_from_slice, serialize_to_slice},
+    rx::transfer::Transfer,
+    session_id::{session_kind::Request, NodeId, SessionKind, TransferPriority},
+    tx::transmitter::{send_with_transfer_id, Transmitter},
+    CanFrame,
+};
+
+/// Serves the service `S` by answering its requests with `handler`.
+///
+/// The handler may return `None` to leave a request unanswered. Responses are
+/// serialized into a stack buffer of `CAPACITY` bytes, which should be at
+/// least as big as the extent of `S::Response`.
+pub struct Server<
+    S: Service,
+    F: Fn(&S::Request, Metadata) -> Option<S::Response>,
+    const CAPACITY: usize,
+> {
+    node_id: NodeId,
+    priority: TransferPriority,
+    handler: F,
+    _marker: PhantomData<S>,
+}
+
+impl<S: Service, F: Fn(&S::Request, Metadata) -> Option<S::Response>, const CAPACITY: usize>
+    Server<S, F, CAPACITY>
+{
+    pub fn new(node_id: NodeId, priority: TransferPriority, handler: F) -> Self {
+        Self {
+            node_id,
+            priority,
+            handler,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Answers `transfer` if it is a request of `S` addressed to this server,
+    /// returning `None` for any other transfer.
+    ///
+    /// The response is sent with the transfer ID of the request, so that the
+    /// client is able to match them.
+    pub fn serve<
+        const TRANSFER_CAPACITY: usize,
+        Tx: Transmitter<Frame, MTU>,
+        Frame: CanFrame<MTU>,
+        const MTU: usize,
+    >(
+        &self,
+        transmitter: &mut Tx,
+        transfer: &Transfer<TRANSFER_CAPACITY>,
+    ) -> Option<Result<(), ServiceError<Tx::Error>>> {
+        match transfer.kind {
+            SessionKind::Request(request)
+                if request.destination_node_id() == self.node_id
+                    && request.service_id() == S::SERVICE_ID =>
+            {
+                Some(self.respond(transmitter, transfer, request))
+            }
+            _ => None,
+        }
+    }
+
+    fn respond<
+        const TRANSFER_CAPACITY: usize,
+        Tx: Transmitter<Frame, MTU>,
+        Frame: CanFrame<MTU>,
+        const MTU: usize,
+    >(
+        &self,
+        transmitter: &mut Tx,
+        transfer: &Transfer<TRANSFER_CAPACITY>,
+        request: Request,
+    ) -> Result<(), ServiceError<Tx::Error>> {
+        let value: S::Request =
+            deserialize_from_slice(&transfer.payload).map_err(ServiceError::Deserialization)?;
+        let metadata = Metadata {
+            remote_node_id: request.source_node_id(),
+            transfer_id: transfer.transfer_id,
+            timestamp: transfer.timestamp,
+        };
+
+        let response = match (self.handler)(&value, metadata) {
+            Some(response) => response,
+            None => return Ok(()),
+        };
+
+        let mut buffer = [0u8; CAPACITY];
+        let length =
+            serialize_to_slice(&response, &mut buffer).map_err(ServiceError::Serialization)?;
+
+        send_with_transfer_id(
+            transmitter,
+            &buffer[..length],
+            SessionKind::Response(request),
+            self.priority,
+            transfer.transfer_id,
+        )
+        .map_err(ServiceError::Transmission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsdl::DeserializationError;
+    use crate::presentation::service::tests::Successor;
+    use crate::presentation::Client;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::node_id::strategy::node_id;
+    use crate::session_id::ServiceId;
+    use crate::tail_byte::TransferId;
+    use crate::tests::{ClassicFrame, TxRxGlue};
+    use crate::tx::stream_transmitter::StreamTransmitter;
+    use crate::CLASSIC_MTU;
+    use heapless::Vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_client_receives_the_response_of_the_server_it_called(client_node in node_id(), server_node in node_id(), value in 0..u32::MAX) {
+            let mut requests = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (requests_producer, mut requests_consumer) = requests.split();
+            let mut client_transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer: requests_producer });
+
+            let mut responses = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (responses_producer, mut responses_consumer) = responses.split();
+            let mut server_transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer: responses_producer });
+
+            let mut client = Client::<Successor, 4>::new(client_node, TransferPriority::Nominal);
+            let server = Server::<Successor, _, 8>::new(server_node, TransferPriority::Nominal, |request: &u32, _| Some(*request as u64 + 1));
+
+            let transfer_id = client.call(&mut client_transmitter, server_node, &value).unwrap();
+            server.serve(&mut server_transmitter, &requests_consumer.next().unwrap()).unwrap().unwrap();
+
+            let (response, metadata) = client.accept(&responses_consumer.next().unwrap()).unwrap().unwrap();
+            prop_assert_eq!(response, value as u64 + 1);
+            prop_assert_eq!(metadata.remote_node_id, server_node);
+            prop_assert_eq!(metadata.transfer_id, transfer_id);
+        }
+
+        #[test]
+        fn a_server_ignores_the_requests_addressed_to_other_nodes(client_node in node_id(), server_node in node_id(), other_node in node_id()) {
+            prop_assume!(server_node != other_node);
+
+            let mut requests = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (requests_producer, mut requests_consumer) = requests.split();
+            let mut client_transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer: requests_producer });
+
+            let mut responses = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (responses_producer, _) = responses.split();
+            let mut server_transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer: responses_producer });
+
+            let mut client = Client::<Successor, 4>::new(client_node, TransferPriority::Nominal);
+            let server = Server::<Successor, _, 8>::new(server_node, TransferPriority::Nominal, |request: &u32, _| Some(*request as u64 + 1));
+
+            client.call(&mut client_transmitter, other_node, &0).unwrap();
+
+            prop_assert!(server.serve(&mut server_transmitter, &requests_consumer.next().unwrap()).is_none());
+        }
+    }
+
+    struct Echo;
+
+    impl Service for Echo {
+        type Request = Vec<u8, 2>;
+        type Response = Vec<u8, 2>;
+
+        const SERVICE_ID: ServiceId = ServiceId::from_const(7);
+    }
+
+    #[test]
+    fn a_request_that_cannot_be_deserialized_is_reported_as_a_deserialization_error() {
+        let mut responses = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (responses_producer, _) = responses.split();
+        let mut server_transmitter =
+            StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue {
+                rx_producer: responses_producer,
+            });
+
+        let server = Server::<Echo, _, 3>::new(
+            NodeId::new(),
+            TransferPriority::Nominal,
+            |request: &Vec<u8, 2>, _| Some(request.clone()),
+        );
+
+        let mut payload = Vec::<u8, 512>::new();
+        payload.push(5).unwrap();
+        let transfer = Transfer::new(
+            payload,
+            SessionKind::Request(Request::new(NodeId::new(), NodeId::new(), Echo::SERVICE_ID)),
+            TransferId::new(),
+            None,
+        );
+
+        assert!(matches!(
+            server.serve(&mut server_transmitter, &transfer),
+            Some(Err(ServiceError::Deserialization(
+                DeserializationError::ArrayLengthOutOfBounds(5)
+            )))
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    dsdl::{Deserialize, Serialize},
+    session_id::{NodeId, ServiceId},
+    tail_byte::TransferId,
+    timestamp::Timestamp,
+};
+
+/// Describes a service by the types of its request and response and by the
+/// port on which it is served.
+///
+/// The code generated from DSDL implements this trait for every service
+/// definition that has a fixed port ID.
+pub trait Service {
+    type Request: Serialize + Deserialize;
+    type Response: Serialize + Deserialize;
+
+    const SERVICE_ID: ServiceId;
+}
+
+/// The metadata of a received request or response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// The node that sent the transfer, that is, the client for a request and
+    /// the server for a response.
+    pub remote_node_id: NodeId,
+    pub transfer_id: TransferId,
+    pub timestamp: Option<Timestamp>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A service that answers with the successor of the number it receives.
+    pub(crate) struct Successor;
+
+    impl Service for Successor {
+        type Request = u32;
+        type Response = u64;
+
+        const SERVICE_ID: ServiceId = ServiceId::from_const(42);
+    }
+}
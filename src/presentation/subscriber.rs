@@ -0,0 +1,121 @@
+use core::marker::PhantomData;
+
+use crate::{
+    dsdl::{deserialize_from_slice, DeserializationError, Deserialize},
+    rx::transfer::Transfer,
+    session_id::{NodeId, SessionKind, SubjectId},
+    tail_byte::TransferId,
+    timestamp::Timestamp,
+};
+
+/// A value received on a subject, together with the metadata of the transfer
+/// that carried it.
+#[derive(Debug)]
+pub struct Message<T> {
+    pub value: T,
+    pub source_node_id: NodeId,
+    pub transfer_id: TransferId,
+    pub timestamp: Option<Timestamp>,
+}
+
+/// Receives values of type `T` from the transfers of a subject.
+pub struct Subscriber<T: Deserialize> {
+    subject_id: SubjectId,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Deserialize> Subscriber<T> {
+    pub fn new(subject_id: SubjectId) -> Self {
+        Self {
+            subject_id,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn subject_id(&self) -> SubjectId {
+        self.subject_id
+    }
+
+    /// Deserializes `transfer` if it is a message published on the subject of
+    /// the subscriber, returning `None` for any other transfer.
+    pub fn accept<const CAPACITY: usize>(
+        &self,
+        transfer: &Transfer<CAPACITY>,
+    ) -> Option<Result<Message<T>, DeserializationError>> {
+        match transfer.kind {
+            SessionKind::Message {
+                source_node_id,
+                subject_id,
+            } if subject_id == self.subject_id => Some(
+                deserialize_from_slice(&transfer.payload).map(|value| Message {
+                    value,
+                    source_node_id,
+                    transfer_id: transfer.transfer_id,
+                    timestamp: transfer.timestamp,
+                }),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Yields the messages of the subject found in `transfers`, such as the
+    /// ones produced by an `RxConsumer`, discarding every other transfer.
+    pub fn receive<'s, const CAPACITY: usize, I: Iterator<Item = Transfer<CAPACITY>> + 's>(
+        &'s self,
+        transfers: I,
+    ) -> impl Iterator<Item = Result<Message<T>, DeserializationError>> + 's {
+        transfers.filter_map(move |transfer| self.accept(&transfer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::Publisher;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::node_id::strategy::node_id;
+    use crate::session_id::subject_id::strategy::subject_id;
+    use crate::session_id::TransferPriority;
+    use crate::tests::{ClassicFrame, TxRxGlue};
+    use crate::tx::stream_transmitter::StreamTransmitter;
+    use crate::CLASSIC_MTU;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_subscriber_receives_the_values_published_on_its_subject(source in node_id(), subject in subject_id(), value in proptest::num::u64::ANY) {
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (rx_producer, rx_consumer) = rx_network.split();
+            let mut transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+
+            let mut publisher = Publisher::<u64, 8>::new(source, subject, TransferPriority::Nominal);
+            publisher.publish(&mut transmitter, &value).unwrap();
+
+            let subscriber = Subscriber::<u64>::new(subject);
+            let message = subscriber.receive(rx_consumer).next().unwrap().unwrap();
+
+            prop_assert_eq!(message.value, value);
+            prop_assert_eq!(message.source_node_id, source);
+            prop_assert_eq!(message.transfer_id, TransferId::new());
+        }
+
+        #[test]
+        fn a_subscriber_ignores_the_values_published_on_other_subjects(source in node_id(), subject in subject_id(), other_subject in subject_id()) {
+            prop_assume!(subject != other_subject);
+
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (rx_producer, rx_consumer) = rx_network.split();
+            let mut transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+
+            let mut publisher = Publisher::<u8, 1>::new(source, other_subject, TransferPriority::Nominal);
+            publisher.publish(&mut transmitter, &0).unwrap();
+
+            let subscriber = Subscriber::<u8>::new(subject);
+
+            prop_assert!(subscriber.receive(rx_consumer).next().is_none());
+        }
+    }
+}
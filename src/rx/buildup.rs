@@ -1,10 +1,11 @@
 use core::{convert::TryInto, marker::PhantomData};
 use crc_any::CRCu16;
-use heapless::{ArrayLength, Vec};
+use heapless::Vec;
 
 use crate::{
     session_id::{MessageSessionId, SessionId, SessionKind},
     tail_byte::{PayloadKind, TailByte, TransferId},
+    timestamp::Timestamp,
     CanFrame,
 };
 
@@ -19,11 +20,41 @@ pub enum Error<Frame: CanFrame<MTU>, const MTU: usize> {
     WrongTypeOfFrame(BuildupState, PayloadKind, Frame),
     CorruptedTailByte(TailByte),
     WrongCRC(u16, u16),
+    /// The frame has no tail byte or claims more bytes than the MTU.
+    InvalidLength(usize),
+    /// The transfer ended before the two bytes of its CRC.
+    MissingCrc,
 }
 
 #[derive(Debug)]
 pub struct NotReady {}
 
+#[derive(Debug)]
+pub struct OutOfSpace {}
+
+/// The storage that the payload of a transfer is built up into.
+pub trait PayloadBuffer {
+    fn extend_from_slice(&mut self, data: &[u8]) -> Result<(), OutOfSpace>;
+
+    fn pop(&mut self) -> Option<u8>;
+
+    fn as_slice(&self) -> &[u8];
+}
+
+impl<const CAPACITY: usize> PayloadBuffer for Vec<u8, CAPACITY> {
+    fn extend_from_slice(&mut self, data: &[u8]) -> Result<(), OutOfSpace> {
+        Vec::extend_from_slice(self, data).map_err(|_| OutOfSpace {})
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        Vec::pop(self)
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum BuildupState {
     Initializing,
@@ -39,34 +70,40 @@ pub enum BuildupKind {
     Response,
 }
 
-pub struct Buildup<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize> {
-    payload: Vec<u8, Capacity>,
+pub struct Buildup<Frame: CanFrame<MTU>, Payload: PayloadBuffer, const MTU: usize> {
+    payload: Payload,
     session_id: SessionId,
     state: BuildupState,
     tail_byte: TailByte,
+    timestamp: Option<Timestamp>,
     _frame_marker: PhantomData<Frame>,
 }
 
-impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize> Default
-    for Buildup<Frame, Capacity, MTU>
+impl<Frame: CanFrame<MTU>, Payload: PayloadBuffer + Default, const MTU: usize> Default
+    for Buildup<Frame, Payload, MTU>
 {
     fn default() -> Self {
+        Self::new(Payload::default())
+    }
+}
+
+impl<Frame: CanFrame<MTU>, Payload: PayloadBuffer, const MTU: usize> Buildup<Frame, Payload, MTU> {
+    /// Creates a buildup that saves the payload into `payload`, which is
+    /// expected to be empty.
+    pub fn new(payload: Payload) -> Self {
         Self {
-            payload: Vec::new(),
+            payload,
             // TODO: Apart from documenting this the unintuitiveness of this
             // temporary value consider removing it entirely. Option is
             // problably a good way here.
             session_id: SessionId::Message(MessageSessionId::new()),
             state: BuildupState::Initializing,
             tail_byte: TailByte::new(),
+            timestamp: None,
             _frame_marker: PhantomData,
         }
     }
-}
 
-impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
-    Buildup<Frame, Capacity, MTU>
-{
     pub fn push(&mut self, frame: Frame) -> Result<BuildupState, Error<Frame, MTU>> {
         let session_id = SessionId::from(frame.id());
         session_id
@@ -90,20 +127,25 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
         frame: Frame,
         session_id: SessionId,
     ) -> Result<BuildupState, Error<Frame, MTU>> {
-        let (data, tail_byte) = TailByte::split_from(frame.payload());
+        let timestamp = frame.timestamp();
+        let (data, tail_byte) = match TailByte::split_from(frame.payload()) {
+            Some(split) => split,
+            None => return Err(Error::InvalidLength(frame.payload().1)),
+        };
         let payload_kind = tail_byte.payload_kind();
 
         match (self.state, payload_kind) {
             (BuildupState::Initializing, PayloadKind::StartOfMultiFrame) => {
-                self.populate_first_frame(data, session_id)?;
+                self.populate_first_frame(data, session_id, timestamp)?;
 
                 self.tail_byte = tail_byte;
 
                 Ok(BuildupState::MultiFrame)
             }
             (BuildupState::Initializing, PayloadKind::SingleFrame) => {
-                self.ensure_no_missing_frames(tail_byte.get_transfer_id())?;
-                self.populate_first_frame(data, session_id)?;
+                self.populate_first_frame(data, session_id, timestamp)?;
+
+                self.tail_byte = tail_byte;
 
                 Ok(BuildupState::Closed)
             }
@@ -124,8 +166,12 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
 
                 let mut crc_bytes = [0u8; 2];
                 match data.len() {
+                    0 => {
+                        crc_bytes[1] = self.payload.pop().ok_or(Error::MissingCrc)?;
+                        crc_bytes[0] = self.payload.pop().ok_or(Error::MissingCrc)?;
+                    }
                     1 => {
-                        crc_bytes[0] = self.payload.pop().unwrap();
+                        crc_bytes[0] = self.payload.pop().ok_or(Error::MissingCrc)?;
                         crc_bytes[1] = data[0];
                     }
                     2 => {
@@ -148,6 +194,29 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
         }
     }
 
+    /// Whether the transfer is still waiting for frames while more than
+    /// `timeout` microseconds have passed at `now` since its first frame.
+    /// Transfers whose frames have no timestamp never time out.
+    pub(crate) fn is_timed_out(&self, now: Option<Timestamp>, timeout: u64) -> bool {
+        match (self.state, self.timestamp, now) {
+            (BuildupState::MultiFrame, Some(start), Some(now)) => {
+                now.saturating_duration_since(start) > timeout
+            }
+            _ => false,
+        }
+    }
+
+    /// Gives back the payload storage along with the session and transfer ID
+    /// of the transfer, whether or not it was completed.
+    pub(crate) fn into_parts(self) -> (Payload, SessionKind, TransferId, Option<Timestamp>) {
+        (
+            self.payload,
+            SessionKind::from(self.session_id),
+            self.tail_byte.get_transfer_id(),
+            self.timestamp,
+        )
+    }
+
     fn save_payload(&mut self, payload: &[u8]) -> Result<(), Error<Frame, MTU>> {
         self.payload
             .extend_from_slice(payload)
@@ -158,8 +227,10 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
         &mut self,
         payload: &[u8],
         session_id: SessionId,
+        timestamp: Option<Timestamp>,
     ) -> Result<(), Error<Frame, MTU>> {
         self.session_id = session_id;
+        self.timestamp = timestamp;
         self.save_payload(payload)
     }
 
@@ -195,7 +266,7 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
 
     fn ensure_payload_integrity(&self, crc: u16) -> Result<(), Error<Frame, MTU>> {
         let mut own_crc = CRCu16::crc16ccitt_false();
-        own_crc.digest(&self.payload);
+        own_crc.digest(self.payload.as_slice());
 
         (own_crc.get_crc() == crc)
             .then(|| ())
@@ -203,17 +274,18 @@ impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize>
     }
 }
 
-impl<Frame: CanFrame<MTU>, Capacity: ArrayLength<u8>, const MTU: usize> TryInto<Transfer<Capacity>>
-    for Buildup<Frame, Capacity, MTU>
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> TryInto<Transfer<CAPACITY>>
+    for Buildup<Frame, Vec<u8, CAPACITY>, MTU>
 {
     type Error = NotReady;
 
-    fn try_into(self) -> Result<Transfer<Capacity>, Self::Error> {
+    fn try_into(self) -> Result<Transfer<CAPACITY>, Self::Error> {
         match self.state {
-            BuildupState::Closed => Ok(Transfer::new(
-                self.payload,
-                SessionKind::from(self.session_id),
-            )),
+            BuildupState::Closed => {
+                let (payload, kind, transfer_id, timestamp) = self.into_parts();
+
+                Ok(Transfer::new(payload, kind, transfer_id, timestamp))
+            }
             _ => Err(NotReady {}),
         }
     }
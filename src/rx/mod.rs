@@ -1,3 +1,7 @@
 pub mod buildup;
+pub mod redundant_receiver;
 pub mod rx_network;
+pub mod slab_pool;
 pub mod transfer;
+#[cfg(feature = "async")]
+pub mod transfer_stream;
@@ -0,0 +1,418 @@
+use super::{
+    buildup::Buildup,
+    rx_network::{receive_frame, RxError},
+    transfer::Transfer,
+};
+use crate::{
+    session_id::SessionKind,
+    statistics::{ends_transfer, RxStatistics},
+    tail_byte::TransferId,
+    timestamp::Timestamp,
+    CanFrame,
+};
+use heapless::{spsc::Producer, Vec};
+
+/// The transfer-ID timeout recommended by the Cyphal specification, in
+/// microseconds.
+pub const DEFAULT_TRANSFER_ID_TIMEOUT: u64 = 2_000_000;
+
+/// What became of a frame received by a [RedundantRxProducer].
+enum Delivery {
+    /// The transfer of the frame awaits more frames.
+    Pending,
+    Delivered,
+    /// The frame completed a transfer already delivered by another
+    /// interface.
+    Duplicate,
+}
+
+/// The last transfer delivered in a session.
+#[derive(Debug, Copy, Clone)]
+struct Delivered {
+    kind: SessionKind,
+    transfer_id: TransferId,
+    timestamp: Option<Timestamp>,
+    interface: usize,
+    /// The number of transfers delivered by the producer when this one was,
+    /// which tells the least recently used session.
+    delivery: u64,
+}
+
+/// Receives the frames of `N` redundant interfaces into a single queue of
+/// transfers.
+///
+/// Each interface rebuilds its own transfers. As recommended by the Cyphal
+/// specification, each session sticks to the interface that delivered its
+/// last transfer: a transfer from that interface is new when its transfer ID
+/// differs from the last one, while the transfers completed by the other
+/// interfaces are discarded as duplicates until the transfer-ID timeout has
+/// elapsed since the last delivered transfer. The timeout is measured with
+/// the timestamps of the frames, so a session whose frames have none never
+/// switches to another interface.
+///
+/// The same timeout abandons the transfers of an interface whose next frame
+/// does not arrive in time.
+///
+/// The last transfer of up to `SESSIONS` sessions is remembered to detect the
+/// duplicates coming from the other interfaces. Beyond it, the least recently
+/// delivered session is forgotten, so that its next copies from the other
+/// interfaces would be delivered again: `SESSIONS` should cover every session
+/// active on the network within the transfer-ID timeout.
+pub struct RedundantRxProducer<
+    'a,
+    Frame: CanFrame<MTU>,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
+    const MTU: usize,
+    const N: usize,
+    const SESSIONS: usize,
+> {
+    producer: Producer<'a, Transfer<TRANSFER_CAPACITY>, CAPACITY>,
+    buildups: [Option<Buildup<Frame, Vec<u8, TRANSFER_CAPACITY>, MTU>>; N],
+    delivered: [Option<Delivered>; SESSIONS],
+    deliveries: u64,
+    transfer_id_timeout: u64,
+    statistics: [RxStatistics; N],
+}
+
+impl<
+        'a,
+        Frame: CanFrame<MTU>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
+        const MTU: usize,
+        const N: usize,
+        const SESSIONS: usize,
+    > RedundantRxProducer<'a, Frame, CAPACITY, TRANSFER_CAPACITY, MTU, N, SESSIONS>
+{
+    pub(super) fn new(producer: Producer<'a, Transfer<TRANSFER_CAPACITY>, CAPACITY>) -> Self {
+        Self {
+            producer,
+            buildups: [(); N].map(|_| None),
+            delivered: [None; SESSIONS],
+            deliveries: 0,
+            transfer_id_timeout: DEFAULT_TRANSFER_ID_TIMEOUT,
+            statistics: [RxStatistics::default(); N],
+        }
+    }
+
+    /// Sets the time, in microseconds, after which a session accepts the
+    /// transfers of another interface than the one of its last transfer, and
+    /// after which a transfer still missing frames is abandoned.
+    pub fn set_transfer_id_timeout(&mut self, microseconds: u64) {
+        self.transfer_id_timeout = microseconds;
+    }
+
+    /// The counters of each interface, in which the transfers discarded as
+    /// duplicates are not counted as transfers.
+    pub fn statistics(&self) -> &[RxStatistics; N] {
+        &self.statistics
+    }
+
+    /// Receives a frame from the interface with index `interface`.
+    pub fn receive(&mut self, interface: usize, frame: Frame) -> Result<(), RxError<Frame, MTU>> {
+        if interface >= N {
+            return Err(RxError::UnknownInterface(interface));
+        }
+
+        let ends_transfer = ends_transfer(&frame);
+        let (expired, pushed) = receive_frame(
+            &mut self.buildups[interface],
+            frame,
+            self.transfer_id_timeout,
+        );
+        if expired.is_some() {
+            self.statistics[interface].timeouts += 1;
+        }
+
+        let delivery = pushed.into_transfer().and_then(|transfer| match transfer {
+            Some(transfer) => self.deliver(interface, transfer),
+            None => Ok(Delivery::Pending),
+        });
+        match delivery {
+            Ok(Delivery::Duplicate) => {
+                self.statistics[interface].record_duplicate();
+
+                Ok(())
+            }
+            result => {
+                let result = result.map(|_| ());
+                self.statistics[interface].record(ends_transfer, &result);
+
+                result
+            }
+        }
+    }
+
+    fn deliver(
+        &mut self,
+        interface: usize,
+        transfer: Transfer<TRANSFER_CAPACITY>,
+    ) -> Result<Delivery, RxError<Frame, MTU>> {
+        let session = self.delivered.iter().position(
+            |delivered| matches!(delivered, Some(delivered) if delivered.kind == transfer.kind),
+        );
+
+        if let Some(last) = session.and_then(|index| self.delivered[index]) {
+            let is_timed_out = match (last.timestamp, transfer.timestamp) {
+                (Some(last), Some(now)) => {
+                    now.saturating_duration_since(last) > self.transfer_id_timeout
+                }
+                _ => false,
+            };
+            let is_new = last.interface == interface && last.transfer_id != transfer.transfer_id;
+
+            if !is_timed_out && !is_new {
+                return Ok(Delivery::Duplicate);
+            }
+        }
+
+        let delivered = Delivered {
+            kind: transfer.kind,
+            transfer_id: transfer.transfer_id,
+            timestamp: transfer.timestamp,
+            interface,
+            delivery: self.deliveries,
+        };
+        self.producer
+            .enqueue(transfer)
+            .map_err(|_| RxError::OutOfSpace)?;
+        self.deliveries += 1;
+
+        let index = session
+            .or_else(|| self.delivered.iter().position(Option::is_none))
+            .or_else(|| self.least_recently_delivered_session());
+        if let Some(index) = index {
+            self.delivered[index] = Some(delivered);
+        }
+
+        Ok(Delivery::Delivered)
+    }
+
+    /// The index of the session whose last transfer was delivered before the
+    /// last transfer of every other session, if any session is remembered.
+    fn least_recently_delivered_session(&self) -> Option<usize> {
+        (0..SESSIONS).min_by_key(|index| self.delivered[*index].map(|delivered| delivered.delivery))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::session_id::{can_id_for_session_kind, NodeId, SubjectId, TransferPriority};
+    use crate::tests::ClassicFrame;
+    use crate::tx::breakdown::Breakdown;
+    use crate::CLASSIC_MTU;
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_transfer_received_on_every_interface_is_delivered_once(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, mut consumer) = network.split_redundant::<3, 16>();
+            let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+
+            for interface in 0..3 {
+                for frame in Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, can_id) {
+                    producer.receive(interface, frame).unwrap();
+                }
+            }
+
+            let transfer = consumer.next().unwrap();
+            prop_assert_eq!(AsRef::<[u8]>::as_ref(&transfer.payload), payload.as_slice());
+            prop_assert!(consumer.next().is_none());
+
+            let statistics = producer.statistics();
+            prop_assert_eq!(statistics[0].transfers, 1);
+            prop_assert_eq!(statistics[1].duplicates, 1);
+            prop_assert_eq!(statistics[2].duplicates, 1);
+        }
+
+        #[test]
+        fn transfers_with_different_transfer_ids_are_all_delivered(kind in session_kind(), count in 1..32u8) {
+            let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, consumer) = network.split_redundant::<2, 16>();
+            let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+
+            for id in 0..count {
+                let transfer_id = TransferId::try_from(id).unwrap();
+                for interface in 0..2 {
+                    for frame in Breakdown::<ClassicFrame, CLASSIC_MTU>::with_transfer_id(&[id], can_id, transfer_id) {
+                        producer.receive(interface, frame).unwrap();
+                    }
+                }
+            }
+
+            prop_assert_eq!(consumer.count(), count as usize);
+        }
+
+        #[test]
+        fn frames_of_the_same_transfer_can_be_interleaved_between_interfaces(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, mut consumer) = network.split_redundant::<2, 16>();
+            let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+
+            let first = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, can_id);
+            let second = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, can_id);
+            for (first_frame, second_frame) in first.zip(second) {
+                producer.receive(1, second_frame).unwrap();
+                producer.receive(0, first_frame).unwrap();
+            }
+
+            let transfer = consumer.next().unwrap();
+            prop_assert_eq!(AsRef::<[u8]>::as_ref(&transfer.payload), payload.as_slice());
+            prop_assert!(consumer.next().is_none());
+        }
+    }
+
+    /// Receives a single-frame transfer on `interface`, its frame being
+    /// timestamped at `micros`.
+    fn receive_at<const N: usize, const SESSIONS: usize>(
+        producer: &mut RedundantRxProducer<'_, ClassicFrame, 64, 512, CLASSIC_MTU, N, SESSIONS>,
+        interface: usize,
+        transfer_id: u8,
+        micros: u64,
+    ) {
+        let kind = SessionKind::Message {
+            source_node_id: NodeId::try_from(42).unwrap(),
+            subject_id: SubjectId::try_from(7509).unwrap(),
+        };
+        let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+        let transfer_id = TransferId::try_from(transfer_id).unwrap();
+
+        for frame in
+            Breakdown::<ClassicFrame, CLASSIC_MTU>::with_transfer_id(&[1], can_id, transfer_id)
+        {
+            producer
+                .receive(
+                    interface,
+                    frame.with_timestamp(Timestamp::from_micros(micros)),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn more_transfers_than_remembered_sessions_in_flight_between_interfaces_are_delivered_once() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = network.split_redundant::<2, 16>();
+
+        for transfer_id in 0..20 {
+            receive_at(&mut producer, 0, transfer_id, 1000 * transfer_id as u64);
+        }
+        for transfer_id in 0..20 {
+            receive_at(
+                &mut producer,
+                1,
+                transfer_id,
+                1000 * transfer_id as u64 + 500,
+            );
+        }
+
+        assert_eq!(consumer.count(), 20);
+        assert_eq!(producer.statistics()[1].duplicates, 20);
+    }
+
+    #[test]
+    fn the_least_recently_delivered_session_is_forgotten_first() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = network.split_redundant::<2, 2>();
+        let receive = |producer: &mut RedundantRxProducer<'_, _, 64, 512, CLASSIC_MTU, 2, 2>,
+                       interface,
+                       subject| {
+            let kind = SessionKind::Message {
+                source_node_id: NodeId::try_from(42).unwrap(),
+                subject_id: SubjectId::try_from(subject).unwrap(),
+            };
+            let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+
+            for frame in Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&[1], can_id) {
+                producer.receive(interface, frame).unwrap();
+            }
+        };
+
+        // The frames have no timestamps, so only the order of the deliveries
+        // tells which session to forget.
+        for subject in 1..=4 {
+            receive(&mut producer, 0, subject);
+        }
+        for subject in 3..=4 {
+            receive(&mut producer, 1, subject);
+        }
+
+        assert_eq!(consumer.count(), 4);
+        assert_eq!(producer.statistics()[1].duplicates, 2);
+    }
+
+    #[test]
+    fn a_node_restarting_from_transfer_id_zero_is_received() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = network.split_redundant::<2, 16>();
+
+        receive_at(&mut producer, 0, 5, 0);
+        receive_at(&mut producer, 1, 5, 100);
+        receive_at(&mut producer, 0, 0, 10_000);
+        receive_at(&mut producer, 1, 0, 10_100);
+
+        assert_eq!(consumer.count(), 2);
+    }
+
+    #[test]
+    fn another_interface_is_used_once_the_transfer_id_timeout_has_elapsed() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = network.split_redundant::<2, 16>();
+
+        receive_at(&mut producer, 0, 5, 0);
+        receive_at(&mut producer, 1, 6, DEFAULT_TRANSFER_ID_TIMEOUT);
+        receive_at(&mut producer, 1, 6, DEFAULT_TRANSFER_ID_TIMEOUT + 1);
+        receive_at(&mut producer, 1, 7, DEFAULT_TRANSFER_ID_TIMEOUT + 2);
+        receive_at(&mut producer, 0, 7, DEFAULT_TRANSFER_ID_TIMEOUT + 3);
+
+        assert_eq!(consumer.count(), 3);
+        assert_eq!(producer.statistics()[1].duplicates, 1);
+        assert_eq!(producer.statistics()[0].duplicates, 1);
+    }
+
+    #[test]
+    fn a_transfer_whose_frames_stop_arriving_on_an_interface_times_out() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, consumer) = network.split_redundant::<2, 16>();
+        let can_id = can_id_for_session_kind(
+            SessionKind::Message {
+                source_node_id: NodeId::try_from(42).unwrap(),
+                subject_id: SubjectId::try_from(7509).unwrap(),
+            },
+            TransferPriority::Nominal,
+        );
+        let start = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&[0; 20], can_id)
+            .next()
+            .unwrap();
+
+        producer
+            .receive(0, start.with_timestamp(Timestamp::from_micros(0)))
+            .unwrap();
+        receive_at(&mut producer, 0, 1, DEFAULT_TRANSFER_ID_TIMEOUT + 1);
+
+        assert_eq!(consumer.count(), 1);
+        assert_eq!(producer.statistics()[0].timeouts, 1);
+        assert_eq!(producer.statistics()[1].timeouts, 0);
+    }
+
+    #[test]
+    fn receiving_from_an_unknown_interface_is_an_error() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = network.split_redundant::<2, 16>();
+
+        assert!(matches!(
+            producer.receive(2, ClassicFrame::from((0, [0; CLASSIC_MTU], 1))),
+            Err(RxError::UnknownInterface(2))
+        ));
+    }
+}
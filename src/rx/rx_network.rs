@@ -1,58 +1,171 @@
 use core::{convert::TryInto, marker::PhantomData};
 
 use super::{
-    buildup::{self, Buildup, BuildupState},
+    buildup::{self, Buildup, BuildupState, PayloadBuffer},
+    redundant_receiver::{RedundantRxProducer, DEFAULT_TRANSFER_ID_TIMEOUT},
     transfer::Transfer,
 };
-use crate::CanFrame;
+use crate::{
+    session_id::{SessionId, SessionKind},
+    statistics::{ends_transfer, RxStatistics, SessionStatistics},
+    CanFrame,
+};
 use heapless::spsc::{Consumer, Producer, Queue};
-use heapless::ArrayLength;
+use heapless::Vec;
 
 #[derive(Debug)]
 pub enum RxError<Frame: CanFrame<MTU>, const MTU: usize> {
     OutOfSpace,
     ZeroLengthFrame,
+    /// The frame claims more bytes than the MTU.
+    FrameTooLong(usize),
     BuildupError(buildup::Error<Frame, MTU>),
+    UnknownInterface(usize),
+}
+
+/// Rejects the frames without a tail byte and those claiming more bytes than
+/// the MTU, before they reach a buildup.
+pub(crate) fn ensure_frame_length<Frame: CanFrame<MTU>, const MTU: usize>(
+    frame: &Frame,
+) -> Result<(), RxError<Frame, MTU>> {
+    match frame.payload().1 {
+        0 => Err(RxError::ZeroLengthFrame),
+        len if len > MTU => Err(RxError::FrameTooLong(len)),
+        _ => Ok(()),
+    }
+}
+
+/// Abandons the transfer built up in `buildup` when `frame` arrives more than
+/// `timeout` microseconds after its first frame, as the rest of its frames
+/// were lost. Returns the abandoned buildup.
+pub(crate) fn expire_buildup<Frame: CanFrame<MTU>, Payload: PayloadBuffer, const MTU: usize>(
+    buildup: &mut Option<Buildup<Frame, Payload, MTU>>,
+    frame: &Frame,
+    timeout: u64,
+) -> Option<Buildup<Frame, Payload, MTU>> {
+    let is_timed_out = buildup
+        .as_ref()
+        .is_some_and(|buildup| buildup.is_timed_out(frame.timestamp(), timeout));
+
+    is_timed_out.then(|| buildup.take().unwrap())
+}
+
+/// What became of a frame given to [push_frame].
+pub(crate) enum Pushed<Frame: CanFrame<MTU>, Payload: PayloadBuffer, const MTU: usize> {
+    /// The transfer of the frame is waiting for more frames.
+    Pending,
+    /// The frame completed the transfer, whose buildup is handed over.
+    Closed(Buildup<Frame, Payload, MTU>),
+    /// The frame was rejected, along with the buildup it was pushed to, if
+    /// one was started.
+    Failed(RxError<Frame, MTU>, Option<Buildup<Frame, Payload, MTU>>),
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize>
+    Pushed<Frame, Vec<u8, CAPACITY>, MTU>
+{
+    /// The transfer completed by the frame, if any.
+    pub(crate) fn into_transfer(self) -> Result<Option<Transfer<CAPACITY>>, RxError<Frame, MTU>> {
+        match self {
+            Pushed::Pending => Ok(None),
+            Pushed::Closed(buildup) => Ok(Some(buildup.try_into().unwrap())),
+            Pushed::Failed(err, _) => Err(err),
+        }
+    }
+}
+
+/// Pushes `frame` to the transfer built up in `buildup`, starting a transfer
+/// into the payload given by `new_payload` when there is none. The frame is
+/// rejected with [RxError::OutOfSpace] when no payload is given.
+pub(crate) fn push_frame<Frame: CanFrame<MTU>, Payload: PayloadBuffer, const MTU: usize>(
+    buildup: &mut Option<Buildup<Frame, Payload, MTU>>,
+    frame: Frame,
+    new_payload: impl FnOnce() -> Option<Payload>,
+) -> Pushed<Frame, Payload, MTU> {
+    if let Err(err) = ensure_frame_length(&frame) {
+        return Pushed::Failed(err, None);
+    }
+
+    if buildup.is_none() {
+        match new_payload() {
+            Some(payload) => *buildup = Some(Buildup::new(payload)),
+            None => return Pushed::Failed(RxError::OutOfSpace, None),
+        }
+    }
+
+    match buildup.as_mut().unwrap().push(frame) {
+        Ok(BuildupState::Closed) => Pushed::Closed(buildup.take().unwrap()),
+        Err(err) => Pushed::Failed(RxError::BuildupError(err), buildup.take()),
+        _ => Pushed::Pending,
+    }
+}
+
+/// Receives `frame` into `buildup`, first abandoning the transfer that the
+/// frame makes time out. Returns the session of the abandoned transfer, if
+/// any, along with what became of the frame.
+pub(crate) fn receive_frame<
+    Frame: CanFrame<MTU>,
+    Payload: PayloadBuffer + Default,
+    const MTU: usize,
+>(
+    buildup: &mut Option<Buildup<Frame, Payload, MTU>>,
+    frame: Frame,
+    timeout: u64,
+) -> (Option<SessionKind>, Pushed<Frame, Payload, MTU>) {
+    let expired = expire_buildup(buildup, &frame, timeout).map(|buildup| buildup.into_parts().1);
+
+    (
+        expired,
+        push_frame(buildup, frame, || Some(Payload::default())),
+    )
 }
 
 pub struct RxConsumer<
     'a,
     Frame: CanFrame<MTU>,
-    Capacity: ArrayLength<Transfer<TransferCapacity>>,
-    TransferCapacity: ArrayLength<u8>,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
     const MTU: usize,
 > {
-    consumer: Consumer<'a, Transfer<TransferCapacity>, Capacity>,
+    consumer: Consumer<'a, Transfer<TRANSFER_CAPACITY>, CAPACITY>,
     _frame_marker: PhantomData<Frame>,
 }
 
 pub struct RxProducer<
     'a,
     Frame: CanFrame<MTU>,
-    Capacity: ArrayLength<Transfer<TransferCapacity>>,
-    TransferCapacity: ArrayLength<u8>,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
     const MTU: usize,
 > {
-    producer: Producer<'a, Transfer<TransferCapacity>, Capacity>,
-    buildup: Option<Buildup<Frame, TransferCapacity, MTU>>,
+    producer: Producer<'a, Transfer<TRANSFER_CAPACITY>, CAPACITY>,
+    buildup: Option<Buildup<Frame, Vec<u8, TRANSFER_CAPACITY>, MTU>>,
+    transfer_id_timeout: u64,
+    statistics: RxStatistics,
 }
 
+/// The queue of the received transfers, shared by an [RxProducer] and an
+/// [RxConsumer].
+///
+/// The queue holds up to `CAPACITY - 1` transfers of up to
+/// `TRANSFER_CAPACITY` bytes each, as a slot is kept free to tell a full
+/// queue from an empty one, so `CAPACITY` must be at least 2.
 pub struct RxNetwork<
     Frame: CanFrame<MTU>,
-    Capacity: ArrayLength<Transfer<TransferCapacity>>,
-    TransferCapacity: ArrayLength<u8>,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
     const MTU: usize,
 > {
-    queue: Queue<Transfer<TransferCapacity>, Capacity>,
+    queue: Queue<Transfer<TRANSFER_CAPACITY>, CAPACITY>,
     _frame_marker: PhantomData<Frame>,
 }
 
 impl<
         Frame: CanFrame<MTU>,
-        Capacity: ArrayLength<Transfer<TransferCapacity>>,
-        TransferCapacity: ArrayLength<u8>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
         const MTU: usize,
-    > Default for RxNetwork<Frame, Capacity, TransferCapacity, MTU>
+    > Default for RxNetwork<Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
 {
     fn default() -> Self {
         Self {
@@ -64,16 +177,16 @@ impl<
 
 impl<
         Frame: CanFrame<MTU>,
-        Capacity: ArrayLength<Transfer<TransferCapacity>>,
-        TransferCapacity: ArrayLength<u8>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
         const MTU: usize,
-    > RxNetwork<Frame, Capacity, TransferCapacity, MTU>
+    > RxNetwork<Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
 {
     pub fn split(
         &mut self,
     ) -> (
-        RxProducer<Frame, Capacity, TransferCapacity, MTU>,
-        RxConsumer<Frame, Capacity, TransferCapacity, MTU>,
+        RxProducer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
+        RxConsumer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
     ) {
         let (producer, consumer) = self.queue.split();
 
@@ -81,6 +194,8 @@ impl<
             RxProducer {
                 producer,
                 buildup: None,
+                transfer_id_timeout: DEFAULT_TRANSFER_ID_TIMEOUT,
+                statistics: RxStatistics::default(),
             },
             RxConsumer {
                 consumer,
@@ -88,16 +203,36 @@ impl<
             },
         )
     }
+
+    /// Splits the network into a producer that receives the frames of `N`
+    /// redundant interfaces and a consumer of the deduplicated transfers,
+    /// remembering the last transfer of up to `SESSIONS` sessions.
+    pub fn split_redundant<const N: usize, const SESSIONS: usize>(
+        &mut self,
+    ) -> (
+        RedundantRxProducer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU, N, SESSIONS>,
+        RxConsumer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
+    ) {
+        let (producer, consumer) = self.queue.split();
+
+        (
+            RedundantRxProducer::new(producer),
+            RxConsumer {
+                consumer,
+                _frame_marker: PhantomData,
+            },
+        )
+    }
 }
 
 impl<
         Frame: CanFrame<MTU>,
-        Capacity: ArrayLength<Transfer<TransferCapacity>>,
-        TransferCapacity: ArrayLength<u8>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
         const MTU: usize,
-    > Iterator for RxConsumer<'_, Frame, Capacity, TransferCapacity, MTU>
+    > Iterator for RxConsumer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
 {
-    type Item = Transfer<TransferCapacity>;
+    type Item = Transfer<TRANSFER_CAPACITY>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.consumer.dequeue()
@@ -106,32 +241,68 @@ impl<
 
 impl<
         Frame: CanFrame<MTU>,
-        Capacity: ArrayLength<Transfer<TransferCapacity>>,
-        TransferCapacity: ArrayLength<u8>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
         const MTU: usize,
-    > RxProducer<'_, Frame, Capacity, TransferCapacity, MTU>
+    > RxProducer<'_, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
 {
+    pub fn statistics(&self) -> &RxStatistics {
+        &self.statistics
+    }
+
+    /// Sets the time, in microseconds, after its first frame at which a
+    /// transfer still missing frames is abandoned and counted as a timeout.
+    pub fn set_transfer_id_timeout(&mut self, microseconds: u64) {
+        self.transfer_id_timeout = microseconds;
+    }
+
     pub fn receive(&mut self, frame: Frame) -> Result<(), RxError<Frame, MTU>> {
-        if let (_, 0) = frame.payload() {
-            return Err(RxError::ZeroLengthFrame);
+        self.receive_expiring(frame).1
+    }
+
+    /// Receives `frame`, also counting it in the statistics of its session.
+    pub fn receive_with_sessions<const N: usize>(
+        &mut self,
+        frame: Frame,
+        sessions: &mut SessionStatistics<N>,
+    ) -> Result<(), RxError<Frame, MTU>> {
+        let session_id = SessionId::from(frame.id());
+        let kind = session_id.is_valid().then(|| SessionKind::from(session_id));
+        let ends_transfer = ends_transfer(&frame);
+
+        let (expired, result) = self.receive_expiring(frame);
+        if let Some(expired) = expired {
+            sessions.record_timeout(expired);
+        }
+        if let Some(kind) = kind {
+            sessions.record(kind, ends_transfer, &result);
         }
 
-        match self
-            .buildup
-            .get_or_insert_with(Buildup::default)
-            .push(frame)
-        {
-            Ok(BuildupState::Closed) => self
+        result
+    }
+
+    /// Receives `frame`, returning the session of the transfer that it made
+    /// time out, if any, along with the result.
+    fn receive_expiring(
+        &mut self,
+        frame: Frame,
+    ) -> (Option<SessionKind>, Result<(), RxError<Frame, MTU>>) {
+        let ends_transfer = ends_transfer(&frame);
+        let (expired, pushed) = receive_frame(&mut self.buildup, frame, self.transfer_id_timeout);
+        if expired.is_some() {
+            self.statistics.timeouts += 1;
+        }
+
+        let result = pushed.into_transfer().and_then(|transfer| match transfer {
+            Some(transfer) => self
                 .producer
-                .enqueue(self.buildup.take().unwrap().try_into().unwrap())
+                .enqueue(transfer)
                 .map_err(|_| RxError::OutOfSpace),
-            Err(err) => {
-                self.buildup.take();
+            None => Ok(()),
+        });
+        self.statistics.record(ends_transfer, &result);
 
-                Err(RxError::BuildupError(err))
-            }
-            _ => Ok(()),
-        }
+        (expired, result)
     }
 }
 
@@ -140,11 +311,18 @@ pub mod tests {
     use super::*;
     use crate::tests::ClassicFrame;
     use crate::CLASSIC_MTU;
-    use heapless::consts::{U512, U64};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    /// A nominal heartbeat of node 42.
+    const HEARTBEAT_ID: u32 = 0x107D552A;
 
     #[test]
     fn receiving_a_frame_with_no_data_results_in_an_error() {
-        let mut network = RxNetwork::<ClassicFrame, U64, U512, CLASSIC_MTU>::default();
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
         let (mut producer, _) = network.split();
         let empty_payload: [u8; 8] = [0; 8];
 
@@ -152,4 +330,61 @@ pub mod tests {
             .receive(ClassicFrame::from((0, empty_payload, 0)))
             .is_err());
     }
+
+    #[test]
+    fn receiving_a_frame_longer_than_the_mtu_results_in_an_error() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = network.split();
+
+        assert!(matches!(
+            producer.receive(ClassicFrame::from((HEARTBEAT_ID, [0xE0; 8], 9))),
+            Err(RxError::FrameTooLong(9))
+        ));
+    }
+
+    #[test]
+    fn receiving_the_end_of_a_transfer_without_a_crc_results_in_an_error() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = network.split();
+        let mut start = [0; 8];
+        start[0] = 0xA0;
+        let mut end = [0; 8];
+        end[0] = 0x40;
+
+        producer
+            .receive(ClassicFrame::from((HEARTBEAT_ID, start, 1)))
+            .unwrap();
+        assert!(matches!(
+            producer.receive(ClassicFrame::from((HEARTBEAT_ID, end, 1))),
+            Err(RxError::BuildupError(buildup::Error::MissingCrc))
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn receiving_arbitrary_frames_never_panics(frames in vec((proptest::num::u32::ANY, proptest::array::uniform8(proptest::num::u8::ANY), 0..12usize), 0..50)) {
+            let mut network = RxNetwork::<ClassicFrame, 4, 64, CLASSIC_MTU>::default();
+            let (mut producer, mut consumer) = network.split();
+
+            for frame in frames {
+                let _ = producer.receive(ClassicFrame::from(frame));
+                consumer.next();
+            }
+        }
+    }
+
+    #[test]
+    fn a_single_frame_transfer_is_received_whatever_its_transfer_id() {
+        let mut network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, mut consumer) = network.split();
+        // A nominal heartbeat of node 42, with the tail byte of a single frame
+        // transfer with transfer ID 5.
+        let payload: [u8; 8] = [1, 2, 0xE5, 0, 0, 0, 0, 0];
+
+        producer
+            .receive(ClassicFrame::from((0x107D552A, payload, 3)))
+            .unwrap();
+
+        assert_eq!(&consumer.next().unwrap().payload[..], &[1, 2]);
+    }
 }
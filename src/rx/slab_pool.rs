@@ -0,0 +1,303 @@
+use super::{
+    buildup::{Buildup, OutOfSpace, PayloadBuffer},
+    redundant_receiver::DEFAULT_TRANSFER_ID_TIMEOUT,
+    rx_network::{expire_buildup, push_frame, Pushed, RxError},
+};
+use crate::{session_id::SessionKind, tail_byte::TransferId, timestamp::Timestamp, CanFrame};
+
+/// Caller-provided storage for the payloads of received transfers, made of
+/// `SLABS` slabs of `SLAB_SIZE` bytes each.
+///
+/// Each transfer is built up directly into a slab of its own, so the bytes
+/// of a frame are copied once and the payload is never moved afterwards.
+pub struct SlabPool<const SLAB_SIZE: usize, const SLABS: usize> {
+    slabs: [[u8; SLAB_SIZE]; SLABS],
+}
+
+impl<const SLAB_SIZE: usize, const SLABS: usize> Default for SlabPool<SLAB_SIZE, SLABS> {
+    fn default() -> Self {
+        Self {
+            slabs: [[0; SLAB_SIZE]; SLABS],
+        }
+    }
+}
+
+impl<const SLAB_SIZE: usize, const SLABS: usize> SlabPool<SLAB_SIZE, SLABS> {
+    /// Lends every slab of the pool to a receiver, for as long as the pool is
+    /// borrowed.
+    pub fn receiver<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+    ) -> SlabReceiver<'_, Frame, MTU, SLAB_SIZE, SLABS> {
+        let mut slabs = self.slabs.iter_mut();
+
+        SlabReceiver {
+            free: core::array::from_fn(|_| slabs.next()),
+            buildup: None,
+            transfer_id_timeout: DEFAULT_TRANSFER_ID_TIMEOUT,
+        }
+    }
+}
+
+struct Slab<'p, const SLAB_SIZE: usize> {
+    data: &'p mut [u8; SLAB_SIZE],
+    len: usize,
+}
+
+impl<const SLAB_SIZE: usize> PayloadBuffer for Slab<'_, SLAB_SIZE> {
+    fn extend_from_slice(&mut self, data: &[u8]) -> Result<(), OutOfSpace> {
+        let end = self.len + data.len();
+        self.data
+            .get_mut(self.len..end)
+            .ok_or(OutOfSpace {})?
+            .copy_from_slice(data);
+        self.len = end;
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        self.len = self.len.checked_sub(1)?;
+
+        Some(self.data[self.len])
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+/// A transfer whose payload lives in a slab of a [SlabPool].
+///
+/// The slab stays out of the pool until the transfer is handed back to the
+/// [SlabReceiver::release] of the receiver it came from.
+pub struct PooledTransfer<'p, const SLAB_SIZE: usize> {
+    slab: Slab<'p, SLAB_SIZE>,
+    pub kind: SessionKind,
+    pub transfer_id: TransferId,
+    /// The timestamp of the first frame of the transfer, if the frames were
+    /// timestamped.
+    pub timestamp: Option<Timestamp>,
+}
+
+impl<const SLAB_SIZE: usize> PooledTransfer<'_, SLAB_SIZE> {
+    pub fn payload(&self) -> &[u8] {
+        self.slab.as_slice()
+    }
+
+    /// Gives access to the payload for decoding it in place.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.slab.data[..self.slab.len]
+    }
+}
+
+/// Builds up received transfers into the slabs of a [SlabPool].
+///
+/// Unlike an [RxProducer], completed transfers are returned by
+/// [SlabReceiver::receive] instead of being moved through a queue.
+///
+/// [RxProducer]: super::rx_network::RxProducer
+pub struct SlabReceiver<
+    'p,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+    const SLAB_SIZE: usize,
+    const SLABS: usize,
+> {
+    free: [Option<&'p mut [u8; SLAB_SIZE]>; SLABS],
+    buildup: Option<Buildup<Frame, Slab<'p, SLAB_SIZE>, MTU>>,
+    transfer_id_timeout: u64,
+}
+
+impl<'p, Frame: CanFrame<MTU>, const MTU: usize, const SLAB_SIZE: usize, const SLABS: usize>
+    SlabReceiver<'p, Frame, MTU, SLAB_SIZE, SLABS>
+{
+    /// The number of slabs that are neither holding a transfer nor used by
+    /// the transfer being built up.
+    pub fn available(&self) -> usize {
+        self.free.iter().filter(|slab| slab.is_some()).count()
+    }
+
+    /// Sets the time, in microseconds, after its first frame at which a
+    /// transfer still missing frames is abandoned and its slab freed.
+    pub fn set_transfer_id_timeout(&mut self, microseconds: u64) {
+        self.transfer_id_timeout = microseconds;
+    }
+
+    /// Accepts the next frame, returning the transfer that it completes, if
+    /// any.
+    ///
+    /// A transfer is only started when a slab is available, otherwise the
+    /// frame is rejected with [RxError::OutOfSpace].
+    pub fn receive(
+        &mut self,
+        frame: Frame,
+    ) -> Result<Option<PooledTransfer<'p, SLAB_SIZE>>, RxError<Frame, MTU>> {
+        if let Some(expired) = expire_buildup(&mut self.buildup, &frame, self.transfer_id_timeout) {
+            self.put_back_buildup(expired);
+        }
+
+        let free = &mut self.free;
+        match push_frame(&mut self.buildup, frame, || {
+            let data = free.iter_mut().find_map(Option::take)?;
+
+            Some(Slab { data, len: 0 })
+        }) {
+            Pushed::Pending => Ok(None),
+            Pushed::Closed(buildup) => {
+                let (slab, kind, transfer_id, timestamp) = buildup.into_parts();
+
+                Ok(Some(PooledTransfer {
+                    slab,
+                    kind,
+                    transfer_id,
+                    timestamp,
+                }))
+            }
+            Pushed::Failed(err, buildup) => {
+                if let Some(buildup) = buildup {
+                    self.put_back_buildup(buildup);
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the slab of `transfer` to the pool.
+    ///
+    /// The transfer is given back when this receiver has no room for its
+    /// slab, as it was received from another receiver.
+    pub fn release(
+        &mut self,
+        transfer: PooledTransfer<'p, SLAB_SIZE>,
+    ) -> Result<(), PooledTransfer<'p, SLAB_SIZE>> {
+        let PooledTransfer {
+            slab,
+            kind,
+            transfer_id,
+            timestamp,
+        } = transfer;
+
+        self.put_back(slab).map_err(|slab| PooledTransfer {
+            slab,
+            kind,
+            transfer_id,
+            timestamp,
+        })
+    }
+
+    /// Returns the slab of an abandoned transfer to the free slabs.
+    fn put_back_buildup(&mut self, buildup: Buildup<Frame, Slab<'p, SLAB_SIZE>, MTU>) {
+        let (slab, ..) = buildup.into_parts();
+        let put_back = self.put_back(slab);
+        debug_assert!(
+            put_back.is_ok(),
+            "the slab of a buildup always has its place among the free slabs"
+        );
+    }
+
+    fn put_back(&mut self, slab: Slab<'p, SLAB_SIZE>) -> Result<(), Slab<'p, SLAB_SIZE>> {
+        match self.free.iter_mut().find(|free| free.is_none()) {
+            Some(free) => {
+                *free = Some(slab.data);
+
+                Ok(())
+            }
+            None => Err(slab),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{frames_of, message, ClassicFrame};
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    proptest! {
+        #[test]
+        fn a_transfer_is_received_into_a_slab_and_the_slab_is_released(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..200)) {
+            let mut pool = SlabPool::<256, 1>::default();
+            let mut receiver = pool.receiver::<ClassicFrame, CLASSIC_MTU>();
+
+            let mut transfers: Vec<_> = frames_of::<ClassicFrame, CLASSIC_MTU>(&payload, kind)
+                .into_iter()
+                .filter_map(|frame| receiver.receive(frame).unwrap())
+                .collect();
+
+            prop_assert_eq!(transfers.len(), 1);
+            let transfer = transfers.pop().unwrap();
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(transfer.payload(), &payload[..]);
+            prop_assert_eq!(receiver.available(), 0);
+
+            receiver.release(transfer).ok().unwrap();
+            prop_assert_eq!(receiver.available(), 1);
+        }
+    }
+
+    #[test]
+    fn a_transfer_cannot_be_started_when_every_slab_is_held() {
+        let mut pool = SlabPool::<16, 1>::default();
+        let mut receiver = pool.receiver::<ClassicFrame, CLASSIC_MTU>();
+
+        let held = receiver
+            .receive(frames_of(&[1], message()).remove(0))
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            receiver.receive(frames_of(&[2], message()).remove(0)),
+            Err(RxError::OutOfSpace)
+        ));
+
+        receiver.release(held).ok().unwrap();
+        let transfer = receiver
+            .receive(frames_of(&[2], message()).remove(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(transfer.payload(), [2]);
+    }
+
+    #[test]
+    fn the_slab_of_a_transfer_that_does_not_fit_is_put_back() {
+        let mut pool = SlabPool::<8, 1>::default();
+        let mut receiver = pool.receiver::<ClassicFrame, CLASSIC_MTU>();
+
+        let results: Vec<_> = frames_of(&[0; 20], message())
+            .into_iter()
+            .map(|frame| receiver.receive(frame))
+            .collect();
+
+        assert!(results
+            .iter()
+            .any(|result| matches!(result, Err(RxError::BuildupError(_)))));
+        assert_eq!(receiver.available(), 1);
+    }
+
+    #[test]
+    fn a_transfer_is_given_back_when_released_to_another_receiver() {
+        let mut pool = SlabPool::<16, 1>::default();
+        let mut receiver = pool.receiver::<ClassicFrame, CLASSIC_MTU>();
+        let mut other_pool = SlabPool::<16, 1>::default();
+        let mut other_receiver = other_pool.receiver::<ClassicFrame, CLASSIC_MTU>();
+
+        let transfer = receiver
+            .receive(frames_of(&[1], message()).remove(0))
+            .unwrap()
+            .unwrap();
+
+        let transfer = other_receiver.release(transfer).err().unwrap();
+        assert_eq!(other_receiver.available(), 1);
+        assert_eq!(transfer.payload(), [1]);
+
+        receiver.release(transfer).ok().unwrap();
+        assert_eq!(receiver.available(), 1);
+    }
+}
@@ -1,14 +1,28 @@
-use crate::session_id::SessionKind;
-use heapless::{ArrayLength, Vec};
+use crate::{session_id::SessionKind, tail_byte::TransferId, timestamp::Timestamp};
+use heapless::Vec;
 
 #[derive(Debug)]
-pub struct Transfer<Capacity: ArrayLength<u8>> {
-    pub payload: Vec<u8, Capacity>,
+pub struct Transfer<const CAPACITY: usize> {
+    pub payload: Vec<u8, CAPACITY>,
     pub kind: SessionKind,
+    pub transfer_id: TransferId,
+    /// The timestamp of the first frame of the transfer, if the frames were
+    /// timestamped.
+    pub timestamp: Option<Timestamp>,
 }
 
-impl<Capacity: ArrayLength<u8>> Transfer<Capacity> {
-    pub fn new(payload: Vec<u8, Capacity>, kind: SessionKind) -> Self {
-        Self { payload, kind }
+impl<const CAPACITY: usize> Transfer<CAPACITY> {
+    pub fn new(
+        payload: Vec<u8, CAPACITY>,
+        kind: SessionKind,
+        transfer_id: TransferId,
+        timestamp: Option<Timestamp>,
+    ) -> Self {
+        Self {
+            payload,
+            kind,
+            transfer_id,
+            timestamp,
+        }
     }
 }
@@ -0,0 +1,219 @@
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{
+    buildup::Buildup,
+    redundant_receiver::DEFAULT_TRANSFER_ID_TIMEOUT,
+    rx_network::{receive_frame, RxError},
+    transfer::Transfer,
+};
+use crate::{
+    statistics::{ends_transfer, RxStatistics},
+    CanFrame,
+};
+use futures_core::Stream;
+use heapless::Vec;
+
+#[derive(Debug)]
+pub enum AsyncRxError<E, Frame: CanFrame<MTU>, const MTU: usize> {
+    Read(E),
+    Rx(RxError<Frame, MTU>),
+}
+
+/// Builds up the frames awaited from an asynchronous source, such as the
+/// receive side of an Embassy or Tokio CAN driver, into a [Stream] of
+/// [Transfer]s.
+///
+/// Errors are yielded in place of a transfer and the stream keeps on
+/// receiving after them. The stream ends when the frame source ends.
+pub struct TransferStream<
+    Frames,
+    Frame: CanFrame<MTU>,
+    const TRANSFER_CAPACITY: usize,
+    const MTU: usize,
+> {
+    frames: Frames,
+    buildup: Option<Buildup<Frame, Vec<u8, TRANSFER_CAPACITY>, MTU>>,
+    transfer_id_timeout: u64,
+    statistics: RxStatistics,
+}
+
+impl<Frames, Frame: CanFrame<MTU>, const TRANSFER_CAPACITY: usize, const MTU: usize>
+    TransferStream<Frames, Frame, TRANSFER_CAPACITY, MTU>
+{
+    pub fn new(frames: Frames) -> Self {
+        Self {
+            frames,
+            buildup: None,
+            transfer_id_timeout: DEFAULT_TRANSFER_ID_TIMEOUT,
+            statistics: RxStatistics::default(),
+        }
+    }
+
+    pub fn statistics(&self) -> &RxStatistics {
+        &self.statistics
+    }
+
+    /// Sets the time, in microseconds, after its first frame at which a
+    /// transfer still missing frames is abandoned and counted as a timeout.
+    pub fn set_transfer_id_timeout(&mut self, microseconds: u64) {
+        self.transfer_id_timeout = microseconds;
+    }
+
+    pub fn into_inner(self) -> Frames {
+        self.frames
+    }
+
+    fn receive(
+        &mut self,
+        frame: Frame,
+    ) -> Result<Option<Transfer<TRANSFER_CAPACITY>>, RxError<Frame, MTU>> {
+        let ends_transfer = ends_transfer(&frame);
+        let (expired, pushed) = receive_frame(&mut self.buildup, frame, self.transfer_id_timeout);
+        if expired.is_some() {
+            self.statistics.timeouts += 1;
+        }
+
+        let (result, transfer) = match pushed.into_transfer() {
+            Ok(transfer) => (Ok(()), transfer),
+            Err(err) => (Err(err), None),
+        };
+        self.statistics.record(ends_transfer, &result);
+
+        result.map(|()| transfer)
+    }
+}
+
+impl<
+        E,
+        Frames: Stream<Item = Result<Frame, E>> + Unpin,
+        Frame: CanFrame<MTU> + Unpin,
+        const TRANSFER_CAPACITY: usize,
+        const MTU: usize,
+    > TransferStream<Frames, Frame, TRANSFER_CAPACITY, MTU>
+{
+    /// Waits for the next transfer, or returns `None` when the frame source
+    /// has ended.
+    pub async fn next(
+        &mut self,
+    ) -> Option<Result<Transfer<TRANSFER_CAPACITY>, AsyncRxError<E, Frame, MTU>>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl<
+        E,
+        Frames: Stream<Item = Result<Frame, E>> + Unpin,
+        Frame: CanFrame<MTU> + Unpin,
+        const TRANSFER_CAPACITY: usize,
+        const MTU: usize,
+    > Stream for TransferStream<Frames, Frame, TRANSFER_CAPACITY, MTU>
+{
+    type Item = Result<Transfer<TRANSFER_CAPACITY>, AsyncRxError<E, Frame, MTU>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            match Pin::new(&mut this.frames).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Some(Err(AsyncRxError::Read(err))))
+                }
+                Poll::Ready(Some(Ok(frame))) => match this.receive(frame) {
+                    Ok(Some(transfer)) => return Poll::Ready(Some(Ok(transfer))),
+                    Ok(None) => continue,
+                    Err(err) => return Poll::Ready(Some(Err(AsyncRxError::Rx(err)))),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::session_id::TransferPriority;
+    use crate::tests::{frames_of, message, ClassicFrame};
+    use crate::timestamp::Timestamp;
+    use crate::tx::async_transmitter::tests::block_on;
+    use crate::tx::transmitter::send;
+    use crate::tx::tx_queue::TxQueue;
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::collections::VecDeque;
+
+    /// A frame source that has every frame ready, and then ends.
+    struct Frames(VecDeque<Result<ClassicFrame, ()>>);
+
+    impl Stream for Frames {
+        type Item = Result<ClassicFrame, ()>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn the_transfer_stream_yields_the_transfers_that_were_sent(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+            send(&mut queue, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            let mut frames = VecDeque::new();
+            while let Some(frame) = queue.pop_ready(Timestamp::default()) {
+                frames.push_back(Ok(frame));
+            }
+
+            let mut stream = TransferStream::<_, ClassicFrame, 128, CLASSIC_MTU>::new(Frames(frames));
+            let transfer = block_on(stream.next()).unwrap().unwrap();
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+            prop_assert!(block_on(stream.next()).is_none());
+        }
+    }
+
+    #[test]
+    fn the_transfer_stream_keeps_on_receiving_after_an_error() {
+        let frames = Frames(VecDeque::from([
+            Err(()),
+            Ok(ClassicFrame::from((0, [0; 8], 0))),
+        ]));
+        let mut stream = TransferStream::<_, ClassicFrame, 128, CLASSIC_MTU>::new(frames);
+
+        assert!(matches!(
+            block_on(stream.next()),
+            Some(Err(AsyncRxError::Read(())))
+        ));
+        assert!(matches!(
+            block_on(stream.next()),
+            Some(Err(AsyncRxError::Rx(RxError::ZeroLengthFrame)))
+        ));
+        assert!(block_on(stream.next()).is_none());
+    }
+
+    #[test]
+    fn a_stalled_transfer_is_abandoned_after_the_transfer_id_timeout() {
+        let stalled = frames_of::<ClassicFrame, CLASSIC_MTU>(&[1; 20], message()).remove(0);
+        let late = Timestamp::from_micros(DEFAULT_TRANSFER_ID_TIMEOUT + 1);
+
+        let mut frames = VecDeque::from([Ok(stalled.with_timestamp(Timestamp::from_micros(0)))]);
+        for frame in frames_of::<ClassicFrame, CLASSIC_MTU>(&[2; 20], message()) {
+            frames.push_back(Ok(frame.with_timestamp(late)));
+        }
+        let mut stream = TransferStream::<_, ClassicFrame, 128, CLASSIC_MTU>::new(Frames(frames));
+
+        let transfer = block_on(stream.next()).unwrap().unwrap();
+        assert_eq!(&transfer.payload[..], &[2; 20]);
+        assert_eq!(stream.statistics().timeouts, 1);
+        assert_eq!(stream.statistics().transfers, 1);
+    }
+}
@@ -0,0 +1,229 @@
+use super::{encoder::DELIMITER, error::Error};
+use crate::udp::{
+    self,
+    header::{Header, HEADER_SIZE},
+    payload::PayloadWithCrc,
+    Transfer,
+};
+use heapless::Vec;
+
+/// Decodes the transfers of a Cyphal/serial byte stream, received in chunks
+/// of any size.
+///
+/// `CAPACITY` is the capacity of the payload of the decoded transfers, which
+/// are those of Cyphal/UDP as the frames carry its header. Bytes before the
+/// first delimiter are discarded.
+#[derive(Debug)]
+pub struct Decoder<const CAPACITY: usize> {
+    header: Vec<u8, HEADER_SIZE>,
+    /// The bytes that follow the header.
+    payload: PayloadWithCrc<CAPACITY>,
+    /// The bytes left in the current COBS block.
+    remaining: u8,
+    /// The code of the current COBS block, or 0 before the first block.
+    code: u8,
+    is_synchronized: bool,
+    error: Option<Error>,
+}
+
+impl<const CAPACITY: usize> Default for Decoder<CAPACITY> {
+    fn default() -> Self {
+        Self {
+            header: Vec::new(),
+            payload: PayloadWithCrc::default(),
+            remaining: 0,
+            code: 0,
+            is_synchronized: false,
+            error: None,
+        }
+    }
+}
+
+impl<const CAPACITY: usize> Decoder<CAPACITY> {
+    /// Accepts the next byte of the stream, returning the transfer that it
+    /// completes, if any.
+    pub fn push(&mut self, byte: u8) -> Option<Result<Transfer<CAPACITY>, Error>> {
+        if byte == DELIMITER {
+            let result = self.end_frame();
+            self.is_synchronized = true;
+
+            return result;
+        }
+
+        if self.is_synchronized && self.error.is_none() {
+            if let Err(err) = self.advance(byte) {
+                self.error = Some(err);
+            }
+        }
+
+        None
+    }
+
+    /// Accepts a chunk of the stream, yielding the transfers that it
+    /// completes.
+    pub fn decode<'a>(
+        &'a mut self,
+        chunk: &'a [u8],
+    ) -> impl Iterator<Item = Result<Transfer<CAPACITY>, Error>> + 'a {
+        chunk.iter().filter_map(move |byte| self.push(*byte))
+    }
+
+    fn advance(&mut self, byte: u8) -> Result<(), Error> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return self.store(byte);
+        }
+
+        // Every block but the longest ones is followed by a zero byte, which
+        // is only known to be part of the data once another block follows.
+        if self.code != 0 && self.code != 0xFF {
+            self.store(0)?;
+        }
+        self.code = byte;
+        self.remaining = byte - 1;
+
+        Ok(())
+    }
+
+    /// Stores a decoded byte of the frame.
+    fn store(&mut self, byte: u8) -> Result<(), Error> {
+        if self.header.is_full() {
+            self.payload.push(byte).map_err(|_| Error::OutOfSpace)
+        } else {
+            // The header was just checked not to be full.
+            let _ = self.header.push(byte);
+
+            Ok(())
+        }
+    }
+
+    fn end_frame(&mut self) -> Option<Result<Transfer<CAPACITY>, Error>> {
+        let header = core::mem::take(&mut self.header);
+        let payload = core::mem::take(&mut self.payload);
+        let error = self.error.take();
+        let is_complete = self.remaining == 0;
+        self.remaining = 0;
+        self.code = 0;
+
+        match (error, header.is_empty()) {
+            (Some(error), _) => Some(Err(error)),
+            (None, true) => None,
+            (None, false) if !is_complete => Some(Err(Error::InvalidEncoding)),
+            (None, false) => Some(Self::transfer_from(&header, payload)),
+        }
+    }
+
+    fn transfer_from(
+        header: &[u8],
+        payload: PayloadWithCrc<CAPACITY>,
+    ) -> Result<Transfer<CAPACITY>, Error> {
+        let (header, _) = Header::parse(header).map_err(Error::Header)?;
+        let kind = header.session_kind().map_err(Error::Header)?;
+
+        (header.frame_index == 0 && header.end_of_transfer)
+            .then_some(())
+            .ok_or(Error::UnsupportedFrame)?;
+
+        let payload = payload.into_payload().map_err(|error| match error {
+            udp::Error::WrongCRC(computed, received) => Error::WrongCRC(computed, received),
+            error => Error::Header(error),
+        })?;
+
+        Ok(Transfer {
+            payload,
+            kind,
+            transfer_id: header.transfer_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serial::encoder::send;
+    use crate::session_id::{SubjectId, TransferPriority};
+    use crate::udp::{session_kind::strategy::session_kind, SessionKind};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec as StdVec};
+
+    fn encode(payload: &[u8], kind: SessionKind, transfer_id: u64) -> StdVec<u8> {
+        let mut buffer = [0; 1024];
+        let mut writer = &mut buffer[..];
+        send(
+            &mut writer,
+            payload,
+            kind,
+            TransferPriority::Nominal,
+            transfer_id,
+        )
+        .unwrap();
+        let len = 1024 - writer.len();
+
+        buffer[..len].to_vec()
+    }
+
+    proptest! {
+        #[test]
+        fn a_transfer_is_decoded_from_chunks_of_any_size(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..400), chunk_size in 1usize..64, transfer_id in proptest::num::u64::ANY) {
+            let stream = encode(&payload, kind, transfer_id);
+            let mut decoder = Decoder::<512>::default();
+
+            let transfers: StdVec<_> = stream
+                .chunks(chunk_size)
+                .flat_map(|chunk| decoder.decode(chunk).collect::<StdVec<_>>())
+                .collect();
+
+            prop_assert_eq!(transfers.len(), 1);
+            let transfer = transfers[0].as_ref().unwrap();
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(transfer.transfer_id, transfer_id);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+        }
+
+        #[test]
+        fn a_corrupted_frame_is_an_error_and_the_next_frame_is_decoded(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 1..100), index in 2usize..20, byte in 1u8..) {
+            let mut corrupted = encode(&payload, kind, 7);
+            prop_assume!(corrupted[index] != byte);
+            corrupted[index] = byte;
+            let mut decoder = Decoder::<512>::default();
+
+            let mut results: StdVec<_> = decoder.decode(&corrupted).collect();
+            results.extend(decoder.decode(&encode(&payload, kind, 7)));
+
+            prop_assert!(results[0].is_err());
+            prop_assert_eq!(&results.last().unwrap().as_ref().unwrap().payload[..], &payload[..]);
+        }
+    }
+
+    #[test]
+    fn a_payload_is_decoded_up_to_the_capacity_of_the_decoder() {
+        let kind = SessionKind::Message {
+            source_node_id: None,
+            subject_id: SubjectId::new(),
+        };
+        let mut decoder = Decoder::<5>::default();
+
+        let transfers: StdVec<_> = decoder.decode(&encode(b"hello", kind, 7)).collect();
+
+        assert_eq!(&transfers[0].as_ref().unwrap().payload[..], b"hello");
+    }
+
+    #[test]
+    fn bytes_before_the_first_delimiter_are_discarded() {
+        let kind = SessionKind::Message {
+            source_node_id: None,
+            subject_id: SubjectId::new(),
+        };
+        let mut stream = StdVec::from(&encode(b"hello", kind, 7)[3..]);
+        stream.extend(encode(b"world", kind, 8));
+        let mut decoder = Decoder::<512>::default();
+
+        let transfers: StdVec<_> = decoder.decode(&stream).collect();
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(&transfers[0].as_ref().unwrap().payload[..], b"world");
+    }
+}
@@ -0,0 +1,134 @@
+use embedded_io::Write;
+
+use crate::{
+    session_id::TransferPriority,
+    udp::{
+        header::{Header, HEADER_SIZE},
+        SessionKind,
+    },
+};
+use crc_any::CRCu32;
+
+pub(crate) const DELIMITER: u8 = 0;
+
+/// The longest run of non-zero bytes that a COBS block can hold.
+const MAX_BLOCK_SIZE: usize = 254;
+
+/// COBS-encodes the bytes written to it into `writer`, one block at a time.
+struct CobsWriter<'a, W: Write> {
+    writer: &'a mut W,
+    block: [u8; MAX_BLOCK_SIZE],
+    len: usize,
+}
+
+impl<'a, W: Write> CobsWriter<'a, W> {
+    fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            block: [0; MAX_BLOCK_SIZE],
+            len: 0,
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        for byte in data {
+            if *byte == 0 {
+                self.flush_block()?;
+            } else {
+                self.block[self.len] = *byte;
+                self.len += 1;
+
+                if self.len == MAX_BLOCK_SIZE {
+                    self.flush_block()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush_block(&mut self) -> Result<(), W::Error> {
+        self.writer.write_all(&[self.len as u8 + 1])?;
+        self.writer.write_all(&self.block[..self.len])?;
+        self.len = 0;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), W::Error> {
+        self.flush_block()
+    }
+}
+
+/// Writes a transfer to `writer` as a delimited COBS frame.
+///
+/// The session and the transfer ID are those of Cyphal/UDP, whose header the
+/// frame carries, so node IDs are 16 bits wide and transfer IDs 64 bits wide.
+pub fn send<W: Write>(
+    writer: &mut W,
+    payload: &[u8],
+    kind: SessionKind,
+    priority: TransferPriority,
+    transfer_id: u64,
+) -> Result<(), W::Error> {
+    let mut header = [0; HEADER_SIZE];
+    Header {
+        end_of_transfer: true,
+        ..Header::for_session_kind(kind, priority, transfer_id)
+    }
+    .write(&mut header);
+
+    let mut crc = CRCu32::crc32c();
+    crc.digest(payload);
+
+    writer.write_all(&[DELIMITER])?;
+    let mut cobs = CobsWriter::new(writer);
+    cobs.write(&header)?;
+    cobs.write(payload)?;
+    cobs.write(&crc.get_crc().to_le_bytes())?;
+    cobs.finish()?;
+    writer.write_all(&[DELIMITER])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<'a>(data: &[u8], buffer: &'a mut [u8]) -> &'a [u8] {
+        let mut writer = &mut buffer[..];
+        let mut cobs = CobsWriter::new(&mut writer);
+        cobs.write(data).unwrap();
+        cobs.finish().unwrap();
+        let remaining = writer.len();
+
+        &buffer[..buffer.len() - remaining]
+    }
+
+    #[test]
+    fn cobs_encoding_matches_the_reference_examples() {
+        let mut buffer = [0; 512];
+
+        assert_eq!(encode(&[0x00], &mut buffer), [0x01, 0x01]);
+        assert_eq!(encode(&[0x00, 0x00], &mut buffer), [0x01, 0x01, 0x01]);
+        assert_eq!(
+            encode(&[0x11, 0x22, 0x00, 0x33], &mut buffer),
+            [0x03, 0x11, 0x22, 0x02, 0x33]
+        );
+        assert_eq!(
+            encode(&[0x11, 0x00, 0x00, 0x00], &mut buffer),
+            [0x02, 0x11, 0x01, 0x01, 0x01]
+        );
+    }
+
+    #[test]
+    fn cobs_encoding_splits_long_runs_of_non_zero_bytes() {
+        let data = [0xAB; MAX_BLOCK_SIZE + 1];
+        let mut buffer = [0; 512];
+        let encoded = encode(&data, &mut buffer);
+
+        assert_eq!(encoded.len(), data.len() + 2);
+        assert_eq!(encoded[0], 0xFF);
+        assert_eq!(encoded[MAX_BLOCK_SIZE + 1], 0x02);
+        assert!(encoded.iter().all(|byte| *byte != 0));
+    }
+}
@@ -0,0 +1,16 @@
+use crate::udp;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The bytes between two delimiters are not a valid COBS encoding.
+    InvalidEncoding,
+    /// The frame does not fit the capacity of the decoder.
+    OutOfSpace,
+    /// The header of the frame, which is shared with Cyphal/UDP, is invalid.
+    Header(udp::Error),
+    /// The frame is not the only frame of its transfer, as it should be on a
+    /// serial link.
+    UnsupportedFrame,
+    /// The transfer CRC that was computed and the one that was received.
+    WrongCRC(u32, u32),
+}
@@ -0,0 +1,17 @@
+//! The Cyphal/serial transport, enabled by the `serial` feature, for byte
+//! streams such as UARTs, RS-485 buses and debug links.
+//!
+//! Each transfer is sent as a single frame holding the same header as
+//! Cyphal/UDP, the payload and the transfer CRC. The frame is encoded with
+//! COBS, so that it contains no zero byte, and is delimited by a zero byte
+//! on each side.
+
+pub mod decoder;
+pub mod encoder;
+pub mod error;
+pub mod transport;
+
+pub use decoder::Decoder;
+pub use encoder::send;
+pub use error::Error;
+pub use transport::SerialTransport;
@@ -0,0 +1,153 @@
+use embedded_io::{Read, ReadReady, Write};
+
+use super::{decoder::Decoder, encoder::send, error::Error};
+use crate::{
+    session_id::TransferPriority,
+    transport::Transport,
+    udp::{SessionKind, Transfer},
+};
+
+#[derive(Debug)]
+pub enum ReceiveError<E> {
+    Io(E),
+    Serial(Error),
+}
+
+/// A Cyphal/serial link as a [Transport], over a byte stream that can tell
+/// whether bytes are ready to be read.
+///
+/// Each frame carries a whole transfer, so the MTU of the transport is
+/// unbounded.
+#[derive(Debug)]
+pub struct SerialTransport<Io: Read + ReadReady + Write, const CAPACITY: usize> {
+    io: Io,
+    decoder: Decoder<CAPACITY>,
+}
+
+impl<Io: Read + ReadReady + Write, const CAPACITY: usize> SerialTransport<Io, CAPACITY> {
+    pub fn new(io: Io) -> Self {
+        Self {
+            io,
+            decoder: Decoder::default(),
+        }
+    }
+
+    pub fn into_inner(self) -> Io {
+        self.io
+    }
+}
+
+impl<Io: Read + ReadReady + Write, const CAPACITY: usize> Transport<CAPACITY>
+    for SerialTransport<Io, CAPACITY>
+{
+    type SendError = Io::Error;
+    type ReceiveError = ReceiveError<Io::Error>;
+
+    fn mtu(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Cyphal/serial has the 16 bits node IDs of Cyphal/UDP.
+    fn max_node_id(&self) -> u16 {
+        crate::udp::NodeId::MAX
+    }
+
+    fn send(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Result<(), Self::SendError> {
+        send(&mut self.io, payload, kind, priority, transfer_id)
+    }
+
+    /// Reads the bytes that are ready, one at a time, until a transfer is
+    /// complete.
+    fn receive(&mut self) -> Result<Option<Transfer<CAPACITY>>, Self::ReceiveError> {
+        let mut byte = [0];
+
+        while self.io.read_ready().map_err(ReceiveError::Io)? {
+            if self.io.read(&mut byte).map_err(ReceiveError::Io)? == 0 {
+                break;
+            }
+
+            if let Some(result) = self.decoder.push(byte[0]) {
+                return result.map(Some).map_err(ReceiveError::Serial);
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::can_transport::tests::round_trip;
+    use crate::udp::session_kind::strategy::session_kind;
+    use core::convert::Infallible;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::collections::VecDeque;
+
+    /// A byte stream that reads back what was written to it.
+    #[derive(Default)]
+    struct Loopback(VecDeque<u8>);
+
+    impl embedded_io::ErrorType for Loopback {
+        type Error = Infallible;
+    }
+
+    impl Read for Loopback {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let len = buf.len().min(self.0.len());
+            for byte in &mut buf[..len] {
+                *byte = self.0.pop_front().unwrap();
+            }
+
+            Ok(len)
+        }
+    }
+
+    impl ReadReady for Loopback {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.0.is_empty())
+        }
+    }
+
+    impl Write for Loopback {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.extend(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn a_transfer_sent_over_the_serial_transport_is_received_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..300), transfer_id in proptest::num::u64::ANY) {
+            let mut transport = SerialTransport::<_, 512>::new(Loopback::default());
+
+            let transfer = round_trip(&mut transport, &payload, kind, transfer_id);
+
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(transfer.transfer_id, transfer_id);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+            prop_assert!(transport.receive().unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn node_ids_are_16_bits_wide() {
+        let transport = SerialTransport::<_, 512>::new(Loopback::default());
+
+        assert_eq!(transport.max_node_id(), 65534);
+    }
+}
@@ -61,6 +61,16 @@ pub mod strategy {
             MessageSessionId::from_base_parts(source_node_id, subject_id, transfer_priority).into()
         }
     }
+
+    prop_compose! {
+        /// The CAN ID of a message of an anonymous node, whose source node ID
+        /// is a pseudo-ID.
+        pub fn anonymous_message_session_id_as_u32()(source_node_id in node_id(), subject_id in subject_id(), transfer_priority in transfer_priority()) -> u32 {
+            MessageSessionId::from_base_parts(source_node_id, subject_id, transfer_priority)
+                .with_is_anonymous(true)
+                .into()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,7 @@ pub use message::MessageSessionId;
 pub use session_id::SessionId;
 // TODO: can_id_for_session_kind should be removed and substituted with a SessionKind.as_u32 or a From<SessionKind> for u32.
 pub use node_id::NodeId;
+pub use service_id::ServiceId;
 pub use session_kind::{can_id_for_session_kind, SessionKind};
 pub use subject_id::SubjectId;
 pub use transfer_priority::TransferPriority;
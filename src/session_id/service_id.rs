@@ -9,6 +9,18 @@ pub struct ServiceId {
     id: B9,
 }
 
+impl ServiceId {
+    /// Builds the identifier from a value known at compile time, such as the
+    /// fixed port identifier of a generated DSDL type.
+    ///
+    /// Panics, or fails to compile in a const context, if `value` is not
+    /// lower than 512.
+    pub const fn from_const(value: u16) -> Self {
+        assert!(value < 512, "identifier out of bounds");
+        Self::from_bytes(value.to_le_bytes())
+    }
+}
+
 impl TryFrom<u16> for ServiceId {
     type Error = OutOfBounds;
 
@@ -52,4 +64,11 @@ mod tests {
             prop_assert!(ServiceId::try_from(id).is_err())
         }
     }
+
+    proptest! {
+        #[test]
+        fn a_service_id_built_from_a_constant_is_equal_to_the_one_built_at_runtime(id in 0..512u16) {
+            prop_assert_eq!(ServiceId::from_const(id), ServiceId::try_from(id).unwrap())
+        }
+    }
 }
@@ -1,4 +1,6 @@
-use super::{message::MessageSessionId, service::ServiceSessionId};
+use modular_bitfield::Specifier;
+
+use super::{message::MessageSessionId, service::ServiceSessionId, TransferPriority};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionId {
@@ -13,6 +15,18 @@ impl SessionId {
             SessionId::Rpc(service) => service.is_valid(),
         }
     }
+
+    /// The priority of the frame, held by the three most significant bits of
+    /// its CAN ID.
+    pub fn priority(&self) -> TransferPriority {
+        let can_id = match self {
+            SessionId::Message(message) => u32::from(*message),
+            SessionId::Rpc(service) => u32::from(*service),
+        };
+
+        // Every pattern of three bits is a priority.
+        TransferPriority::from_bytes(((can_id >> 26) & 7) as u8).unwrap()
+    }
 }
 
 impl From<u32> for SessionId {
@@ -42,4 +56,16 @@ mod tests {
 
         assert!(matches!(id, SessionId::Rpc(_)))
     }
+
+    #[test]
+    fn the_priority_of_a_session_is_held_by_the_three_most_significant_bits() {
+        assert_eq!(
+            SessionId::from(0x0C7D552Au32).priority(),
+            TransferPriority::High
+        );
+        assert_eq!(
+            SessionId::from(0x136B852Au32).priority(),
+            TransferPriority::Nominal
+        );
+    }
 }
@@ -18,6 +18,18 @@ impl Request {
             service_id,
         }
     }
+
+    pub fn source_node_id(&self) -> NodeId {
+        self.source_node_id
+    }
+
+    pub fn destination_node_id(&self) -> NodeId {
+        self.destination_node_id
+    }
+
+    pub fn service_id(&self) -> ServiceId {
+        self.service_id
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -9,6 +9,18 @@ pub struct SubjectId {
     id: B13,
 }
 
+impl SubjectId {
+    /// Builds the identifier from a value known at compile time, such as the
+    /// fixed port identifier of a generated DSDL type.
+    ///
+    /// Panics, or fails to compile in a const context, if `value` is not
+    /// lower than 8192.
+    pub const fn from_const(value: u16) -> Self {
+        assert!(value < 8192, "identifier out of bounds");
+        Self::from_bytes(value.to_le_bytes())
+    }
+}
+
 impl TryFrom<u16> for SubjectId {
     type Error = OutOfBounds;
 
@@ -52,4 +64,11 @@ mod tests {
             prop_assert!(SubjectId::try_from(id).is_err())
         }
     }
+
+    proptest! {
+        #[test]
+        fn a_subject_id_built_from_a_constant_is_equal_to_the_one_built_at_runtime(id in 0..8192u16) {
+            prop_assert_eq!(SubjectId::from_const(id), SubjectId::try_from(id).unwrap())
+        }
+    }
 }
@@ -0,0 +1,429 @@
+use core::marker::PhantomData;
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, vec::Vec};
+
+use super::faults::{Faults, Rng};
+use crate::{tx::stream_transmitter::CanWriter, CanFrame};
+
+/// The frame could not be sent because its node is off the bus.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BusOff;
+
+/// A frame as it travels on the bus, rebuilt into the frame type of each
+/// node once delivered.
+type RawFrame<const MTU: usize> = (u32, [u8; MTU], usize);
+
+struct Node<const MTU: usize> {
+    tx_queue: VecDeque<RawFrame<MTU>>,
+    rx_queue: VecDeque<RawFrame<MTU>>,
+    is_bus_off: bool,
+}
+
+struct Bus<const MTU: usize> {
+    nodes: Vec<Node<MTU>>,
+    faults: Faults,
+    rng: Rng,
+}
+
+impl<const MTU: usize> Bus<MTU> {
+    fn deliver(&mut self, index: usize, mut frame: RawFrame<MTU>) {
+        let faults = self.faults;
+        let rng = &mut self.rng;
+        let node = &mut self.nodes[index];
+
+        if rng.chance(faults.drop) {
+            return;
+        }
+        // A frame claiming more bytes than the MTU only has its MTU to flip.
+        let len = frame.2.min(MTU);
+        if len > 0 && rng.chance(faults.bit_flip) {
+            let bit = (rng.next_u64() % (len as u64 * 8)) as usize;
+            frame.1[bit / 8] ^= 1 << (bit % 8);
+        }
+        let copies = if rng.chance(faults.duplicate) { 2 } else { 1 };
+
+        for _ in 0..copies {
+            node.rx_queue.push_back(frame);
+        }
+    }
+}
+
+/// An in-memory CAN bus shared by any number of simulated nodes, to test
+/// multi-node protocols deterministically without a CAN interface.
+///
+/// Each node attaches to the bus with a [BusPort], through which it writes
+/// and reads frames. Written frames wait in the transmit queue of their node
+/// until the bus is [stepped](Self::step) or [run](Self::run). Like on a
+/// real bus, the frame with the lowest CAN ID among the frames at the head of
+/// the transmit queues wins the arbitration and is delivered to every other
+/// node that is not off the bus, so the frames of a node keep their order.
+///
+/// [Faults] are injected on delivery, drawn from a generator seeded with the
+/// seed of the bus, so that a test gives the same outcome on every run.
+pub struct VirtualBus<const MTU: usize> {
+    bus: Rc<RefCell<Bus<MTU>>>,
+}
+
+impl<const MTU: usize> VirtualBus<MTU> {
+    /// Creates a bus that delivers every frame faithfully.
+    pub fn new(seed: u64) -> Self {
+        Self::with_faults(seed, Faults::default())
+    }
+
+    pub fn with_faults(seed: u64, faults: Faults) -> Self {
+        Self {
+            bus: Rc::new(RefCell::new(Bus {
+                nodes: Vec::new(),
+                faults,
+                rng: Rng::new(seed),
+            })),
+        }
+    }
+
+    pub fn set_faults(&self, faults: Faults) {
+        self.bus.borrow_mut().faults = faults;
+    }
+
+    /// Attaches a new node to the bus.
+    pub fn attach<Frame: CanFrame<MTU>>(&self) -> BusPort<Frame, MTU> {
+        let mut bus = self.bus.borrow_mut();
+        bus.nodes.push(Node {
+            tx_queue: VecDeque::new(),
+            rx_queue: VecDeque::new(),
+            is_bus_off: false,
+        });
+
+        BusPort {
+            bus: Rc::clone(&self.bus),
+            index: bus.nodes.len() - 1,
+            _frame_marker: PhantomData,
+        }
+    }
+
+    /// Transmits the frame that wins the arbitration, returning `false` when
+    /// no node has a frame to send.
+    pub fn step(&self) -> bool {
+        let mut bus = self.bus.borrow_mut();
+        let winner = bus
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| node.tx_queue.front().map(|frame| (frame.0, index)))
+            .min();
+
+        let (_, sender) = match winner {
+            Some(winner) => winner,
+            None => return false,
+        };
+        let frame = bus.nodes[sender].tx_queue.pop_front().unwrap();
+
+        for index in 0..bus.nodes.len() {
+            if index != sender && !bus.nodes[index].is_bus_off {
+                bus.deliver(index, frame);
+            }
+        }
+
+        true
+    }
+
+    /// Transmits frames until every transmit queue is empty, returning the
+    /// number of frames transmitted.
+    pub fn run(&self) -> usize {
+        let mut transmitted = 0;
+        while self.step() {
+            transmitted += 1;
+        }
+
+        transmitted
+    }
+
+    /// Whether no node has a frame to send.
+    pub(super) fn is_idle(&self) -> bool {
+        self.bus
+            .borrow()
+            .nodes
+            .iter()
+            .all(|node| node.tx_queue.is_empty())
+    }
+
+    /// Draws whether the bus lets the other buses of a [RedundantBus]
+    /// transmit first.
+    ///
+    /// [RedundantBus]: super::RedundantBus
+    pub(super) fn falls_behind(&self) -> bool {
+        let mut bus = self.bus.borrow_mut();
+        let reorder = bus.faults.reorder;
+
+        reorder > 0.0 && bus.rng.chance(reorder)
+    }
+}
+
+/// The attachment of a node to a [VirtualBus].
+///
+/// Frames written to the port are queued until the bus transmits them, and
+/// the frames delivered to the node are read with [receive](Self::receive).
+pub struct BusPort<Frame: CanFrame<MTU>, const MTU: usize> {
+    bus: Rc<RefCell<Bus<MTU>>>,
+    index: usize,
+    _frame_marker: PhantomData<Frame>,
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> BusPort<Frame, MTU> {
+    /// The position of the node among the nodes attached to the bus, which
+    /// breaks arbitration ties between frames with the same CAN ID.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the next frame delivered to the node, if any.
+    pub fn receive(&mut self) -> Option<Frame> {
+        self.bus.borrow_mut().nodes[self.index]
+            .rx_queue
+            .pop_front()
+            .map(Frame::from)
+    }
+
+    /// Takes the node off the bus or puts it back. A node off the bus loses
+    /// the frames waiting to be sent, fails to write new ones and receives
+    /// nothing until it is back.
+    pub fn set_bus_off(&mut self, is_bus_off: bool) {
+        let mut bus = self.bus.borrow_mut();
+        let node = &mut bus.nodes[self.index];
+
+        node.is_bus_off = is_bus_off;
+        if is_bus_off {
+            node.tx_queue.clear();
+        }
+    }
+
+    pub fn is_bus_off(&self) -> bool {
+        self.bus.borrow().nodes[self.index].is_bus_off
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> CanWriter<Frame, MTU> for BusPort<Frame, MTU> {
+    type Error = BusOff;
+
+    fn write_frame(&mut self, frame: Frame) -> Result<(), Self::Error> {
+        let mut bus = self.bus.borrow_mut();
+        let node = &mut bus.nodes[self.index];
+        if node.is_bus_off {
+            return Err(BusOff);
+        }
+
+        let (data, len) = frame.payload();
+        node.tx_queue.push_back((frame.id(), *data, len));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candump::LoggedFrame;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::{NodeId, SessionKind, SubjectId, TransferPriority};
+    use crate::tests::ClassicFrame;
+    use crate::tx::{stream_transmitter::StreamTransmitter, transmitter::send};
+    use crate::CLASSIC_MTU;
+
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    type Frame = LoggedFrame<CLASSIC_MTU>;
+
+    fn frame(id: u32, data: &[u8]) -> Frame {
+        let mut bytes = [0; CLASSIC_MTU];
+        bytes[..data.len()].copy_from_slice(data);
+        Frame::from((id, bytes, data.len()))
+    }
+
+    fn received(port: &mut BusPort<Frame, CLASSIC_MTU>) -> Vec<Frame> {
+        core::iter::from_fn(|| port.receive()).collect()
+    }
+
+    #[test]
+    fn frames_are_delivered_to_every_node_but_the_sender() {
+        let bus = VirtualBus::<CLASSIC_MTU>::new(0);
+        let mut sender = bus.attach::<Frame>();
+        let mut first = bus.attach::<Frame>();
+        let mut second = bus.attach::<Frame>();
+
+        sender.write_frame(frame(0x10, &[1, 2])).unwrap();
+        assert_eq!(bus.run(), 1);
+
+        assert_eq!(received(&mut first), [frame(0x10, &[1, 2])]);
+        assert_eq!(received(&mut second), [frame(0x10, &[1, 2])]);
+        assert!(received(&mut sender).is_empty());
+    }
+
+    #[test]
+    fn the_lowest_can_id_wins_the_arbitration_without_reordering_the_frames_of_a_node() {
+        let bus = VirtualBus::<CLASSIC_MTU>::new(0);
+        let mut low = bus.attach::<Frame>();
+        let mut high = bus.attach::<Frame>();
+        let mut listener = bus.attach::<Frame>();
+
+        high.write_frame(frame(0x30, &[])).unwrap();
+        low.write_frame(frame(0x20, &[])).unwrap();
+        low.write_frame(frame(0x40, &[])).unwrap();
+        low.write_frame(frame(0x10, &[])).unwrap();
+        bus.run();
+
+        let ids: Vec<_> = received(&mut listener).iter().map(|f| f.id()).collect();
+        assert_eq!(ids, [0x20, 0x30, 0x40, 0x10]);
+    }
+
+    #[test]
+    fn a_node_off_the_bus_neither_sends_nor_receives() {
+        let bus = VirtualBus::<CLASSIC_MTU>::new(0);
+        let mut node = bus.attach::<Frame>();
+        let mut other = bus.attach::<Frame>();
+
+        node.write_frame(frame(0x10, &[])).unwrap();
+        node.set_bus_off(true);
+        assert_eq!(node.write_frame(frame(0x10, &[])), Err(BusOff));
+        other.write_frame(frame(0x20, &[])).unwrap();
+        bus.run();
+
+        assert!(received(&mut node).is_empty());
+        assert!(received(&mut other).is_empty());
+
+        node.set_bus_off(false);
+        other.write_frame(frame(0x20, &[])).unwrap();
+        bus.run();
+        assert_eq!(received(&mut node), [frame(0x20, &[])]);
+    }
+
+    #[test]
+    fn certain_faults_drop_duplicate_and_flip_every_frame() {
+        let dropping = VirtualBus::<CLASSIC_MTU>::with_faults(
+            0,
+            Faults {
+                drop: 1.0,
+                ..Faults::default()
+            },
+        );
+        let mut sender = dropping.attach::<Frame>();
+        let mut receiver = dropping.attach::<Frame>();
+        sender.write_frame(frame(0x10, &[1])).unwrap();
+        dropping.run();
+        assert!(received(&mut receiver).is_empty());
+
+        let duplicating = VirtualBus::<CLASSIC_MTU>::with_faults(
+            0,
+            Faults {
+                duplicate: 1.0,
+                ..Faults::default()
+            },
+        );
+        let mut sender = duplicating.attach::<Frame>();
+        let mut receiver = duplicating.attach::<Frame>();
+        sender.write_frame(frame(0x10, &[1])).unwrap();
+        duplicating.run();
+        assert_eq!(
+            received(&mut receiver),
+            [frame(0x10, &[1]), frame(0x10, &[1])]
+        );
+
+        let flipping = VirtualBus::<CLASSIC_MTU>::with_faults(
+            0,
+            Faults {
+                bit_flip: 1.0,
+                ..Faults::default()
+            },
+        );
+        let mut sender = flipping.attach::<Frame>();
+        let mut receiver = flipping.attach::<Frame>();
+        sender.write_frame(frame(0x10, &[0, 0])).unwrap();
+        flipping.run();
+        let flipped = received(&mut receiver);
+        assert_eq!(flipped.len(), 1);
+        let ones: u32 = flipped[0].data().iter().map(|byte| byte.count_ones()).sum();
+        assert_eq!(ones, 1);
+    }
+
+    #[test]
+    fn a_bit_is_flipped_within_the_mtu_of_a_frame_claiming_more_bytes() {
+        let bus = VirtualBus::<CLASSIC_MTU>::with_faults(
+            0,
+            Faults {
+                bit_flip: 1.0,
+                ..Faults::default()
+            },
+        );
+        let mut sender = bus.attach::<ClassicFrame>();
+        let mut receiver = bus.attach::<ClassicFrame>();
+
+        sender
+            .write_frame(ClassicFrame::from((
+                0x10,
+                [0; CLASSIC_MTU],
+                CLASSIC_MTU + 1,
+            )))
+            .unwrap();
+        bus.run();
+
+        let frame = receiver.receive().unwrap();
+        let (data, _) = frame.payload();
+        assert_eq!(data.iter().map(|byte| byte.count_ones()).sum::<u32>(), 1);
+    }
+
+    proptest! {
+        #[test]
+        fn transfers_published_by_several_nodes_are_rebuilt_by_every_other_node(payloads in vec(vec(proptest::num::u8::ANY, 0..40), 2..5)) {
+            let bus = VirtualBus::<CLASSIC_MTU>::new(0);
+            let kind = |index: usize| SessionKind::Message {
+                source_node_id: NodeId::try_from(index as u8).unwrap(),
+                subject_id: SubjectId::try_from(100).unwrap(),
+            };
+
+            // The frames of a node all share the same CAN ID, so the transfers
+            // are not interleaved on the bus.
+            let mut ports: Vec<_> = payloads.iter().enumerate().map(|(index, payload)| {
+                let mut transmitter = StreamTransmitter::new(bus.attach::<Frame>());
+                send(&mut transmitter, payload, kind(index), TransferPriority::Nominal).unwrap();
+                transmitter.into_writer()
+            }).collect();
+            bus.run();
+
+            for (index, port) in ports.iter_mut().enumerate() {
+                let mut network = RxNetwork::<Frame, 8, 64, CLASSIC_MTU>::default();
+                let (mut producer, consumer) = network.split();
+                for frame in received(port) {
+                    producer.receive(frame).unwrap();
+                }
+
+                let mut transfers: Vec<_> = consumer.map(|transfer| (transfer.kind, transfer.payload.to_vec())).collect();
+                transfers.sort_by_key(|(kind, _)| format!("{:?}", kind));
+                let mut expected: Vec<_> = payloads.iter().enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(other, payload)| (kind(other), payload.clone()))
+                    .collect();
+                expected.sort_by_key(|(kind, _)| format!("{:?}", kind));
+                prop_assert_eq!(transfers, expected);
+            }
+        }
+
+        #[test]
+        fn the_same_seed_injects_the_same_faults(seed in proptest::num::u64::ANY, ids in vec(0u32..0x1FFF_FFFF, 1..50)) {
+            let faults = Faults { drop: 0.2, duplicate: 0.2, bit_flip: 0.2, reorder: 0.2 };
+            let run = || {
+                let bus = VirtualBus::<CLASSIC_MTU>::with_faults(seed, faults);
+                let mut sender = bus.attach::<Frame>();
+                let mut receiver = bus.attach::<Frame>();
+                for id in &ids {
+                    sender.write_frame(frame(*id, &[0xAA; 8])).unwrap();
+                }
+                bus.run();
+                received(&mut receiver)
+            };
+
+            prop_assert_eq!(run(), run());
+        }
+    }
+}
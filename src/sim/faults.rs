@@ -0,0 +1,68 @@
+/// The faults injected by a [VirtualBus], as the probability of each fault
+/// happening to a frame delivered to a node.
+///
+/// Faults are drawn separately for every node receiving a frame, so that a
+/// frame can be lost by one node and still reach the others.
+///
+/// [VirtualBus]: super::VirtualBus
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Faults {
+    /// The frame is not delivered.
+    pub drop: f64,
+    /// The frame is delivered twice in a row.
+    pub duplicate: f64,
+    /// A random bit of the data of the frame is inverted.
+    pub bit_flip: f64,
+    /// The bus lets the other buses of a [RedundantBus] transmit first when
+    /// its turn comes, so that the copies of the frames of a redundant node
+    /// reach the other nodes in a different order on each interface. A bus
+    /// stepped on its own keeps the order of its frames, as a real CAN bus.
+    ///
+    /// [RedundantBus]: super::RedundantBus
+    pub reorder: f64,
+}
+
+/// A xorshift generator, so that faults are drawn deterministically from the
+/// seed of the bus without depending on a random number crate.
+#[derive(Debug, Clone)]
+pub(super) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(super) fn new(seed: u64) -> Self {
+        // The state of a xorshift generator must not be zero, which the
+        // lowest bit rules out for every seed.
+        Self {
+            state: (seed ^ 0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        self.state
+    }
+
+    /// Returns `true` with the given `probability`.
+    pub(super) fn chance(&mut self, probability: f64) -> bool {
+        // The 53 high bits give a uniform fraction between 0 and 1.
+        let fraction = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+
+        probability > 0.0 && fraction < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_seed_gives_a_generator_that_does_not_get_stuck_at_zero() {
+        let mut rng = Rng::new(0x9E37_79B9_7F4A_7C15);
+
+        assert!((0..100).all(|_| rng.next_u64() != 0));
+    }
+}
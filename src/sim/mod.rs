@@ -0,0 +1,19 @@
+//! A simulated CAN bus for testing multi-node protocols, enabled by the
+//! `std` feature.
+//!
+//! Nodes attach to a [VirtualBus] and get a [BusPort], which is a
+//! [CanWriter] for transmitters and the source of the frames delivered to
+//! the node. Frames are delivered in arbitration order, with optional
+//! [Faults] drawn from a seed, so that tests are reproducible in CI without
+//! a `vcan` interface. A node with redundant interfaces attaches to each bus
+//! of a [RedundantBus], which interleaves the deliveries of its buses.
+//!
+//! [CanWriter]: crate::tx::stream_transmitter::CanWriter
+
+pub mod bus;
+pub mod faults;
+pub mod redundant_bus;
+
+pub use bus::{BusOff, BusPort, VirtualBus};
+pub use faults::Faults;
+pub use redundant_bus::RedundantBus;
@@ -0,0 +1,235 @@
+use super::{
+    bus::{BusPort, VirtualBus},
+    faults::Faults,
+};
+use crate::CanFrame;
+
+/// The `N` buses of a redundant network, each connecting one interface of
+/// every node.
+///
+/// The buses are [stepped](Self::step) in turns, one frame at a time, so that
+/// the copies of a frame sent by a redundant node reach the other nodes on
+/// each interface in between the frames of the other buses. A bus with a
+/// [reorder](Faults::reorder) fault lets the other buses transmit first when
+/// its turn comes, delivering its copies later than the copies of the other
+/// interfaces, while each bus keeps the order of its own frames.
+pub struct RedundantBus<const MTU: usize, const N: usize> {
+    buses: [VirtualBus<MTU>; N],
+    /// The bus whose turn it is to transmit.
+    turn: usize,
+}
+
+impl<const MTU: usize, const N: usize> RedundantBus<MTU, N> {
+    /// Creates buses that deliver every frame faithfully.
+    pub fn new(seed: u64) -> Self {
+        Self::with_faults(seed, [Faults::default(); N])
+    }
+
+    /// Creates buses with the faults of each bus, seeded differently so that
+    /// their faults are not drawn in lockstep.
+    pub fn with_faults(seed: u64, faults: [Faults; N]) -> Self {
+        let mut index = 0;
+
+        Self {
+            buses: faults.map(|faults| {
+                index += 1;
+                VirtualBus::with_faults(seed.wrapping_add(index), faults)
+            }),
+            turn: 0,
+        }
+    }
+
+    pub fn buses(&self) -> &[VirtualBus<MTU>; N] {
+        &self.buses
+    }
+
+    /// Attaches a new node to every bus, returning its port on each of them.
+    pub fn attach<Frame: CanFrame<MTU>>(&self) -> [BusPort<Frame, MTU>; N] {
+        let mut buses = self.buses.iter();
+
+        [(); N].map(|_| buses.next().unwrap().attach())
+    }
+
+    /// Transmits a frame on the first bus, from the one whose turn it is,
+    /// that has a frame to send and does not fall behind. Returns `false`
+    /// when no bus has a frame to send.
+    pub fn step(&mut self) -> bool {
+        let pending: heapless::Vec<usize, N> = (0..N)
+            .map(|offset| (self.turn + offset) % N)
+            .filter(|index| !self.buses[*index].is_idle())
+            .collect();
+
+        // A bus only falls behind another bus that has a frame to send.
+        let index = match pending
+            .iter()
+            .find(|index| !self.buses[**index].falls_behind())
+            .or_else(|| pending.first())
+        {
+            Some(index) => *index,
+            None => return false,
+        };
+
+        self.turn = (index + 1) % N;
+        self.buses[index].step()
+    }
+
+    /// Transmits frames until every transmit queue of every bus is empty,
+    /// returning the number of frames transmitted.
+    pub fn run(&mut self) -> usize {
+        let mut transmitted = 0;
+        while self.step() {
+            transmitted += 1;
+        }
+
+        transmitted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candump::LoggedFrame;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::TransferPriority;
+    use crate::tail_byte::TransferId;
+    use crate::tests::message;
+    use crate::tx::{
+        redundant_transmitter::RedundantTransmitter, stream_transmitter::CanWriter,
+        transmitter::send_with_transfer_id,
+    };
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Frame = LoggedFrame<CLASSIC_MTU>;
+
+    fn frame(id: u32) -> Frame {
+        Frame::from((id, [0; CLASSIC_MTU], 0))
+    }
+
+    /// Steps the buses until they are idle, returning the interface and the
+    /// CAN ID of the frames in the order they reached the ports.
+    fn deliveries<const N: usize>(
+        bus: &mut RedundantBus<CLASSIC_MTU, N>,
+        ports: &mut [BusPort<Frame, CLASSIC_MTU>; N],
+    ) -> Vec<(usize, u32)> {
+        let mut deliveries = Vec::new();
+        while bus.step() {
+            for (interface, port) in ports.iter_mut().enumerate() {
+                while let Some(frame) = port.receive() {
+                    deliveries.push((interface, frame.id()));
+                }
+            }
+        }
+
+        deliveries
+    }
+
+    #[test]
+    fn the_buses_transmit_in_turns() {
+        let mut bus = RedundantBus::<CLASSIC_MTU, 2>::new(0);
+        let mut sender = bus.attach::<Frame>();
+        let mut receiver = bus.attach::<Frame>();
+
+        for id in 1..=2 {
+            for port in &mut sender {
+                port.write_frame(frame(id)).unwrap();
+            }
+        }
+
+        assert_eq!(
+            deliveries(&mut bus, &mut receiver),
+            [(0, 1), (1, 1), (0, 2), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn a_bus_that_falls_behind_delivers_after_the_other_buses() {
+        let mut bus = RedundantBus::<CLASSIC_MTU, 2>::with_faults(
+            0,
+            [
+                Faults {
+                    reorder: 1.0,
+                    ..Faults::default()
+                },
+                Faults::default(),
+            ],
+        );
+        let mut sender = bus.attach::<Frame>();
+        let mut receiver = bus.attach::<Frame>();
+
+        for id in 1..=2 {
+            for port in &mut sender {
+                port.write_frame(frame(id)).unwrap();
+            }
+        }
+
+        assert_eq!(
+            deliveries(&mut bus, &mut receiver),
+            [(1, 1), (1, 2), (0, 1), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn frames_are_counted_on_every_bus() {
+        let mut bus = RedundantBus::<CLASSIC_MTU, 3>::new(0);
+        let mut sender = bus.attach::<Frame>();
+        bus.attach::<Frame>();
+
+        for port in &mut sender {
+            port.write_frame(frame(1)).unwrap();
+        }
+
+        assert_eq!(bus.run(), 3);
+    }
+
+    proptest! {
+        #[test]
+        fn the_transfers_of_a_redundant_node_are_received_once_despite_reordering(seed in proptest::num::u64::ANY, payloads in vec(vec(proptest::num::u8::ANY, 0..40), 1..5)) {
+            let faults = Faults { reorder: 0.5, ..Faults::default() };
+            let mut bus = RedundantBus::<CLASSIC_MTU, 2>::with_faults(seed, [faults; 2]);
+            let mut transmitter = RedundantTransmitter::new(bus.attach::<Frame>());
+            let mut ports = bus.attach::<Frame>();
+
+            let mut transfer_id = TransferId::new();
+            for payload in &payloads {
+                send_with_transfer_id(&mut transmitter, payload, message(), TransferPriority::Nominal, transfer_id).unwrap();
+                transfer_id.advance();
+            }
+
+            let mut network = RxNetwork::<Frame, 8, 64, CLASSIC_MTU>::default();
+            let (mut producer, consumer) = network.split_redundant::<2, 16>();
+            while bus.step() {
+                for (interface, port) in ports.iter_mut().enumerate() {
+                    while let Some(frame) = port.receive() {
+                        producer.receive(interface, frame).unwrap();
+                    }
+                }
+            }
+
+            let received: Vec<_> = consumer.map(|transfer| transfer.payload.to_vec()).collect();
+            prop_assert_eq!(received, payloads);
+        }
+
+        #[test]
+        fn the_same_seed_interleaves_the_buses_the_same_way(seed in proptest::num::u64::ANY, ids in vec(0u32..0x1FFF_FFFF, 1..20)) {
+            let faults = Faults { reorder: 0.5, ..Faults::default() };
+            let run = || {
+                let mut bus = RedundantBus::<CLASSIC_MTU, 3>::with_faults(seed, [faults; 3]);
+                let mut sender = bus.attach::<Frame>();
+                let mut receiver = bus.attach::<Frame>();
+                for id in &ids {
+                    for port in &mut sender {
+                        port.write_frame(frame(*id)).unwrap();
+                    }
+                }
+                deliveries(&mut bus, &mut receiver)
+            };
+
+            prop_assert_eq!(run(), run());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use core::convert::TryFrom;
+
+use crate::session_id::{
+    message::MessageSessionId, service::ServiceSessionId, NodeId, ServiceId, SubjectId,
+    TransferPriority,
+};
+
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+
+/// A kernel acceptance filter, letting through the frames whose ID, masked by
+/// `mask`, is equal to `id`.
+///
+/// The filters built by this type only accept data frames with an extended
+/// ID.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub id: u32,
+    pub mask: u32,
+}
+
+impl Filter {
+    fn new(id: u32, mask: u32) -> Self {
+        Self {
+            id: id | CAN_EFF_FLAG,
+            mask: mask | CAN_EFF_FLAG | CAN_RTR_FLAG,
+        }
+    }
+
+    /// Accepts the messages published on `subject_id`, such as the ones
+    /// expected by a [crate::presentation::Subscriber].
+    pub fn messages(subject_id: SubjectId) -> Self {
+        let id = MessageSessionId::from_base_parts(
+            NodeId::new(),
+            subject_id,
+            TransferPriority::Exceptional,
+        );
+        let mask = MessageSessionId::new()
+            .with_subject_id(SubjectId::from_const(8191))
+            .with_is_service(true);
+
+        Self::new(u32::from(id), u32::from(mask))
+    }
+
+    /// Accepts the requests of `service_id` addressed to `node_id`, such as
+    /// the ones expected by a [crate::presentation::Server].
+    pub fn requests(service_id: ServiceId, node_id: NodeId) -> Self {
+        let id = ServiceSessionId::request_from_base_parts(
+            NodeId::new(),
+            node_id,
+            service_id,
+            TransferPriority::Exceptional,
+        );
+
+        Self::new(u32::from(id), service_mask())
+    }
+
+    /// Accepts the responses of `service_id` addressed to `node_id`, such as
+    /// the ones expected by a [crate::presentation::Client].
+    pub fn responses(service_id: ServiceId, node_id: NodeId) -> Self {
+        let id = ServiceSessionId::response_from_base_parts(
+            NodeId::new(),
+            node_id,
+            service_id,
+            TransferPriority::Exceptional,
+        );
+
+        Self::new(u32::from(id), service_mask())
+    }
+
+    pub fn accepts(&self, can_id: u32) -> bool {
+        (can_id | CAN_EFF_FLAG) & self.mask == self.id & self.mask
+    }
+}
+
+/// The bits of a service session ID that identify the service, the
+/// destination of the transfer and whether it is a request or a response.
+fn service_mask() -> u32 {
+    u32::from(ServiceSessionId::request_from_base_parts(
+        NodeId::new(),
+        NodeId::try_from(127).unwrap(),
+        ServiceId::from_const(511),
+        TransferPriority::Exceptional,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::node_id::strategy::node_id;
+    use crate::session_id::service_id::strategy::service_id;
+    use crate::session_id::session_kind::Request;
+    use crate::session_id::subject_id::strategy::subject_id;
+    use crate::session_id::transfer_priority::strategy::transfer_priority;
+    use crate::session_id::{can_id_for_session_kind, SessionKind};
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_message_filter_accepts_the_messages_of_its_subject_only(source in node_id(), subject in subject_id(), other_subject in subject_id(), priority in transfer_priority()) {
+            let filter = Filter::messages(subject);
+            let message = |subject_id| can_id_for_session_kind(SessionKind::Message { source_node_id: source, subject_id }, priority);
+
+            prop_assert!(filter.accepts(message(subject)));
+            prop_assert_eq!(filter.accepts(message(other_subject)), subject == other_subject);
+        }
+
+        #[test]
+        fn a_request_filter_accepts_the_requests_addressed_to_its_node_only(source in node_id(), destination in node_id(), service in service_id(), priority in transfer_priority()) {
+            let filter = Filter::requests(service, destination);
+            let request = Request::new(source, destination, service);
+
+            prop_assert!(filter.accepts(can_id_for_session_kind(SessionKind::Request(request), priority)));
+            prop_assert!(!filter.accepts(can_id_for_session_kind(SessionKind::Response(Request::new(destination, source, service)), priority)));
+        }
+
+        #[test]
+        fn a_response_filter_accepts_the_responses_addressed_to_its_node_only(client in node_id(), server in node_id(), service in service_id(), priority in transfer_priority()) {
+            let filter = Filter::responses(service, client);
+            let request = Request::new(client, server, service);
+
+            prop_assert!(filter.accepts(can_id_for_session_kind(SessionKind::Response(request), priority)));
+            prop_assert!(!filter.accepts(can_id_for_session_kind(SessionKind::Request(request), priority)));
+        }
+
+        #[test]
+        fn a_message_filter_rejects_every_service_transfer(subject in subject_id(), client in node_id(), server in node_id(), service in service_id(), priority in transfer_priority()) {
+            let filter = Filter::messages(subject);
+            let request = Request::new(client, server, service);
+
+            prop_assert!(!filter.accepts(can_id_for_session_kind(SessionKind::Request(request), priority)));
+            prop_assert!(!filter.accepts(can_id_for_session_kind(SessionKind::Response(request), priority)));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{timestamp::Timestamp, CanFrame};
+
+/// A data frame with an extended ID, as exchanged with a [super::SocketCan].
+///
+/// `MTU` is either [crate::CLASSIC_MTU] for classic CAN or
+/// [crate::EXTENDED_MTU] for CAN FD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketCanFrame<const MTU: usize> {
+    id: u32,
+    data: [u8; MTU],
+    len: usize,
+    timestamp: Option<Timestamp>,
+}
+
+impl<const MTU: usize> SocketCanFrame<MTU> {
+    pub(super) fn with_timestamp(mut self, timestamp: Option<Timestamp>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl<const MTU: usize> From<(u32, [u8; MTU], usize)> for SocketCanFrame<MTU> {
+    fn from((id, data, len): (u32, [u8; MTU], usize)) -> Self {
+        Self {
+            id,
+            data,
+            len,
+            timestamp: None,
+        }
+    }
+}
+
+impl<const MTU: usize> CanFrame<MTU> for SocketCanFrame<MTU> {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn payload(&self) -> (&[u8; MTU], usize) {
+        (&self.data, self.len)
+    }
+
+    /// The time at which the kernel received the frame, or at which it was
+    /// sent for the frames that confirm a transmission.
+    fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+}
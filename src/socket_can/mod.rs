@@ -0,0 +1,22 @@
+//! A ready-made interface to the Linux SocketCAN stack, enabled by the `std`
+//! feature.
+//!
+//! The tests that need a CAN interface expect a virtual one named `vcan0`,
+//! which can be set up with:
+//!
+//! ```text
+//! sudo modprobe vcan
+//! sudo ip link add dev vcan0 type vcan
+//! sudo ip link set dev vcan0 mtu 72
+//! sudo ip link set up vcan0
+//! ```
+//!
+//! and are run with `cargo test --features std -- --ignored`.
+
+pub mod filter;
+pub mod frame;
+pub mod socket;
+
+pub use filter::Filter;
+pub use frame::SocketCanFrame;
+pub use socket::{Event, SocketCan};
@@ -0,0 +1,473 @@
+// This module is the boundary with the SocketCAN API of the kernel, which is
+// only reachable through the C interface of libc.
+#![allow(unsafe_code)]
+
+use std::{
+    ffi::CString,
+    io, mem,
+    os::unix::io::{AsRawFd, RawFd},
+    ptr,
+};
+
+use super::{filter::Filter, frame::SocketCanFrame};
+use crate::{
+    padded_frame_length, timestamp::Timestamp, tx::stream_transmitter::CanWriter, CLASSIC_MTU,
+    EXTENDED_MTU,
+};
+
+/// What was read from a [SocketCan].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<const MTU: usize> {
+    /// A frame sent on the bus by another node, or by another socket of this
+    /// node.
+    Received(SocketCanFrame<MTU>),
+    /// A frame written by this socket, reported back by the kernel once it
+    /// was sent on the bus.
+    Transmitted(SocketCanFrame<MTU>),
+}
+
+/// A non-blocking raw CAN socket bound to a network interface.
+///
+/// `MTU` selects between classic CAN, with [CLASSIC_MTU], and CAN FD, with
+/// [EXTENDED_MTU]. A CAN FD socket also receives the classic frames on the
+/// bus.
+///
+/// The kernel timestamps every frame it receives, preferring the hardware
+/// timestamp when the interface supports it, and reports every frame written
+/// by the socket back as an [Event::Transmitted] once it was sent, so that
+/// the transmission can be confirmed.
+#[derive(Debug)]
+pub struct SocketCan<const MTU: usize> {
+    fd: RawFd,
+}
+
+fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+fn timestamp_from(time: &libc::timespec) -> Option<Timestamp> {
+    (time.tv_sec != 0 || time.tv_nsec != 0).then(|| {
+        Timestamp::from_micros(time.tv_sec as u64 * 1_000_000 + time.tv_nsec as u64 / 1_000)
+    })
+}
+
+impl<const MTU: usize> SocketCan<MTU> {
+    pub fn open(interface: &str) -> io::Result<Self> {
+        if MTU != CLASSIC_MTU && MTU != EXTENDED_MTU {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the MTU of a SocketCAN interface is either CLASSIC_MTU or EXTENDED_MTU",
+            ));
+        }
+
+        let name = CString::new(interface)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        // SAFETY: `name` is a valid nul-terminated string that outlives the
+        // call.
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // SAFETY: `socket` takes no pointer, and its result is checked.
+        let fd = check(unsafe {
+            libc::socket(
+                libc::PF_CAN,
+                libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                libc::CAN_RAW,
+            )
+        })?;
+        // From now on the socket is closed on drop, even when the rest of the
+        // setup fails.
+        let socket = Self { fd };
+
+        socket.set_option(libc::SOL_CAN_RAW, libc::CAN_RAW_RECV_OWN_MSGS, &1)?;
+        if MTU == EXTENDED_MTU {
+            socket.set_option(libc::SOL_CAN_RAW, libc::CAN_RAW_FD_FRAMES, &1)?;
+        }
+
+        let timestamping = libc::SOF_TIMESTAMPING_RX_SOFTWARE
+            | libc::SOF_TIMESTAMPING_SOFTWARE
+            | libc::SOF_TIMESTAMPING_RX_HARDWARE
+            | libc::SOF_TIMESTAMPING_RAW_HARDWARE;
+        socket.set_option(libc::SOL_SOCKET, libc::SO_TIMESTAMPING, &timestamping)?;
+
+        // SAFETY: `sockaddr_can` is a plain C struct, for which all zeros is a
+        // valid value.
+        let mut address: libc::sockaddr_can = unsafe { mem::zeroed() };
+        address.can_family = libc::AF_CAN as libc::sa_family_t;
+        address.can_ifindex = index as libc::c_int;
+        // SAFETY: `address` is a valid `sockaddr_can`, whose size is the
+        // length given to `bind`.
+        check(unsafe {
+            libc::bind(
+                socket.fd,
+                &address as *const libc::sockaddr_can as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_can>() as libc::socklen_t,
+            )
+        })?;
+
+        Ok(socket)
+    }
+
+    fn set_option<T>(&self, level: libc::c_int, name: libc::c_int, value: &T) -> io::Result<()> {
+        // SAFETY: `value` points to a `T` that outlives the call, whose size is
+        // the length given to `setsockopt`.
+        check(unsafe {
+            libc::setsockopt(
+                self.fd,
+                level,
+                name,
+                value as *const T as *const libc::c_void,
+                mem::size_of::<T>() as libc::socklen_t,
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Replaces the acceptance filters of the socket, so that the kernel only
+    /// delivers the frames accepted by at least one of `filters`.
+    ///
+    /// The filters apply to every frame read from the socket, including the
+    /// confirmations of the frames written by the socket and the frames
+    /// written by the other sockets of this node. An empty slice of filters
+    /// thus stops the reception of every frame, confirmations included.
+    pub fn set_filters(&self, filters: &[Filter]) -> io::Result<()> {
+        let mut kernel_filters = std::vec::Vec::with_capacity(filters.len());
+        kernel_filters.extend(filters.iter().map(|filter| libc::can_filter {
+            can_id: filter.id,
+            can_mask: filter.mask,
+        }));
+
+        // SAFETY: `kernel_filters` outlives the call, and the length given to
+        // `setsockopt` is the size of its elements.
+        check(unsafe {
+            libc::setsockopt(
+                self.fd,
+                libc::SOL_CAN_RAW,
+                libc::CAN_RAW_FILTER,
+                kernel_filters.as_ptr() as *const libc::c_void,
+                mem::size_of_val(kernel_filters.as_slice()) as libc::socklen_t,
+            )
+        })
+        .map(|_| ())
+    }
+
+    /// Reads the next event without blocking, returning `None` when there is
+    /// nothing to read.
+    ///
+    /// Frames that cannot be part of a transfer, such as remote frames, error
+    /// frames and frames with a standard ID, are skipped.
+    pub fn read(&mut self) -> io::Result<Option<Event<MTU>>> {
+        loop {
+            // SAFETY: `canfd_frame` is a plain C struct, for which all zeros is
+            // a valid value.
+            let mut frame: libc::canfd_frame = unsafe { mem::zeroed() };
+            // Enough room for the three timespec of SO_TIMESTAMPING.
+            let mut control = [0u64; 16];
+
+            let mut iov = libc::iovec {
+                iov_base: &mut frame as *mut libc::canfd_frame as *mut libc::c_void,
+                iov_len: mem::size_of::<libc::canfd_frame>(),
+            };
+            // SAFETY: `msghdr` is a plain C struct, for which all zeros, with
+            // null pointers, is a valid value.
+            let mut message: libc::msghdr = unsafe { mem::zeroed() };
+            message.msg_iov = &mut iov;
+            message.msg_iovlen = 1;
+            message.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+            message.msg_controllen = mem::size_of_val(&control) as _;
+
+            // SAFETY: `message` points to `iov` and `control`, which outlive
+            // the call, with their sizes, and `iov` points to `frame` with its
+            // size.
+            let read = unsafe { libc::recvmsg(self.fd, &mut message, libc::MSG_DONTWAIT) };
+            if read < 0 {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::WouldBlock => Ok(None),
+                    _ => Err(error),
+                };
+            }
+
+            let is_extended = frame.can_id & libc::CAN_EFF_FLAG != 0;
+            let is_data = frame.can_id & (libc::CAN_RTR_FLAG | libc::CAN_ERR_FLAG) == 0;
+            let len = frame.len as usize;
+            if !is_extended || !is_data || len > MTU {
+                continue;
+            }
+
+            let mut data = [0u8; MTU];
+            data[..len].copy_from_slice(&frame.data[..len]);
+            let frame = SocketCanFrame::from((frame.can_id & libc::CAN_EFF_MASK, data, len))
+                .with_timestamp(Self::timestamp_of(&message));
+
+            return Ok(Some(if message.msg_flags & libc::MSG_CONFIRM != 0 {
+                Event::Transmitted(frame)
+            } else {
+                Event::Received(frame)
+            }));
+        }
+    }
+
+    fn timestamp_of(message: &libc::msghdr) -> Option<Timestamp> {
+        // SAFETY: `message` was filled by `recvmsg`, so that its control
+        // buffer holds valid control messages up to `msg_controllen`.
+        let mut header = unsafe { libc::CMSG_FIRSTHDR(message) };
+
+        while !header.is_null() {
+            // SAFETY: `header` is not null, and was returned by `CMSG_FIRSTHDR`
+            // or `CMSG_NXTHDR`, which only point into the control buffer.
+            let (level, kind) = unsafe { ((*header).cmsg_level, (*header).cmsg_type) };
+            if level == libc::SOL_SOCKET && kind == libc::SCM_TIMESTAMPING {
+                // The software timestamp comes first and the raw hardware
+                // timestamp last, with an unused timestamp in between.
+                // SAFETY: the data of an `SCM_TIMESTAMPING` control message is
+                // made of three timespec, which may be unaligned.
+                let times = unsafe {
+                    ptr::read_unaligned(libc::CMSG_DATA(header) as *const [libc::timespec; 3])
+                };
+
+                return timestamp_from(&times[2]).or_else(|| timestamp_from(&times[0]));
+            }
+
+            // SAFETY: `header` is a valid control message of `message`.
+            header = unsafe { libc::CMSG_NXTHDR(message, header) };
+        }
+
+        None
+    }
+
+    /// Writes `frame` without blocking, failing with
+    /// [io::ErrorKind::WouldBlock] when the transmit queue of the interface is
+    /// full.
+    ///
+    /// A CAN FD frame whose length cannot be encoded by a DLC is padded with
+    /// zeros before its tail byte.
+    pub fn write(&mut self, frame: &SocketCanFrame<MTU>) -> io::Result<()> {
+        let written = if MTU == CLASSIC_MTU {
+            let raw = classic_frame(frame);
+
+            // SAFETY: `raw` outlives the call, and the length given to `write`
+            // is its size.
+            unsafe {
+                libc::write(
+                    self.fd,
+                    &raw as *const libc::can_frame as *const libc::c_void,
+                    mem::size_of::<libc::can_frame>(),
+                )
+            }
+        } else {
+            let raw = fd_frame(frame);
+
+            // SAFETY: `raw` outlives the call, and the length given to `write`
+            // is its size.
+            unsafe {
+                libc::write(
+                    self.fd,
+                    &raw as *const libc::canfd_frame as *const libc::c_void,
+                    mem::size_of::<libc::canfd_frame>(),
+                )
+            }
+        };
+
+        if written < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn frame_id<const MTU: usize>(frame: &SocketCanFrame<MTU>) -> u32 {
+    use crate::CanFrame;
+
+    (frame.id() & libc::CAN_EFF_MASK) | libc::CAN_EFF_FLAG
+}
+
+fn classic_frame<const MTU: usize>(frame: &SocketCanFrame<MTU>) -> libc::can_frame {
+    let data = frame.data();
+
+    // SAFETY: `can_frame` is a plain C struct, for which all zeros is a valid
+    // value.
+    let mut raw: libc::can_frame = unsafe { mem::zeroed() };
+    raw.can_id = frame_id(frame);
+    raw.can_dlc = data.len() as u8;
+    raw.data[..data.len()].copy_from_slice(data);
+
+    raw
+}
+
+fn fd_frame<const MTU: usize>(frame: &SocketCanFrame<MTU>) -> libc::canfd_frame {
+    let data = frame.data();
+    let len = padded_frame_length(data.len());
+
+    // SAFETY: `canfd_frame` is a plain C struct, for which all zeros is a
+    // valid value.
+    let mut raw: libc::canfd_frame = unsafe { mem::zeroed() };
+    raw.can_id = frame_id(frame);
+    raw.len = len as u8;
+    if let Some((tail_byte, data)) = data.split_last() {
+        // The padding goes between the data and the tail byte, which stays
+        // the last byte of the frame.
+        raw.data[..data.len()].copy_from_slice(data);
+        raw.data[len - 1] = *tail_byte;
+    }
+
+    raw
+}
+
+impl<const MTU: usize> CanWriter<SocketCanFrame<MTU>, MTU> for SocketCan<MTU> {
+    type Error = io::Error;
+
+    fn write_frame(&mut self, frame: SocketCanFrame<MTU>) -> Result<(), Self::Error> {
+        self.write(&frame)
+    }
+}
+
+impl<const MTU: usize> AsRawFd for SocketCan<MTU> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl<const MTU: usize> Drop for SocketCan<MTU> {
+    fn drop(&mut self) {
+        // SAFETY: the socket owns `fd`, which is not used after this.
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::{SubjectId, TransferPriority};
+    use crate::tests::message;
+    use crate::tx::{stream_transmitter::StreamTransmitter, transmitter::send};
+    use crate::CanFrame;
+
+    const INTERFACE: &str = "vcan0";
+
+    fn wait_for<const MTU: usize>(socket: &mut SocketCan<MTU>) -> Event<MTU> {
+        for _ in 0..1000 {
+            if let Some(event) = socket.read().unwrap() {
+                return event;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        panic!("no frame was read from {}", INTERFACE);
+    }
+
+    #[test]
+    fn opening_a_socket_with_an_mtu_other_than_the_classic_or_extended_one_is_an_error() {
+        assert_eq!(
+            SocketCan::<16>::open(INTERFACE).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn a_can_fd_frame_of_13_bytes_is_written_padded_to_16_bytes_with_its_tail_byte_last() {
+        let mut data = [0; EXTENDED_MTU];
+        data[..13].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 0xE0]);
+
+        let raw = fd_frame(&SocketCanFrame::from((0x1234, data, 13)));
+
+        assert_eq!(raw.len, 16);
+        assert_eq!(raw.data[..12], [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        assert_eq!(raw.data[12..15], [0; 3]);
+        assert_eq!(raw.data[15], 0xE0);
+    }
+
+    #[test]
+    #[ignore = "requires a vcan0 interface"]
+    fn a_transfer_sent_on_a_socket_is_received_by_another_socket_on_the_same_interface() {
+        let mut receiver = SocketCan::<CLASSIC_MTU>::open(INTERFACE).unwrap();
+        let mut transmitter =
+            StreamTransmitter::new(SocketCan::<CLASSIC_MTU>::open(INTERFACE).unwrap());
+
+        let payload = [7u8; 20];
+        send(
+            &mut transmitter,
+            &payload,
+            message(),
+            TransferPriority::Nominal,
+        )
+        .unwrap();
+
+        let mut network = RxNetwork::<SocketCanFrame<CLASSIC_MTU>, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, mut consumer) = network.split();
+        let transfer = loop {
+            match wait_for(&mut receiver) {
+                Event::Received(frame) => producer.receive(frame).unwrap(),
+                Event::Transmitted(_) => panic!("the receiver did not transmit anything"),
+            }
+
+            if let Some(transfer) = consumer.next() {
+                break transfer;
+            }
+        };
+
+        assert_eq!(AsRef::<[u8]>::as_ref(&transfer.payload), &payload[..]);
+        assert!(transfer.timestamp.is_some());
+    }
+
+    #[test]
+    #[ignore = "requires a vcan0 interface"]
+    fn a_frame_written_by_a_socket_is_confirmed_to_the_same_socket() {
+        let mut socket = SocketCan::<CLASSIC_MTU>::open(INTERFACE).unwrap();
+        let frame = SocketCanFrame::from((0x1234, [1; CLASSIC_MTU], 3));
+
+        socket.write(&frame).unwrap();
+
+        match wait_for(&mut socket) {
+            Event::Transmitted(confirmed) => {
+                assert_eq!(confirmed.id(), frame.id());
+                assert_eq!(confirmed.data(), frame.data());
+            }
+            Event::Received(_) => panic!("the frame was not reported as transmitted"),
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a vcan0 interface with an MTU of 72"]
+    fn a_can_fd_frame_is_received_with_its_whole_payload() {
+        let mut receiver = SocketCan::<EXTENDED_MTU>::open(INTERFACE).unwrap();
+        let mut transmitter = SocketCan::<EXTENDED_MTU>::open(INTERFACE).unwrap();
+        let frame = SocketCanFrame::from((0x1234, [9; EXTENDED_MTU], EXTENDED_MTU));
+
+        transmitter.write(&frame).unwrap();
+
+        match wait_for(&mut receiver) {
+            Event::Received(received) => assert_eq!(received.data(), frame.data()),
+            Event::Transmitted(_) => panic!("the receiver did not transmit anything"),
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a vcan0 interface"]
+    fn frames_rejected_by_the_filters_are_not_received() {
+        let mut receiver = SocketCan::<CLASSIC_MTU>::open(INTERFACE).unwrap();
+        let mut transmitter = SocketCan::<CLASSIC_MTU>::open(INTERFACE).unwrap();
+        receiver
+            .set_filters(&[Filter::messages(SubjectId::from_const(1))])
+            .unwrap();
+
+        let rejected =
+            crate::session_id::can_id_for_session_kind(message(), TransferPriority::Nominal);
+        transmitter
+            .write(&SocketCanFrame::from((rejected, [0xE0; CLASSIC_MTU], 1)))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        assert_eq!(receiver.read().unwrap(), None);
+    }
+}
@@ -0,0 +1,31 @@
+//! Counters of the frames and transfers handled by the receiving and
+//! transmitting sides of an interface, for health telemetry.
+
+pub mod rx_statistics;
+pub mod session_statistics;
+pub mod tx_statistics;
+
+pub use rx_statistics::RxStatistics;
+pub use session_statistics::SessionStatistics;
+pub use tx_statistics::TxStatistics;
+
+use crate::{
+    tail_byte::{PayloadKind, TailByte},
+    CanFrame,
+};
+
+/// Whether `frame` is the first frame of its transfer.
+pub(crate) fn starts_transfer<Frame: CanFrame<MTU>, const MTU: usize>(frame: &Frame) -> bool {
+    matches!(
+        TailByte::split_from(frame.payload()).map(|(_, tail_byte)| tail_byte.payload_kind()),
+        Some(PayloadKind::SingleFrame | PayloadKind::StartOfMultiFrame)
+    )
+}
+
+/// Whether `frame` is the last frame of its transfer.
+pub(crate) fn ends_transfer<Frame: CanFrame<MTU>, const MTU: usize>(frame: &Frame) -> bool {
+    matches!(
+        TailByte::split_from(frame.payload()).map(|(_, tail_byte)| tail_byte.payload_kind()),
+        Some(PayloadKind::SingleFrame | PayloadKind::EndOfMultiFrame)
+    )
+}
@@ -0,0 +1,168 @@
+use crate::{
+    rx::{buildup, rx_network::RxError},
+    CanFrame,
+};
+
+/// Counters kept by a receiver, either for a whole interface or for a single
+/// session.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct RxStatistics {
+    pub frames: u64,
+    /// The transfers that were completed and delivered.
+    pub transfers: u64,
+    /// The transfers whose CRC did not match their payload.
+    pub crc_errors: u64,
+    /// The frames whose transfer ID showed that frames were missed.
+    pub missing_frames: u64,
+    /// The frames whose tail byte did not follow the previous frame of the
+    /// transfer, such as a wrong toggle bit.
+    pub toggle_errors: u64,
+    /// The transfers that were lost because the queue of transfers was full.
+    pub overflows: u64,
+    /// The transfers that were abandoned because their next frame did not
+    /// arrive within the transfer-ID timeout.
+    pub timeouts: u64,
+    /// The transfers that were discarded because another interface of a
+    /// redundant receiver had already delivered them.
+    pub duplicates: u64,
+    /// The frames that could not be part of a transfer, such as empty frames,
+    /// frames with an invalid ID, frames out of sequence and frames of
+    /// transfers exceeding the capacity of the receiver.
+    pub malformed_frames: u64,
+}
+
+impl RxStatistics {
+    /// The total number of errors.
+    pub fn errors(&self) -> u64 {
+        self.crc_errors
+            + self.missing_frames
+            + self.toggle_errors
+            + self.overflows
+            + self.timeouts
+            + self.malformed_frames
+    }
+
+    /// Records the reception of a frame, which completes a transfer if it
+    /// `ends_transfer` and was received successfully.
+    pub(crate) fn record<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        ends_transfer: bool,
+        result: &Result<(), RxError<Frame, MTU>>,
+    ) {
+        self.frames += 1;
+
+        let counter = match result {
+            Ok(()) if ends_transfer => &mut self.transfers,
+            Ok(()) => return,
+            Err(RxError::OutOfSpace) => &mut self.overflows,
+            Err(RxError::BuildupError(buildup::Error::WrongCRC(_, _))) => &mut self.crc_errors,
+            Err(RxError::BuildupError(buildup::Error::MissingFrames(_))) => {
+                &mut self.missing_frames
+            }
+            Err(RxError::BuildupError(buildup::Error::CorruptedTailByte(_))) => {
+                &mut self.toggle_errors
+            }
+            Err(_) => &mut self.malformed_frames,
+        };
+
+        *counter += 1;
+    }
+
+    /// Records the reception of a frame completing a transfer that another
+    /// interface already delivered.
+    pub(crate) fn record_duplicate(&mut self) {
+        self.frames += 1;
+        self.duplicates += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rx::redundant_receiver::DEFAULT_TRANSFER_ID_TIMEOUT;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{frames_of, message, ClassicFrame};
+    use crate::timestamp::Timestamp;
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn every_frame_and_completed_transfer_is_counted(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, _) = rx_network.split();
+
+            let frames = frames_of(&payload, kind);
+            let frames_count = frames.len() as u64;
+            for frame in frames {
+                producer.receive(frame).unwrap();
+            }
+
+            prop_assert_eq!(*producer.statistics(), RxStatistics { frames: frames_count, transfers: 1, ..RxStatistics::default() });
+        }
+
+        #[test]
+        fn a_corrupted_payload_is_counted_as_a_crc_error(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 8..100)) {
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (mut producer, _) = rx_network.split();
+
+            let mut frames = frames_of::<ClassicFrame, CLASSIC_MTU>(&payload, kind);
+            let (data, len) = frames[0].payload();
+            let mut data = *data;
+            data[0] ^= 0xFF;
+            frames[0] = ClassicFrame::from((frames[0].id(), data, len));
+
+            for frame in frames {
+                let _ = producer.receive(frame);
+            }
+
+            prop_assert_eq!(producer.statistics().crc_errors, 1);
+            prop_assert_eq!(producer.statistics().transfers, 0);
+        }
+    }
+
+    #[test]
+    fn a_transfer_that_does_not_fit_the_queue_is_counted_as_an_overflow() {
+        let kind = message();
+        let mut rx_network = RxNetwork::<ClassicFrame, 2, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = rx_network.split();
+
+        for frame in frames_of(&[1], kind)
+            .into_iter()
+            .chain(frames_of(&[2], kind))
+        {
+            let _ = producer.receive(frame);
+        }
+
+        assert_eq!(producer.statistics().overflows, 1);
+        assert_eq!(producer.statistics().errors(), 1);
+    }
+
+    #[test]
+    fn a_transfer_whose_next_frame_arrives_after_the_transfer_id_timeout_is_counted_as_a_timeout() {
+        let kind = message();
+        let mut rx_network = RxNetwork::<ClassicFrame, 4, 512, CLASSIC_MTU>::default();
+        let (mut producer, mut consumer) = rx_network.split();
+        let late = Timestamp::from_micros(DEFAULT_TRANSFER_ID_TIMEOUT + 1);
+
+        let mut frames = frames_of::<ClassicFrame, CLASSIC_MTU>(&[0; 20], kind).into_iter();
+        let first = frames.next().unwrap();
+        let (data, len) = first.payload();
+        let restarted = ClassicFrame::from((first.id(), *data, len));
+        producer
+            .receive(first.with_timestamp(Timestamp::from_micros(0)))
+            .unwrap();
+        for frame in core::iter::once(restarted).chain(frames) {
+            producer.receive(frame.with_timestamp(late)).unwrap();
+        }
+
+        assert!(consumer.next().is_some());
+        assert_eq!(producer.statistics().timeouts, 1);
+        assert_eq!(producer.statistics().transfers, 1);
+    }
+}
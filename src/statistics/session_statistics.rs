@@ -0,0 +1,121 @@
+use super::rx_statistics::RxStatistics;
+use crate::{rx::rx_network::RxError, session_id::SessionKind, CanFrame};
+
+/// Counters of the first `N` sessions seen by a receiver, filled by
+/// [RxProducer::receive_with_sessions].
+///
+/// Frames whose ID is not valid do not belong to any session, and the
+/// sessions seen once the table is full are not counted.
+///
+/// [RxProducer::receive_with_sessions]: crate::rx::rx_network::RxProducer::receive_with_sessions
+#[derive(Debug)]
+pub struct SessionStatistics<const N: usize> {
+    sessions: [Option<(SessionKind, RxStatistics)>; N],
+}
+
+impl<const N: usize> Default for SessionStatistics<N> {
+    fn default() -> Self {
+        Self {
+            sessions: [None; N],
+        }
+    }
+}
+
+impl<const N: usize> SessionStatistics<N> {
+    pub fn get(&self, kind: SessionKind) -> Option<&RxStatistics> {
+        self.iter()
+            .find(|(session, _)| *session == kind)
+            .map(|(_, statistics)| statistics)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SessionKind, &RxStatistics)> + '_ {
+        self.sessions
+            .iter()
+            .flatten()
+            .map(|(session, statistics)| (*session, statistics))
+    }
+
+    pub fn clear(&mut self) {
+        self.sessions = [None; N];
+    }
+
+    pub(crate) fn record<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        kind: SessionKind,
+        ends_transfer: bool,
+        result: &Result<(), RxError<Frame, MTU>>,
+    ) {
+        if let Some(statistics) = self.session_mut(kind) {
+            statistics.record(ends_transfer, result);
+        }
+    }
+
+    pub(crate) fn record_timeout(&mut self, kind: SessionKind) {
+        if let Some(statistics) = self.session_mut(kind) {
+            statistics.timeouts += 1;
+        }
+    }
+
+    fn session_mut(&mut self, kind: SessionKind) -> Option<&mut RxStatistics> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|slot| matches!(slot, Some((session, _)) if *session == kind))
+            .or_else(|| self.sessions.iter().position(Option::is_none))?;
+
+        Some(
+            &mut self.sessions[index]
+                .get_or_insert((kind, RxStatistics::default()))
+                .1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::{NodeId, SubjectId, TransferPriority};
+    use crate::tests::ClassicFrame;
+    use crate::timestamp::Timestamp;
+    use crate::tx::transmitter::send;
+    use crate::tx::tx_queue::TxQueue;
+    use crate::CLASSIC_MTU;
+    use core::convert::TryFrom;
+
+    fn message(subject_id: u16) -> SessionKind {
+        SessionKind::Message {
+            source_node_id: NodeId::new(),
+            subject_id: SubjectId::try_from(subject_id).unwrap(),
+        }
+    }
+
+    #[test]
+    fn the_transfers_of_each_session_are_counted_separately_up_to_the_capacity() {
+        let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (mut producer, _) = rx_network.split();
+        let mut sessions = SessionStatistics::<2>::default();
+        let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+
+        for subject_id in [1, 2, 1, 3] {
+            send(
+                &mut queue,
+                &[0; 10],
+                message(subject_id),
+                TransferPriority::Nominal,
+            )
+            .unwrap();
+        }
+        while let Some(frame) = queue.pop_ready(Timestamp::default()) {
+            producer
+                .receive_with_sessions(frame, &mut sessions)
+                .unwrap();
+        }
+
+        assert_eq!(sessions.get(message(1)).unwrap().transfers, 2);
+        assert_eq!(sessions.get(message(1)).unwrap().frames, 4);
+        assert_eq!(sessions.get(message(2)).unwrap().transfers, 1);
+        assert_eq!(sessions.get(message(3)), None);
+        assert_eq!(producer.statistics().transfers, 4);
+    }
+}
@@ -0,0 +1,89 @@
+/// Counters kept by a transmitter.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TxStatistics {
+    /// The frames handed to the bus.
+    pub frames: u64,
+    /// The transfers whose last frame was handed to the bus.
+    pub transfers: u64,
+    /// The frames that were dropped because their deadline passed before
+    /// they could be sent.
+    pub timeouts: u64,
+    /// The frames that were dropped because they could not be queued or
+    /// written.
+    pub drops: u64,
+}
+
+impl TxStatistics {
+    pub(crate) fn record_sent(&mut self, ends_transfer: bool) {
+        self.frames += 1;
+        if ends_transfer {
+            self.transfers += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::{NodeId, SessionKind, SubjectId, TransferPriority};
+    use crate::tail_byte::TransferId;
+    use crate::tests::ClassicFrame;
+    use crate::timestamp::Timestamp;
+    use crate::tx::stream_transmitter::{CanWriter, StreamTransmitter};
+    use crate::tx::transmitter::send;
+    use crate::tx::tx_queue::TxQueue;
+    use crate::CLASSIC_MTU;
+
+    const KIND: SessionKind = SessionKind::Message {
+        source_node_id: NodeId::from_bytes([0]),
+        subject_id: SubjectId::from_const(0),
+    };
+
+    #[test]
+    fn a_tx_queue_counts_sent_expired_and_dropped_frames() {
+        let mut queue = TxQueue::<ClassicFrame, 4, CLASSIC_MTU>::default();
+
+        send(&mut queue, &[0; 10], KIND, TransferPriority::Nominal).unwrap();
+        queue
+            .send_with_deadline(
+                &[0; 1],
+                KIND,
+                TransferPriority::Optional,
+                TransferId::new(),
+                Timestamp::from_micros(10),
+            )
+            .unwrap();
+        assert!(send(&mut queue, &[0; 10], KIND, TransferPriority::Nominal).is_err());
+        while queue.pop_ready(Timestamp::from_micros(20)).is_some() {}
+
+        assert_eq!(
+            *queue.statistics(),
+            TxStatistics {
+                frames: 2,
+                transfers: 1,
+                timeouts: 1,
+                drops: 2,
+            }
+        );
+    }
+
+    struct FailingWriter;
+
+    impl CanWriter<ClassicFrame, CLASSIC_MTU> for FailingWriter {
+        type Error = ();
+
+        fn write_frame(&mut self, _frame: ClassicFrame) -> Result<(), Self::Error> {
+            Err(())
+        }
+    }
+
+    #[test]
+    fn a_stream_transmitter_counts_the_frames_that_could_not_be_written() {
+        let mut transmitter = StreamTransmitter::new(FailingWriter);
+
+        assert!(send(&mut transmitter, &[0; 10], KIND, TransferPriority::Nominal).is_err());
+
+        assert_eq!(transmitter.statistics().drops, 1);
+        assert_eq!(transmitter.statistics().frames, 0);
+    }
+}
@@ -40,22 +40,18 @@ impl TailByte {
         u8::from_le_bytes(self.into_bytes())
     }
 
-    // TODO: hack fix remember to do correctly
-    pub fn split_from<const SIZE: usize>(payload: (&[u8; SIZE], usize)) -> (&[u8], TailByte) {
-        // if payload[SIZE - 1] != 0 {
-        //     (&payload[..SIZE - 1], Self::from_bytes([payload[SIZE - 1]]))
-        // } else {
+    /// Splits the `len` bytes of a frame into its data and its tail byte, the
+    /// last byte. Returns `None` when there is no byte or when `len` exceeds
+    /// the bytes of the frame.
+    pub fn split_from<const SIZE: usize>(
+        payload: (&[u8; SIZE], usize),
+    ) -> Option<(&[u8], TailByte)> {
         let (payload, len) = payload;
-        // let mut tail_index: usize = SIZE - 1;
-        // for index in 0..SIZE - 1 {
-        //     if payload[SIZE - 1 - index] != 0 {
-        //         tail_index = SIZE - 1 - index;
-        //         break;
-        //     }
-        // }
+        if len == 0 || len > SIZE {
+            return None;
+        }
 
-        (&payload[..len - 1], Self::from_bytes([payload[len - 1]]))
-        // }
+        Some((&payload[..len - 1], Self::from_bytes([payload[len - 1]])))
     }
 
     pub fn from_u8(byte: u8) -> Self {
@@ -235,4 +231,10 @@ mod tests {
 
         assert_eq!(tail_byte.transfer_id(), original_transfer_id);
     }
+
+    #[test]
+    fn a_payload_without_bytes_or_longer_than_its_frame_has_no_tail_byte() {
+        assert!(TailByte::split_from((&[0xE0; 8], 0)).is_none());
+        assert!(TailByte::split_from((&[0xE0; 8], 9)).is_none());
+    }
 }
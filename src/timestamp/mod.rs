@@ -0,0 +1,3 @@
+mod timestamp;
+
+pub use timestamp::*;
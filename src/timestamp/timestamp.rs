@@ -0,0 +1,51 @@
+/// A monotonic instant expressed in microseconds since an arbitrary epoch,
+/// such as the boot time of the node.
+///
+/// The epoch is left to the driver that produces the frames, so that
+/// timestamps are only meaningful when compared with other timestamps coming
+/// from the same source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timestamp {
+    microseconds: u64,
+}
+
+impl Timestamp {
+    pub const fn from_micros(microseconds: u64) -> Self {
+        Self { microseconds }
+    }
+
+    pub const fn as_micros(&self) -> u64 {
+        self.microseconds
+    }
+
+    /// Returns the amount of microseconds elapsed from `earlier` to `self`,
+    /// or zero if `earlier` is later than `self`.
+    pub fn saturating_duration_since(&self, earlier: Timestamp) -> u64 {
+        self.microseconds.saturating_sub(earlier.microseconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn the_duration_between_two_timestamps_is_the_difference_of_their_microseconds(earlier in 0..u64::MAX / 2, elapsed in 0..u64::MAX / 2) {
+            let later = Timestamp::from_micros(earlier + elapsed);
+
+            prop_assert_eq!(later.saturating_duration_since(Timestamp::from_micros(earlier)), elapsed);
+        }
+
+        #[test]
+        fn the_duration_since_a_later_timestamp_is_zero(earlier in 0..u64::MAX - 1) {
+            let later = Timestamp::from_micros(earlier + 1);
+
+            prop_assert_eq!(Timestamp::from_micros(earlier).saturating_duration_since(later), 0);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use core::convert::{Infallible, TryFrom};
+
+use super::transport::Transport;
+use crate::{
+    rx::rx_network::RxConsumer,
+    session_id::{self, TransferPriority},
+    tail_byte::TransferId,
+    tx::transmitter::{send_with_transfer_id, Transmitter},
+    udp::{self, SessionKind, Transfer},
+    CanFrame,
+};
+
+#[derive(Debug)]
+pub enum SendError<E> {
+    Transmission(E),
+    /// The session has an anonymous source or a node ID beyond the 7 bits of
+    /// the CAN transport.
+    Session(udp::Error),
+}
+
+/// The CAN stack as a [Transport], sending through a [Transmitter] and
+/// receiving the transfers built up by the [RxProducer] of the same
+/// [RxNetwork] as `consumer`.
+///
+/// Transfer IDs are sent modulo 32, and received as such. Receiving errors
+/// are reported by the producer, so receiving from the transport cannot fail.
+///
+/// [RxProducer]: crate::rx::rx_network::RxProducer
+/// [RxNetwork]: crate::rx::rx_network::RxNetwork
+pub struct CanTransport<
+    'a,
+    Tx: Transmitter<Frame, MTU>,
+    Frame: CanFrame<MTU>,
+    const CAPACITY: usize,
+    const TRANSFER_CAPACITY: usize,
+    const MTU: usize,
+> {
+    transmitter: Tx,
+    consumer: RxConsumer<'a, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
+}
+
+impl<
+        'a,
+        Tx: Transmitter<Frame, MTU>,
+        Frame: CanFrame<MTU>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
+        const MTU: usize,
+    > CanTransport<'a, Tx, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
+{
+    pub fn new(
+        transmitter: Tx,
+        consumer: RxConsumer<'a, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>,
+    ) -> Self {
+        Self {
+            transmitter,
+            consumer,
+        }
+    }
+
+    pub fn transmitter(&mut self) -> &mut Tx {
+        &mut self.transmitter
+    }
+
+    pub fn into_parts(self) -> (Tx, RxConsumer<'a, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>) {
+        (self.transmitter, self.consumer)
+    }
+}
+
+impl<
+        Tx: Transmitter<Frame, MTU>,
+        Frame: CanFrame<MTU>,
+        const CAPACITY: usize,
+        const TRANSFER_CAPACITY: usize,
+        const MTU: usize,
+    > Transport<TRANSFER_CAPACITY>
+    for CanTransport<'_, Tx, Frame, CAPACITY, TRANSFER_CAPACITY, MTU>
+{
+    type SendError = SendError<Tx::Error>;
+    type ReceiveError = Infallible;
+
+    fn mtu(&self) -> usize {
+        MTU
+    }
+
+    fn max_node_id(&self) -> u16 {
+        127
+    }
+
+    fn send(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Result<(), Self::SendError> {
+        let kind = session_id::SessionKind::try_from(kind).map_err(SendError::Session)?;
+        let transfer_id = TransferId::try_from((transfer_id % 32) as u8).unwrap();
+
+        send_with_transfer_id(&mut self.transmitter, payload, kind, priority, transfer_id)
+            .map_err(SendError::Transmission)
+    }
+
+    fn receive(&mut self) -> Result<Option<Transfer<TRANSFER_CAPACITY>>, Self::ReceiveError> {
+        Ok(self.consumer.next().map(|transfer| Transfer {
+            payload: transfer.payload,
+            kind: transfer.kind.into(),
+            transfer_id: u64::from(u8::from_le_bytes(transfer.transfer_id.into_bytes())),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::rx::rx_network::RxNetwork;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{ClassicFrame, TxRxGlue};
+    use crate::tx::stream_transmitter::StreamTransmitter;
+    use crate::udp::NodeId;
+    use crate::CLASSIC_MTU;
+    use core::fmt::Debug;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    /// Sends `payload` over a transport that receives its own transfers, and
+    /// returns the transfer that it received, as a component written against
+    /// [Transport] would.
+    pub(crate) fn round_trip<T: Transport<CAPACITY>, const CAPACITY: usize>(
+        transport: &mut T,
+        payload: &[u8],
+        kind: SessionKind,
+        transfer_id: u64,
+    ) -> Transfer<CAPACITY>
+    where
+        T::SendError: Debug,
+        T::ReceiveError: Debug,
+    {
+        transport
+            .send(payload, kind, TransferPriority::Nominal, transfer_id)
+            .unwrap();
+
+        loop {
+            if let Some(transfer) = transport.receive().unwrap() {
+                return transfer;
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn a_transfer_sent_over_the_can_transport_is_received_unchanged(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 1..100), transfer_id in proptest::num::u64::ANY) {
+            let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+            let (rx_producer, rx_consumer) = rx_network.split();
+            let transmitter = StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+            let mut transport = CanTransport::new(transmitter, rx_consumer);
+
+            let transfer = round_trip(&mut transport, &payload, kind.into(), transfer_id);
+
+            prop_assert_eq!(transfer.kind, kind.into());
+            prop_assert_eq!(transfer.transfer_id, transfer_id % 32);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+        }
+    }
+
+    #[test]
+    fn node_ids_are_7_bits_wide() {
+        let mut rx_network = RxNetwork::<ClassicFrame, 64, 512, CLASSIC_MTU>::default();
+        let (rx_producer, rx_consumer) = rx_network.split();
+        let transmitter =
+            StreamTransmitter::<_, ClassicFrame, CLASSIC_MTU>::new(TxRxGlue { rx_producer });
+        let mut transport = CanTransport::new(transmitter, rx_consumer);
+
+        assert_eq!(transport.max_node_id(), 127);
+        assert!(matches!(
+            transport.send(
+                &[],
+                SessionKind::Message {
+                    source_node_id: Some(NodeId::try_from(128).unwrap()),
+                    subject_id: session_id::SubjectId::new(),
+                },
+                TransferPriority::Nominal,
+                0,
+            ),
+            Err(SendError::Session(udp::Error::NodeIdOutOfRange(128)))
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod can_transport;
+pub mod transport;
+
+pub use can_transport::CanTransport;
+pub use transport::Transport;
@@ -0,0 +1,40 @@
+use crate::{
+    session_id::TransferPriority,
+    udp::{SessionKind, Transfer},
+};
+
+/// A Cyphal transport, such as CAN, UDP or serial, exchanging transfers.
+///
+/// Components written against this trait, rather than against the frames of
+/// a transport, run unchanged over any of them.
+///
+/// Transfers are exchanged with the sessions and transfer IDs of Cyphal/UDP,
+/// whose 16 bits node IDs and 64 bits transfer IDs are the widest of the
+/// transports. A transport with narrower IDs, such as CAN, rejects the
+/// sessions beyond its [max_node_id](Self::max_node_id) and exchanges
+/// transfer IDs modulo its own range.
+///
+/// `CAPACITY` is the capacity of the payload of the received transfers.
+pub trait Transport<const CAPACITY: usize> {
+    type SendError;
+    type ReceiveError;
+
+    /// The largest payload carried by a single frame of the transport.
+    fn mtu(&self) -> usize;
+
+    /// The highest node ID that can be used on the transport, which depends
+    /// on the width of its node IDs.
+    fn max_node_id(&self) -> u16;
+
+    fn send(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Result<(), Self::SendError>;
+
+    /// Returns the next received transfer, or `None` if no transfer is
+    /// available yet, without waiting for one.
+    fn receive(&mut self) -> Result<Option<Transfer<CAPACITY>>, Self::ReceiveError>;
+}
@@ -0,0 +1,361 @@
+use core::{
+    future::{poll_fn, Future},
+    marker::PhantomData,
+    task::{Poll, Waker},
+};
+
+use super::{breakdown::Breakdown, transmitter::Transmitter};
+use crate::{
+    session_id::{can_id_for_session_kind, SessionKind, TransferPriority},
+    tail_byte::TransferId,
+    CanFrame,
+};
+
+/// The asynchronous counterpart of
+/// [CanWriter](super::stream_transmitter::CanWriter), for drivers that
+/// await the room to write a frame.
+pub trait AsyncCanWriter<Frame: CanFrame<MTU>, const MTU: usize> {
+    type Error;
+
+    fn write_frame(&mut self, frame: Frame)
+        -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// The asynchronous counterpart of [Transmitter].
+pub trait AsyncTransmitter<Frame: CanFrame<MTU>, const MTU: usize> {
+    type Error;
+
+    fn transmit(&mut self, frame: Frame) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Waits until `frames_count` frames can be transmitted, so that the
+    /// frames of a transfer are never partially transmitted.
+    fn wait_for_space(
+        &mut self,
+        _frames_count: usize,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// A transmitter whose space is freed elsewhere, such as a [TxQueue] whose
+/// frames are popped by the interrupt that feeds the hardware.
+///
+/// [TxQueue]: super::tx_queue::TxQueue
+pub trait WakeOnSpace {
+    /// Registers `waker` to be woken the next time frames leave the
+    /// transmitter, in place of the waker registered before.
+    fn register_space_waker(&mut self, waker: &Waker);
+}
+
+pub struct AsyncStreamTransmitter<
+    Writer: AsyncCanWriter<Frame, MTU>,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+> {
+    writer: Writer,
+    _marker: PhantomData<Frame>,
+}
+
+impl<Writer: AsyncCanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>
+    AsyncStreamTransmitter<Writer, Frame, MTU>
+{
+    pub fn new(writer: Writer) -> Self {
+        Self {
+            writer,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn into_writer(self) -> Writer {
+        self.writer
+    }
+}
+
+impl<Writer: AsyncCanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>
+    AsyncTransmitter<Frame, MTU> for AsyncStreamTransmitter<Writer, Frame, MTU>
+{
+    type Error = Writer::Error;
+
+    fn transmit(&mut self, frame: Frame) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.writer.write_frame(frame)
+    }
+}
+
+/// Adapts a [Transmitter] whose space is freed elsewhere, such as a handle to
+/// a [TxQueue](super::tx_queue::TxQueue) shared with the interrupt that feeds
+/// the hardware, so that sending a transfer waits for space instead of
+/// failing.
+///
+/// The waiting task is woken by the transmitter, through [WakeOnSpace], each
+/// time frames leave it. A transfer with more frames than the transmitter can
+/// ever hold waits forever.
+pub struct Backpressure<T>(pub T);
+
+impl<T: Transmitter<Frame, MTU> + WakeOnSpace + Send, Frame: CanFrame<MTU>, const MTU: usize>
+    AsyncTransmitter<Frame, MTU> for Backpressure<T>
+where
+    T::Error: Send,
+{
+    type Error = T::Error;
+
+    fn transmit(&mut self, frame: Frame) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        let result = self.0.transmit(frame);
+
+        async { result }
+    }
+
+    fn wait_for_space(
+        &mut self,
+        frames_count: usize,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        poll_fn(move |cx| {
+            if self.0.ensure_available_space(frames_count).is_ok() {
+                return Poll::Ready(Ok(()));
+            }
+            self.0.register_space_waker(cx.waker());
+
+            // The space may have been freed before the waker was registered.
+            match self.0.ensure_available_space(frames_count) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(_) => Poll::Pending,
+            }
+        })
+    }
+}
+
+pub async fn send_async<T: AsyncTransmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
+    transmitter: &mut T,
+    payload: &[u8],
+    kind: SessionKind,
+    priority: TransferPriority,
+) -> Result<(), T::Error> {
+    send_with_transfer_id_async(transmitter, payload, kind, priority, TransferId::new()).await
+}
+
+/// Sends `payload` as a transfer identified by `transfer_id`, waiting for the
+/// transmitter to have room for all of its frames.
+pub async fn send_with_transfer_id_async<
+    T: AsyncTransmitter<Frame, MTU>,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+>(
+    transmitter: &mut T,
+    payload: &[u8],
+    kind: SessionKind,
+    priority: TransferPriority,
+    transfer_id: TransferId,
+) -> Result<(), T::Error> {
+    let can_id = can_id_for_session_kind(kind, priority);
+    let breakdown = Breakdown::with_transfer_id(payload, can_id, transfer_id);
+
+    transmitter.wait_for_space(breakdown.frames_count()).await?;
+    for frame in breakdown {
+        transmitter.transmit(frame).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{message, ClassicFrame};
+    use crate::timestamp::Timestamp;
+    use crate::tx::transmitter::send;
+    use crate::tx::tx_queue::{TxQueue, TxQueueError};
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use core::{pin::Pin, task::Context};
+
+    extern crate std;
+    use std::{
+        boxed::Box,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        task::Wake,
+        thread::{self, Thread},
+        vec::Vec,
+    };
+
+    /// Wakes the thread blocked on a future.
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Polls `future` until it completes, parking the thread until the future
+    /// is woken, as a minimal executor.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    /// Yields once to the executor, so that other tasks can make progress.
+    struct YieldNow {
+        has_yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.has_yielded {
+                Poll::Ready(())
+            } else {
+                self.has_yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// A handle to a queue shared with the code that pops its frames.
+    struct SharedQueue(Arc<Mutex<TxQueue<ClassicFrame, 4, CLASSIC_MTU>>>);
+
+    impl Transmitter<ClassicFrame, CLASSIC_MTU> for SharedQueue {
+        type Error = TxQueueError;
+
+        fn transmit(&mut self, frame: ClassicFrame) -> Result<(), Self::Error> {
+            self.0.lock().unwrap().transmit(frame)
+        }
+
+        fn ensure_available_space(&self, frames_count: usize) -> Result<(), Self::Error> {
+            self.0.lock().unwrap().ensure_available_space(frames_count)
+        }
+    }
+
+    impl WakeOnSpace for SharedQueue {
+        fn register_space_waker(&mut self, waker: &Waker) {
+            self.0.lock().unwrap().register_space_waker(waker)
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingWriter {
+        frames: Vec<ClassicFrame>,
+    }
+
+    impl AsyncCanWriter<ClassicFrame, CLASSIC_MTU> for RecordingWriter {
+        type Error = ();
+
+        async fn write_frame(&mut self, frame: ClassicFrame) -> Result<(), Self::Error> {
+            YieldNow { has_yielded: false }.await;
+            self.frames.push(frame);
+            Ok(())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn sending_asynchronously_writes_the_same_frames_as_sending_synchronously(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut transmitter = AsyncStreamTransmitter::new(RecordingWriter::default());
+            block_on(send_async(&mut transmitter, &payload, kind, TransferPriority::Nominal)).unwrap();
+
+            let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+            send(&mut queue, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            let written = transmitter.into_writer().frames;
+            prop_assert_eq!(written.len(), queue.len());
+            for frame in written {
+                let expected = queue.pop_ready(Timestamp::default()).unwrap();
+                prop_assert_eq!((frame.id(), frame.payload()), (expected.id(), expected.payload()));
+            }
+        }
+    }
+
+    #[test]
+    fn sending_through_backpressure_waits_for_space_instead_of_failing() {
+        let mut transmitter = Backpressure(TxQueue::<ClassicFrame, 4, CLASSIC_MTU>::default());
+        let kind = message();
+
+        block_on(send_async(
+            &mut transmitter,
+            &[0; 14],
+            kind,
+            TransferPriority::Nominal,
+        ))
+        .unwrap();
+        assert_eq!(
+            send(
+                &mut transmitter.0,
+                &[0; 14],
+                kind,
+                TransferPriority::Nominal
+            ),
+            Err(TxQueueError::OutOfSpace)
+        );
+
+        let mut sending = Box::pin(send_async(
+            &mut transmitter,
+            &[0; 14],
+            kind,
+            TransferPriority::Nominal,
+        ));
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(sending.as_mut().poll(&mut context).is_pending());
+        assert!(sending.as_mut().poll(&mut context).is_pending());
+    }
+
+    #[test]
+    fn a_transfer_waiting_for_space_is_woken_once_frames_leave_the_queue() {
+        let queue = Arc::new(Mutex::new(
+            TxQueue::<ClassicFrame, 4, CLASSIC_MTU>::default(),
+        ));
+        let mut transmitter = Backpressure(SharedQueue(queue.clone()));
+        block_on(send_async(
+            &mut transmitter,
+            &[0; 14],
+            message(),
+            TransferPriority::Nominal,
+        ))
+        .unwrap();
+
+        let wakes = Arc::new(CountingWaker::default());
+        let waker = Waker::from(wakes.clone());
+        let mut context = Context::from_waker(&waker);
+        let mut sending = Box::pin(send_async(
+            &mut transmitter,
+            &[0; 14],
+            message(),
+            TransferPriority::Nominal,
+        ));
+        assert!(sending.as_mut().poll(&mut context).is_pending());
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        for _ in 0..3 {
+            queue
+                .lock()
+                .unwrap()
+                .pop_ready(Timestamp::default())
+                .unwrap();
+        }
+
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(sending.as_mut().poll(&mut context), Poll::Ready(Ok(())));
+    }
+}
@@ -8,6 +8,7 @@ use core::{marker::PhantomData, slice::ChunksExact};
 use crc_any::CRCu16;
 
 use crate::{
+    padded_frame_length,
     tail_byte::{TailByte, TransferId},
     CanFrame,
 };
@@ -19,15 +20,18 @@ pub enum BreakdownKind {
 }
 
 pub fn breakdown_kind_for_payload<const MTU: usize>(payload: &[u8]) -> BreakdownKind {
-    let payload_size = payload.len();
+    breakdown_kind_for_length::<MTU>(payload.len())
+}
 
+/// The kind of breakdown of a payload of `payload_size` bytes, along with its
+/// number of frames when it spans several frames.
+pub fn breakdown_kind_for_length<const MTU: usize>(payload_size: usize) -> BreakdownKind {
     if payload_size < MTU {
         BreakdownKind::SingleFrame
     } else {
-        let frame_count_without_crc_frame = payload_size / MTU;
-        let frame_count = frame_count_without_crc_frame + 1;
-
-        BreakdownKind::MultiFrame(frame_count)
+        // The payload is followed by the two bytes of the crc, spread over
+        // frames that carry MTU-1 bytes each.
+        BreakdownKind::MultiFrame((payload_size + 2).div_ceil(MTU - 1))
     }
 }
 
@@ -110,16 +114,43 @@ fn crc_kind<const MTU: usize>(payload_remainder: usize) -> CRCKind {
     }
 }
 
-fn build_frame<Frame: CanFrame<MTU>, const MTU: usize>(
+/// The amount of zeros needed after `length` bytes of data, tail byte
+/// included, for the frame to have a valid CAN FD length.
+fn padding_for<const MTU: usize>(length: usize) -> usize {
+    padded_frame_length(length).min(MTU) - length
+}
+
+/// Appends the crc to the `len` bytes of data of the last frame of a
+/// transfer, after the zeros that pad the frame to a valid CAN FD length,
+/// which the crc covers. Returns the length of the data of the frame.
+pub(super) fn append_crc<const MTU: usize>(
+    data: &mut [u8; MTU],
+    len: usize,
+    crc: &mut CRCu16,
+) -> usize {
+    let data_len = len + padding_for::<MTU>(len + 3);
+    data[len..data_len].fill(0);
+    crc.digest(&data[len..data_len]);
+
+    data[data_len..data_len + 2].copy_from_slice(&crc.get_crc().to_be_bytes());
+
+    data_len + 2
+}
+
+/// Builds a frame carrying `data` and `tail_byte`, padded with zeros before the
+/// tail byte when needed for the frame to have a valid CAN FD length.
+pub(super) fn build_frame<Frame: CanFrame<MTU>, const MTU: usize>(
     can_id: u32,
     data: &[u8],
     tail_byte: TailByte,
 ) -> Frame {
+    let len = data.len() + padding_for::<MTU>(data.len() + 1) + 1;
+
     let mut payload = [0u8; MTU];
     payload[..data.len()].copy_from_slice(data);
-    payload[data.len()] = tail_byte.into_u8();
+    payload[len - 1] = tail_byte.into_u8();
 
-    Frame::from((can_id, payload, data.len() + 1))
+    Frame::from((can_id, payload, len))
 }
 
 #[derive(Debug)]
@@ -150,10 +181,14 @@ pub struct Breakdown<'a, Frame: CanFrame<MTU>, const MTU: usize> {
 
 impl<'a, Frame: CanFrame<MTU>, const MTU: usize> Breakdown<'a, Frame, MTU> {
     pub fn new(payload: &'a [u8], can_id: u32) -> Self {
+        Self::with_transfer_id(payload, can_id, TransferId::new())
+    }
+
+    pub fn with_transfer_id(payload: &'a [u8], can_id: u32, transfer_id: TransferId) -> Self {
         let breakdown_kind = breakdown_kind_for_payload::<MTU>(payload);
         let tail_byte = match breakdown_kind {
-            BreakdownKind::SingleFrame => TailByte::single_frame(TransferId::new()),
-            BreakdownKind::MultiFrame(_) => TailByte::start_of_multi_frame(TransferId::new()),
+            BreakdownKind::SingleFrame => TailByte::single_frame(transfer_id),
+            BreakdownKind::MultiFrame(_) => TailByte::start_of_multi_frame(transfer_id),
         };
 
         Self {
@@ -166,9 +201,20 @@ impl<'a, Frame: CanFrame<MTU>, const MTU: usize> Breakdown<'a, Frame, MTU> {
         }
     }
 
-    // TODO: this need to change considering the new change to CRC positioning
+    /// The number of frames that are still to be produced by the breakdown.
     pub fn frames_count(&self) -> usize {
-        self.payload.size_hint().0 + 1
+        match self.state {
+            BreakdownState::SingleFrame | BreakdownState::MultiFrameHalfCRC => 1,
+            BreakdownState::Closed => 0,
+            BreakdownState::MultiFrame => {
+                // The remaining data is followed by the two bytes of the crc,
+                // spread over frames that carry MTU-1 bytes each.
+                let remaining_data =
+                    self.payload.len() * (MTU - 1) + self.payload.remainder().len();
+
+                (remaining_data + 2 + (MTU - 2)) / (MTU - 1)
+            }
+        }
     }
 
     pub fn transfer_id(&self) -> TransferId {
@@ -201,17 +247,15 @@ impl<'a, Frame: CanFrame<MTU>, const MTU: usize> Breakdown<'a, Frame, MTU> {
 
                 match crc_kind::<MTU>(data.len()) {
                     CRCKind::Embedded => {
-                        let crc_bytes = self.crc.get_crc().to_be_bytes();
-
                         let mut data_with_crc = [0u8; MTU];
                         data_with_crc[..data.len()].copy_from_slice(data);
-                        data_with_crc[data.len()..data.len() + 2].copy_from_slice(&crc_bytes);
+                        let len = append_crc(&mut data_with_crc, data.len(), &mut self.crc);
 
                         self.state = BreakdownState::Closed;
 
                         build_frame(
                             self.can_id,
-                            &data_with_crc[..data.len() + 2],
+                            &data_with_crc[..len],
                             self.tail_byte.end_of_multi_transfer(),
                         )
                     }
@@ -280,11 +324,15 @@ mod tests {
     extern crate std;
     use std::format;
 
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+
+    use crate::rx::rx_network::RxNetwork;
     use crate::session_id::can_id_for_session_kind;
     use crate::session_id::session_kind::strategy::session_kind;
     use crate::session_id::transfer_priority::strategy::transfer_priority;
-    use crate::tests::ClassicFrame;
-    use crate::CLASSIC_MTU;
+    use crate::tests::{ClassicFrame, FdFrame};
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
 
     proptest! {
         #[test]
@@ -341,5 +389,65 @@ mod tests {
 
             prop_assert_eq!(payload_len, 1);
         }
+
+        #[test]
+        fn the_frames_count_of_a_breakdown_is_the_number_of_frames_it_produces(payload in vec(proptest::num::u8::ANY, 0..100), consumed in 0..20usize) {
+            let mut breakdown = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, 0);
+            for _ in 0..consumed {
+                breakdown.next();
+            }
+
+            prop_assert_eq!(breakdown.frames_count(), breakdown.count());
+        }
+
+        #[test]
+        fn every_frame_of_a_breakdown_carries_the_transfer_id_it_was_built_with(payload in vec(proptest::num::u8::ANY, 0..100), id in 0..32u8) {
+            let transfer_id = TransferId::try_from(id).unwrap();
+            let breakdown = Breakdown::<ClassicFrame, CLASSIC_MTU>::with_transfer_id(&payload, 0, transfer_id);
+
+            for frame in breakdown {
+                let (_, tail_byte) = TailByte::split_from(frame.payload()).unwrap();
+                prop_assert_eq!(tail_byte.get_transfer_id(), transfer_id);
+            }
+        }
+
+        #[test]
+        fn every_can_fd_frame_of_a_breakdown_has_a_length_its_dlc_can_encode(payload in vec(proptest::num::u8::ANY, 0..300)) {
+            for frame in Breakdown::<FdFrame, EXTENDED_MTU>::new(&payload, 0) {
+                let (_, len) = frame.payload();
+                prop_assert_eq!(padded_frame_length(len), len);
+            }
+        }
+
+        #[test]
+        fn a_padded_can_fd_transfer_is_received_with_its_padding_covered_by_the_crc(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..300)) {
+            let mut network = RxNetwork::<FdFrame, 4, 512, EXTENDED_MTU>::default();
+            let (mut producer, mut consumer) = network.split();
+            let can_id = can_id_for_session_kind(kind, crate::session_id::TransferPriority::Nominal);
+
+            for frame in Breakdown::<FdFrame, EXTENDED_MTU>::new(&payload, can_id) {
+                producer.receive(frame).unwrap();
+            }
+
+            let transfer = consumer.next().unwrap();
+            let (received, padding) = transfer.payload.split_at(payload.len());
+            prop_assert_eq!(received, payload.as_slice());
+            prop_assert!(padding.iter().all(|byte| *byte == 0));
+        }
+    }
+
+    #[test]
+    fn a_single_frame_of_12_bytes_is_padded_to_16_with_the_tail_byte_last() {
+        let mut breakdown = Breakdown::<FdFrame, EXTENDED_MTU>::new(&[7; 12], 0);
+        let frame = breakdown.next().unwrap();
+        let (data, len) = frame.payload();
+
+        assert_eq!(len, 16);
+        assert_eq!(data[..12], [7; 12]);
+        assert_eq!(data[12..15], [0; 3]);
+        assert_eq!(
+            data[15],
+            TailByte::single_frame(TransferId::new()).into_u8()
+        );
     }
 }
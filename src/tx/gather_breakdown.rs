@@ -0,0 +1,218 @@
+use core::marker::PhantomData;
+
+use crc_any::CRCu16;
+
+use super::breakdown::{append_crc, breakdown_kind_for_length, build_frame, BreakdownKind};
+use crate::{
+    tail_byte::{TailByte, TransferId},
+    CanFrame,
+};
+
+/// Breaks down a payload that is scattered over several slices into frames,
+/// without gathering it into a contiguous buffer first.
+///
+/// The slices are read in order and their bytes are copied straight into the
+/// frames, while the CRC is computed incrementally. The frames are laid out
+/// by the same helpers as a [Breakdown] of the whole payload, so both produce
+/// the same frames.
+///
+/// `Slices` is cloned once to compute the length of the payload, so it
+/// should be a cheap iterator such as `[header, body].iter().copied()`.
+///
+/// [Breakdown]: super::breakdown::Breakdown
+pub struct GatherBreakdown<
+    'a,
+    Slices: Iterator<Item = &'a [u8]>,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+> {
+    slices: Slices,
+    current: &'a [u8],
+    crc: CRCu16,
+    /// The number of bytes of the CRC that were already written, or `None`
+    /// for a single frame transfer, which carries no CRC.
+    written_crc_bytes: Option<usize>,
+    remaining_frames: usize,
+    can_id: u32,
+    tail_byte: TailByte,
+    _frame_marker: PhantomData<Frame>,
+}
+
+impl<'a, Slices: Iterator<Item = &'a [u8]> + Clone, Frame: CanFrame<MTU>, const MTU: usize>
+    GatherBreakdown<'a, Slices, Frame, MTU>
+{
+    pub fn new(slices: Slices, can_id: u32) -> Self {
+        Self::with_transfer_id(slices, can_id, TransferId::new())
+    }
+
+    pub fn with_transfer_id(slices: Slices, can_id: u32, transfer_id: TransferId) -> Self {
+        let payload_size: usize = slices.clone().map(<[u8]>::len).sum();
+
+        let (tail_byte, written_crc_bytes, remaining_frames) =
+            match breakdown_kind_for_length::<MTU>(payload_size) {
+                BreakdownKind::SingleFrame => (TailByte::single_frame(transfer_id), None, 1),
+                BreakdownKind::MultiFrame(frames_count) => (
+                    TailByte::start_of_multi_frame(transfer_id),
+                    Some(0),
+                    frames_count,
+                ),
+            };
+
+        Self {
+            slices,
+            current: &[],
+            crc: CRCu16::crc16ccitt_false(),
+            written_crc_bytes,
+            remaining_frames,
+            can_id,
+            tail_byte,
+            _frame_marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Slices: Iterator<Item = &'a [u8]>, Frame: CanFrame<MTU>, const MTU: usize>
+    GatherBreakdown<'a, Slices, Frame, MTU>
+{
+    /// The number of frames that are still to be produced by the breakdown.
+    pub fn frames_count(&self) -> usize {
+        self.remaining_frames
+    }
+
+    pub fn transfer_id(&self) -> TransferId {
+        self.tail_byte.get_transfer_id()
+    }
+
+    /// Fills `data` with the next bytes of the payload, returning how many
+    /// were written.
+    fn gather(&mut self, data: &mut [u8]) -> usize {
+        let mut len = 0;
+
+        while len < data.len() {
+            if self.current.is_empty() {
+                match self.slices.next() {
+                    Some(slice) => self.current = slice,
+                    None => break,
+                }
+            }
+
+            let count = self.current.len().min(data.len() - len);
+            let (chunk, rest) = self.current.split_at(count);
+            data[len..len + count].copy_from_slice(chunk);
+            self.crc.digest(chunk);
+            self.current = rest;
+            len += count;
+        }
+
+        len
+    }
+}
+
+impl<'a, Slices: Iterator<Item = &'a [u8]>, Frame: CanFrame<MTU>, const MTU: usize> Iterator
+    for GatherBreakdown<'a, Slices, Frame, MTU>
+{
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining_frames == 0 {
+            return None;
+        }
+        self.remaining_frames -= 1;
+
+        let mut data = [0u8; MTU];
+        let mut len = self.gather(&mut data[..MTU - 1]);
+
+        match self.written_crc_bytes.as_mut() {
+            // The whole crc ends the last frame, as with an embedded crc.
+            Some(written) if *written == 0 && self.remaining_frames == 0 => {
+                len = append_crc(&mut data, len, &mut self.crc);
+                *written = 2;
+            }
+            Some(written) => {
+                let crc_bytes = self.crc.get_crc().to_be_bytes();
+                while len < MTU - 1 && *written < crc_bytes.len() {
+                    data[len] = crc_bytes[*written];
+                    *written += 1;
+                    len += 1;
+                }
+            }
+            None => {}
+        }
+
+        let tail_byte = match (self.written_crc_bytes, self.remaining_frames) {
+            (Some(_), 0) => self.tail_byte.end_of_multi_transfer(),
+            _ => self.tail_byte,
+        };
+        self.tail_byte.advance();
+
+        Some(build_frame(self.can_id, &data[..len], tail_byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{ClassicFrame, FdFrame};
+    use crate::tx::breakdown::Breakdown;
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    fn frame_contents<Frame: CanFrame<MTU>, const MTU: usize>(frame: Frame) -> (u32, Vec<u8>) {
+        let (data, len) = frame.payload();
+
+        (frame.id(), data[..len].to_vec())
+    }
+
+    proptest! {
+        #[test]
+        fn a_gathered_payload_is_broken_down_into_the_same_frames_as_the_contiguous_payload(payload in vec(proptest::num::u8::ANY, 0..100), splits in vec(0..100usize, 0..5), id in 0..32u8) {
+            let transfer_id = TransferId::try_from(id).unwrap();
+            let mut splits: Vec<_> = splits.into_iter().map(|split| split.min(payload.len())).collect();
+            splits.sort_unstable();
+            let slices: Vec<&[u8]> = core::iter::once(0)
+                .chain(splits.iter().copied())
+                .zip(splits.iter().copied().chain(core::iter::once(payload.len())))
+                .map(|(start, end)| &payload[start..end])
+                .collect();
+
+            let gathered: Vec<_> = GatherBreakdown::<_, ClassicFrame, CLASSIC_MTU>::with_transfer_id(slices.iter().copied(), 42, transfer_id)
+                .map(frame_contents)
+                .collect();
+            let contiguous: Vec<_> = Breakdown::<ClassicFrame, CLASSIC_MTU>::with_transfer_id(&payload, 42, transfer_id)
+                .map(frame_contents)
+                .collect();
+
+            prop_assert_eq!(gathered, contiguous);
+        }
+
+        #[test]
+        fn a_gathered_payload_is_padded_like_the_contiguous_payload_on_can_fd(payload in vec(proptest::num::u8::ANY, 0..300), split in 0..300usize) {
+            let (header, body) = payload.split_at(split.min(payload.len()));
+
+            let gathered: Vec<_> = GatherBreakdown::<_, FdFrame, EXTENDED_MTU>::new([header, body].iter().copied(), 42)
+                .map(frame_contents)
+                .collect();
+            let contiguous: Vec<_> = Breakdown::<FdFrame, EXTENDED_MTU>::new(&payload, 42)
+                .map(frame_contents)
+                .collect();
+
+            prop_assert_eq!(gathered, contiguous);
+        }
+
+        #[test]
+        fn the_frames_count_of_a_gather_breakdown_is_the_number_of_frames_it_produces(payload in vec(proptest::num::u8::ANY, 0..100), consumed in 0..20usize) {
+            let slices = [&payload[..payload.len() / 2], &payload[payload.len() / 2..]];
+            let mut breakdown = GatherBreakdown::<_, ClassicFrame, CLASSIC_MTU>::new(slices.iter().copied(), 0);
+            for _ in 0..consumed {
+                breakdown.next();
+            }
+
+            prop_assert_eq!(breakdown.frames_count(), breakdown.count());
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use super::transmitter::{send_with_transfer_id, Transmitter};
+use crate::{
+    session_id::{SessionId, SessionKind, TransferPriority},
+    tail_byte::{PayloadKind, TailByte, TransferId},
+    timestamp::Timestamp,
+    CanFrame,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoopbackError<E> {
+    /// The tracker is already waiting for as many transfers as it can hold.
+    OutOfSpace,
+    Transmission(E),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct OutOfSpace {}
+
+/// Reports that a loopback transfer was transmitted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TxConfirmation {
+    pub kind: SessionKind,
+    pub transfer_id: TransferId,
+    /// The instant at which the first frame of the transfer left the
+    /// controller.
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Pending {
+    kind: SessionKind,
+    transfer_id: TransferId,
+    timestamp: Option<Timestamp>,
+    /// The instant after which the transfer is abandoned by
+    /// [LoopbackTracker::expire] if none of its frames was transmitted.
+    deadline: Option<Timestamp>,
+}
+
+/// Keeps track of up to `N` loopback transfers, which are waiting for the
+/// driver to report that their frames were transmitted.
+///
+/// The driver passes each frame that it transmitted to [Self::confirm], for
+/// example from a TX-complete interrupt or when a `SocketCan` reports a
+/// transmitted frame. Frames of the transfers that were not sent as loopback
+/// are ignored.
+///
+/// A transfer that will never be transmitted, because the driver dropped its
+/// frames, is released with [Self::cancel], or with [Self::expire] once the
+/// deadline it was tracked with has passed.
+#[derive(Debug)]
+pub struct LoopbackTracker<const N: usize> {
+    pending: [Option<Pending>; N],
+}
+
+impl<const N: usize> Default for LoopbackTracker<N> {
+    fn default() -> Self {
+        Self { pending: [None; N] }
+    }
+}
+
+impl<const N: usize> LoopbackTracker<N> {
+    /// Marks the transfer identified by `kind` and `transfer_id` as loopback.
+    pub fn track(&mut self, kind: SessionKind, transfer_id: TransferId) -> Result<(), OutOfSpace> {
+        self.insert(Pending {
+            kind,
+            transfer_id,
+            timestamp: None,
+            deadline: None,
+        })
+    }
+
+    /// Marks the transfer identified by `kind` and `transfer_id` as loopback,
+    /// until `deadline` if none of its frames was transmitted by then, as
+    /// with a transfer queued with [TxQueue::send_with_deadline].
+    ///
+    /// [TxQueue::send_with_deadline]: super::tx_queue::TxQueue::send_with_deadline
+    pub fn track_until(
+        &mut self,
+        kind: SessionKind,
+        transfer_id: TransferId,
+        deadline: Timestamp,
+    ) -> Result<(), OutOfSpace> {
+        self.insert(Pending {
+            kind,
+            transfer_id,
+            timestamp: None,
+            deadline: Some(deadline),
+        })
+    }
+
+    /// Stops waiting for the transfer identified by `kind` and `transfer_id`,
+    /// returning whether it was tracked.
+    pub fn cancel(&mut self, kind: SessionKind, transfer_id: TransferId) -> bool {
+        self.untrack(kind, transfer_id).is_some()
+    }
+
+    /// Stops waiting for the transfers whose deadline has passed at `now`
+    /// before their first frame was transmitted, returning how many were
+    /// abandoned.
+    pub fn expire(&mut self, now: Timestamp) -> usize {
+        let mut expired = 0;
+        for slot in &mut self.pending {
+            let is_expired = slot.is_some_and(|pending| {
+                pending.timestamp.is_none()
+                    && pending.deadline.is_some_and(|deadline| now > deadline)
+            });
+            if is_expired {
+                *slot = None;
+                expired += 1;
+            }
+        }
+
+        expired
+    }
+
+    /// The number of transfers that are still waiting for a confirmation.
+    pub fn len(&self) -> usize {
+        self.pending.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&mut self, pending: Pending) -> Result<(), OutOfSpace> {
+        let slot = self
+            .pending
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(OutOfSpace {})?;
+        *slot = Some(pending);
+
+        Ok(())
+    }
+
+    fn untrack(&mut self, kind: SessionKind, transfer_id: TransferId) -> Option<Pending> {
+        self.pending
+            .iter_mut()
+            .find(|slot| {
+                matches!(slot, Some(pending) if pending.kind == kind && pending.transfer_id == transfer_id)
+            })
+            .and_then(Option::take)
+    }
+
+    /// Accepts a frame that was transmitted at `timestamp`, returning the
+    /// confirmation of the loopback transfer that it completes, if any.
+    pub fn confirm<Frame: CanFrame<MTU>, const MTU: usize>(
+        &mut self,
+        frame: &Frame,
+        timestamp: Timestamp,
+    ) -> Option<TxConfirmation> {
+        let session_id = SessionId::from(frame.id());
+        if !session_id.is_valid() {
+            return None;
+        }
+
+        let kind = SessionKind::from(session_id);
+        let (_, tail_byte) = TailByte::split_from(frame.payload())?;
+        let transfer_id = tail_byte.get_transfer_id();
+
+        match tail_byte.payload_kind() {
+            PayloadKind::SingleFrame | PayloadKind::EndOfMultiFrame => (),
+            PayloadKind::StartOfMultiFrame => {
+                if let Some(pending) = self
+                    .pending
+                    .iter_mut()
+                    .flatten()
+                    .find(|pending| pending.kind == kind && pending.transfer_id == transfer_id)
+                {
+                    pending.timestamp = Some(timestamp);
+                }
+
+                return None;
+            }
+            PayloadKind::MiddleOfMultiFrame => return None,
+        }
+
+        self.untrack(kind, transfer_id)
+            .map(|pending| TxConfirmation {
+                kind,
+                transfer_id,
+                timestamp: pending.timestamp.unwrap_or(timestamp),
+            })
+    }
+}
+
+/// Sends `payload` as a loopback transfer, whose transmission is confirmed
+/// through `tracker`.
+pub fn send_with_loopback<
+    T: Transmitter<Frame, MTU>,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+    const N: usize,
+>(
+    transmitter: &mut T,
+    tracker: &mut LoopbackTracker<N>,
+    payload: &[u8],
+    kind: SessionKind,
+    priority: TransferPriority,
+    transfer_id: TransferId,
+) -> Result<(), LoopbackError<T::Error>> {
+    tracker
+        .track(kind, transfer_id)
+        .map_err(|_| LoopbackError::OutOfSpace)?;
+
+    send_with_transfer_id(transmitter, payload, kind, priority, transfer_id).map_err(|err| {
+        tracker.untrack(kind, transfer_id);
+
+        LoopbackError::Transmission(err)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::ClassicFrame;
+    use crate::tx::transmitter::send;
+    use crate::tx::tx_queue::TxQueue;
+    use crate::CLASSIC_MTU;
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_loopback_transfer_is_confirmed_with_the_timestamp_of_its_first_frame(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100), transfer_id in 0u8..32) {
+            let transfer_id = TransferId::try_from(transfer_id).unwrap();
+            let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+            let mut tracker = LoopbackTracker::<1>::default();
+
+            send_with_loopback(&mut queue, &mut tracker, &payload, kind, TransferPriority::Nominal, transfer_id).unwrap();
+
+            let mut confirmations = std::vec::Vec::new();
+            let mut now = 1000;
+            while let Some(frame) = queue.pop_ready(Timestamp::from_micros(now)) {
+                confirmations.extend(tracker.confirm(&frame, Timestamp::from_micros(now)));
+                now += 10;
+            }
+
+            prop_assert_eq!(confirmations, std::vec![TxConfirmation { kind, transfer_id, timestamp: Timestamp::from_micros(1000) }]);
+            prop_assert!(tracker.is_empty());
+        }
+
+        #[test]
+        fn transfers_that_are_not_loopback_are_not_confirmed(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+            let mut tracker = LoopbackTracker::<1>::default();
+
+            send(&mut queue, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            while let Some(frame) = queue.pop_ready(Timestamp::default()) {
+                prop_assert_eq!(tracker.confirm(&frame, Timestamp::default()), None);
+            }
+        }
+    }
+
+    #[test]
+    fn a_transfer_that_could_not_be_transmitted_is_no_longer_tracked() {
+        let kind = SessionKind::Message {
+            source_node_id: crate::session_id::NodeId::new(),
+            subject_id: crate::session_id::SubjectId::new(),
+        };
+        let mut queue = TxQueue::<ClassicFrame, 4, CLASSIC_MTU>::default();
+        let mut tracker = LoopbackTracker::<1>::default();
+
+        assert!(matches!(
+            send_with_loopback(
+                &mut queue,
+                &mut tracker,
+                &[0; 64],
+                kind,
+                TransferPriority::Nominal,
+                TransferId::new()
+            ),
+            Err(LoopbackError::Transmission(_))
+        ));
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn tracking_more_transfers_than_the_capacity_is_an_error() {
+        let kind = SessionKind::Message {
+            source_node_id: crate::session_id::NodeId::new(),
+            subject_id: crate::session_id::SubjectId::new(),
+        };
+        let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+        let mut tracker = LoopbackTracker::<1>::default();
+
+        send_with_loopback(
+            &mut queue,
+            &mut tracker,
+            &[0; 4],
+            kind,
+            TransferPriority::Nominal,
+            TransferId::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            send_with_loopback(
+                &mut queue,
+                &mut tracker,
+                &[0; 4],
+                kind,
+                TransferPriority::Nominal,
+                TransferId::new()
+            ),
+            Err(LoopbackError::OutOfSpace)
+        );
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn a_cancelled_transfer_frees_its_place() {
+        let kind = SessionKind::Message {
+            source_node_id: crate::session_id::NodeId::new(),
+            subject_id: crate::session_id::SubjectId::new(),
+        };
+        let mut tracker = LoopbackTracker::<1>::default();
+
+        tracker.track(kind, TransferId::new()).unwrap();
+        assert!(tracker.cancel(kind, TransferId::new()));
+        assert!(!tracker.cancel(kind, TransferId::new()));
+        assert!(tracker.track(kind, TransferId::new()).is_ok());
+    }
+
+    #[test]
+    fn a_transfer_is_abandoned_after_its_deadline_unless_it_started() {
+        let kind = SessionKind::Message {
+            source_node_id: crate::session_id::NodeId::new(),
+            subject_id: crate::session_id::SubjectId::new(),
+        };
+        let started = TransferId::try_from(1).unwrap();
+        let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+        let mut tracker = LoopbackTracker::<2>::default();
+
+        tracker
+            .track_until(kind, TransferId::new(), Timestamp::from_micros(100))
+            .unwrap();
+        tracker
+            .track_until(kind, started, Timestamp::from_micros(100))
+            .unwrap();
+        send_with_transfer_id(
+            &mut queue,
+            &[0; 20],
+            kind,
+            TransferPriority::Nominal,
+            started,
+        )
+        .unwrap();
+        let first = queue.pop_ready(Timestamp::default()).unwrap();
+        assert_eq!(tracker.confirm(&first, Timestamp::from_micros(50)), None);
+
+        assert_eq!(tracker.expire(Timestamp::from_micros(100)), 0);
+        assert_eq!(tracker.expire(Timestamp::from_micros(101)), 1);
+        assert_eq!(tracker.len(), 1);
+        assert!(tracker.cancel(kind, started));
+    }
+}
@@ -1,3 +1,10 @@
+#[cfg(feature = "async")]
+pub mod async_transmitter;
 pub mod breakdown;
+pub mod gather_breakdown;
+pub mod loopback;
+pub mod redundant_transmitter;
 pub mod stream_transmitter;
 pub mod transmitter;
+pub mod tx_queue;
+pub mod tx_session;
@@ -0,0 +1,167 @@
+use core::marker::PhantomData;
+
+use super::{stream_transmitter::CanWriter, transmitter::Transmitter};
+use crate::{
+    statistics::{ends_transfer, TxStatistics},
+    CanFrame,
+};
+
+/// A transmitter that sends a copy of every frame on each of `N` redundant
+/// interfaces.
+///
+/// A failure on an interface does not prevent the frame from being written to
+/// the others, and is only reported to the caller when every interface failed
+/// to write the frame, in which case the error of the last interface is
+/// returned. The frames that an interface failed to write are counted as
+/// drops in its [TxStatistics].
+pub struct RedundantTransmitter<
+    Writer: CanWriter<Frame, MTU>,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+    const N: usize,
+> {
+    writers: [Writer; N],
+    statistics: [TxStatistics; N],
+    _marker: PhantomData<Frame>,
+}
+
+impl<Writer: CanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize, const N: usize>
+    RedundantTransmitter<Writer, Frame, MTU, N>
+{
+    pub fn new(writers: [Writer; N]) -> Self {
+        Self {
+            writers,
+            statistics: [TxStatistics::default(); N],
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn statistics(&self) -> &[TxStatistics; N] {
+        &self.statistics
+    }
+
+    pub fn writers(&mut self) -> &mut [Writer; N] {
+        &mut self.writers
+    }
+}
+
+impl<Writer: CanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize, const N: usize>
+    Transmitter<Frame, MTU> for RedundantTransmitter<Writer, Frame, MTU, N>
+{
+    type Error = Writer::Error;
+
+    fn transmit(&mut self, frame: Frame) -> Result<(), Self::Error> {
+        let (payload, len) = frame.payload();
+        let ends_transfer = ends_transfer(&frame);
+        let mut failures = 0;
+        let mut last_error = None;
+
+        for (writer, statistics) in self.writers.iter_mut().zip(self.statistics.iter_mut()) {
+            match writer.write_frame(Frame::from((frame.id(), *payload, len))) {
+                Ok(()) => statistics.record_sent(ends_transfer),
+                Err(error) => {
+                    statistics.drops += 1;
+                    failures += 1;
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        match last_error {
+            Some(error) if failures == N => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::session_id::TransferPriority;
+    use crate::tests::ClassicFrame;
+    use crate::tx::transmitter::send;
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    #[derive(Default)]
+    struct RecordingWriter {
+        frames: Vec<ClassicFrame>,
+        is_broken: bool,
+    }
+
+    impl CanWriter<ClassicFrame, CLASSIC_MTU> for RecordingWriter {
+        type Error = ();
+
+        fn write_frame(&mut self, frame: ClassicFrame) -> Result<(), Self::Error> {
+            if self.is_broken {
+                return Err(());
+            }
+
+            self.frames.push(frame);
+            Ok(())
+        }
+    }
+
+    fn frames_of(writer: &RecordingWriter) -> Vec<(u32, [u8; CLASSIC_MTU], usize)> {
+        writer
+            .frames
+            .iter()
+            .map(|frame| (frame.id(), *frame.payload().0, frame.payload().1))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn every_interface_receives_the_same_frames(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut transmitter = RedundantTransmitter::<_, ClassicFrame, CLASSIC_MTU, 3>::new([
+                RecordingWriter::default(),
+                RecordingWriter::default(),
+                RecordingWriter::default(),
+            ]);
+
+            send(&mut transmitter, &payload, kind, TransferPriority::Nominal).unwrap();
+
+            let [first, second, third] = transmitter.writers();
+            prop_assert!(!first.frames.is_empty());
+            prop_assert_eq!(frames_of(first), frames_of(second));
+            prop_assert_eq!(frames_of(first), frames_of(third));
+        }
+
+        #[test]
+        fn a_failing_interface_does_not_prevent_the_others_from_transmitting(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100)) {
+            let mut transmitter = RedundantTransmitter::<_, ClassicFrame, CLASSIC_MTU, 2>::new([
+                RecordingWriter { frames: Vec::new(), is_broken: true },
+                RecordingWriter::default(),
+            ]);
+
+            prop_assert!(send(&mut transmitter, &payload, kind, TransferPriority::Nominal).is_ok());
+
+            let frames = transmitter.writers()[1].frames.len() as u64;
+            prop_assert_eq!(transmitter.statistics()[0], TxStatistics { drops: frames, ..TxStatistics::default() });
+            prop_assert_eq!(transmitter.statistics()[1], TxStatistics { frames, transfers: 1, ..TxStatistics::default() });
+        }
+    }
+
+    #[test]
+    fn transmitting_is_an_error_when_every_interface_fails() {
+        let mut transmitter = RedundantTransmitter::<_, ClassicFrame, CLASSIC_MTU, 2>::new([
+            RecordingWriter {
+                frames: Vec::new(),
+                is_broken: true,
+            },
+            RecordingWriter {
+                frames: Vec::new(),
+                is_broken: true,
+            },
+        ]);
+
+        assert!(transmitter
+            .transmit(ClassicFrame::from((0, [0; CLASSIC_MTU], 1)))
+            .is_err());
+    }
+}
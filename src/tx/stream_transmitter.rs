@@ -1,7 +1,10 @@
 use core::marker::PhantomData;
 
 use super::transmitter::Transmitter;
-use crate::CanFrame;
+use crate::{
+    statistics::{ends_transfer, TxStatistics},
+    CanFrame,
+};
 
 pub trait CanWriter<Frame: CanFrame<MTU>, const MTU: usize> {
     type Error;
@@ -12,6 +15,7 @@ pub trait CanWriter<Frame: CanFrame<MTU>, const MTU: usize> {
 pub struct StreamTransmitter<Writer: CanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>
 {
     writer: Writer,
+    statistics: TxStatistics,
     _marker: PhantomData<Frame>,
 }
 
@@ -21,9 +25,18 @@ impl<Writer: CanWriter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>
     pub fn new(writer: Writer) -> Self {
         Self {
             writer,
+            statistics: TxStatistics::default(),
             _marker: PhantomData,
         }
     }
+
+    pub fn statistics(&self) -> &TxStatistics {
+        &self.statistics
+    }
+
+    pub fn into_writer(self) -> Writer {
+        self.writer
+    }
 }
 
 impl<Frame: CanFrame<MTU>, Writer: CanWriter<Frame, MTU>, const MTU: usize> Transmitter<Frame, MTU>
@@ -32,8 +45,19 @@ impl<Frame: CanFrame<MTU>, Writer: CanWriter<Frame, MTU>, const MTU: usize> Tran
     type Error = Writer::Error;
 
     fn transmit(&mut self, frame: Frame) -> Result<(), Self::Error> {
-        self.writer.write_frame(frame)?;
+        let ends_transfer = ends_transfer(&frame);
 
-        Ok(())
+        match self.writer.write_frame(frame) {
+            Ok(()) => {
+                self.statistics.record_sent(ends_transfer);
+
+                Ok(())
+            }
+            Err(err) => {
+                self.statistics.drops += 1;
+
+                Err(err)
+            }
+        }
     }
 }
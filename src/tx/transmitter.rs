@@ -1,7 +1,8 @@
-use super::breakdown::Breakdown;
+use super::{breakdown::Breakdown, gather_breakdown::GatherBreakdown};
 use crate::session_id::TransferPriority;
 use crate::{
     session_id::{can_id_for_session_kind, SessionKind},
+    tail_byte::TransferId,
     CanFrame,
 };
 
@@ -13,6 +14,11 @@ pub trait Transmitter<Frame: CanFrame<MTU>, const MTU: usize> {
     fn ensure_available_space(&self, _frames_count: usize) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    /// Called when a transfer of `frames_count` frames is not sent because
+    /// [Self::ensure_available_space] failed, so that transmitters keeping
+    /// statistics can count it as dropped.
+    fn record_dropped_transfer(&mut self, _frames_count: usize) {}
 }
 
 pub fn send<T: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
@@ -20,12 +26,61 @@ pub fn send<T: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
     payload: &[u8],
     kind: SessionKind,
     priority: TransferPriority,
+) -> Result<(), T::Error> {
+    send_with_transfer_id(transmitter, payload, kind, priority, TransferId::new())
+}
+
+/// Sends `payload` as a transfer identified by `transfer_id`.
+///
+/// Consecutive transfers of the same session should use successive transfer
+/// IDs, so that receivers are able to tell them apart.
+pub fn send_with_transfer_id<T: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
+    transmitter: &mut T,
+    payload: &[u8],
+    kind: SessionKind,
+    priority: TransferPriority,
+    transfer_id: TransferId,
+) -> Result<(), T::Error> {
+    let can_id = can_id_for_session_kind(kind, priority);
+    let breakdown = Breakdown::with_transfer_id(payload, can_id, transfer_id);
+
+    transmit_all(transmitter, breakdown.frames_count(), breakdown)
+}
+
+/// Sends the concatenation of `slices` as a transfer identified by
+/// `transfer_id`, without copying it into a contiguous buffer first.
+///
+/// See [GatherBreakdown] for the requirements on `slices`.
+pub fn send_gathered<
+    'a,
+    T: Transmitter<Frame, MTU>,
+    Slices: Iterator<Item = &'a [u8]> + Clone,
+    Frame: CanFrame<MTU>,
+    const MTU: usize,
+>(
+    transmitter: &mut T,
+    slices: Slices,
+    kind: SessionKind,
+    priority: TransferPriority,
+    transfer_id: TransferId,
 ) -> Result<(), T::Error> {
     let can_id = can_id_for_session_kind(kind, priority);
-    let breakdown = Breakdown::new(payload, can_id);
+    let breakdown = GatherBreakdown::with_transfer_id(slices, can_id, transfer_id);
 
-    transmitter.ensure_available_space(breakdown.frames_count())?;
-    for frame in breakdown {
+    transmit_all(transmitter, breakdown.frames_count(), breakdown)
+}
+
+fn transmit_all<T: Transmitter<Frame, MTU>, Frame: CanFrame<MTU>, const MTU: usize>(
+    transmitter: &mut T,
+    frames_count: usize,
+    frames: impl Iterator<Item = Frame>,
+) -> Result<(), T::Error> {
+    if let Err(err) = transmitter.ensure_available_space(frames_count) {
+        transmitter.record_dropped_transfer(frames_count);
+
+        return Err(err);
+    }
+    for frame in frames {
         transmitter.transmit(frame)?;
     }
 
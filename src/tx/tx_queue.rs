@@ -0,0 +1,353 @@
+use core::cmp::Ordering;
+#[cfg(feature = "async")]
+use core::task::Waker;
+
+use heapless::{binary_heap::Min, BinaryHeap};
+
+use super::transmitter::{send_with_transfer_id, Transmitter};
+use crate::{
+    session_id::{SessionKind, TransferPriority},
+    statistics::{ends_transfer, starts_transfer, TxStatistics},
+    tail_byte::TransferId,
+    timestamp::Timestamp,
+    CanFrame,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TxQueueError {
+    OutOfSpace,
+}
+
+/// A frame waiting in a [TxQueue].
+///
+/// Frames are ordered by their CAN ID, as the arbitration of the bus would do,
+/// and then by the order in which they were queued, so that the frames of a
+/// transfer are never reordered.
+#[derive(Debug)]
+pub struct QueuedFrame<Frame: CanFrame<MTU>, const MTU: usize> {
+    frame: Frame,
+    deadline: Option<Timestamp>,
+    sequence: u64,
+    starts_transfer: bool,
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> QueuedFrame<Frame, MTU> {
+    fn key(&self) -> (u32, u64) {
+        (self.frame.id(), self.sequence)
+    }
+
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.deadline.is_some_and(|deadline| now > deadline)
+    }
+
+    /// Whether `next` is the frame that follows this one in its transfer.
+    fn is_followed_by(&self, next: &Self) -> bool {
+        !next.starts_transfer && next.key() == (self.frame.id(), self.sequence + 1)
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> PartialEq for QueuedFrame<Frame, MTU> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> Eq for QueuedFrame<Frame, MTU> {}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> PartialOrd for QueuedFrame<Frame, MTU> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const MTU: usize> Ord for QueuedFrame<Frame, MTU> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// A fixed-capacity transmitter that holds the frames of whole transfers
+/// until the driver is ready to send them.
+///
+/// Transfers are only accepted when all of their frames fit the queue, and
+/// the frames are handed out by [TxQueue::pop_ready] highest priority first.
+/// A transfer queued with [TxQueue::send_with_deadline] is dropped as a
+/// whole, instead of being sent, when its deadline has passed before its
+/// first frame is handed out. Once started, a transfer is always completed,
+/// so that the bus never carries a part of it.
+pub struct TxQueue<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> {
+    frames: BinaryHeap<QueuedFrame<Frame, MTU>, Min, CAPACITY>,
+    /// The deadline given to the frames being queued, only set for the
+    /// duration of [TxQueue::send_with_deadline].
+    deadline: Option<Timestamp>,
+    sequence: u64,
+    statistics: TxStatistics,
+    /// The task waiting for space, woken by [TxQueue::pop_ready].
+    #[cfg(feature = "async")]
+    space_waker: Option<Waker>,
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> Default
+    for TxQueue<Frame, CAPACITY, MTU>
+{
+    fn default() -> Self {
+        Self {
+            frames: BinaryHeap::new(),
+            deadline: None,
+            sequence: 0,
+            statistics: TxStatistics::default(),
+            #[cfg(feature = "async")]
+            space_waker: None,
+        }
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> TxQueue<Frame, CAPACITY, MTU> {
+    /// Queues `payload` as a transfer identified by `transfer_id` whose
+    /// frames are dropped once `deadline` has passed. Transfers queued
+    /// otherwise never expire.
+    pub fn send_with_deadline(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: TransferId,
+        deadline: Timestamp,
+    ) -> Result<(), TxQueueError> {
+        self.deadline = Some(deadline);
+        let result = send_with_transfer_id(self, payload, kind, priority, transfer_id);
+        self.deadline = None;
+
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn available_space(&self) -> usize {
+        self.frames.capacity() - self.frames.len()
+    }
+
+    /// Counts the frames handed out by [TxQueue::pop_ready] as sent.
+    pub fn statistics(&self) -> &TxStatistics {
+        &self.statistics
+    }
+
+    /// Removes and returns the highest priority frame, dropping the
+    /// transfers found along the way whose deadline has passed at `now`.
+    pub fn pop_ready(&mut self, now: Timestamp) -> Option<Frame> {
+        #[cfg(feature = "async")]
+        if !self.frames.is_empty() {
+            if let Some(waker) = self.space_waker.take() {
+                waker.wake();
+            }
+        }
+
+        while let Some(mut queued) = self.frames.pop() {
+            if !queued.starts_transfer || !queued.is_expired(now) {
+                self.statistics.record_sent(ends_transfer(&queued.frame));
+
+                return Some(queued.frame);
+            }
+
+            // The other frames of the transfer come next, as they share its
+            // CAN ID and follow it in sequence.
+            self.statistics.timeouts += 1;
+            while self
+                .frames
+                .peek()
+                .is_some_and(|next| queued.is_followed_by(next))
+            {
+                queued = self.frames.pop().unwrap();
+                self.statistics.timeouts += 1;
+            }
+        }
+
+        None
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> Transmitter<Frame, MTU>
+    for TxQueue<Frame, CAPACITY, MTU>
+{
+    type Error = TxQueueError;
+
+    fn transmit(&mut self, frame: Frame) -> Result<(), Self::Error> {
+        let queued = QueuedFrame {
+            starts_transfer: starts_transfer(&frame),
+            frame,
+            deadline: self.deadline,
+            sequence: self.sequence,
+        };
+
+        if self.frames.push(queued).is_err() {
+            self.statistics.drops += 1;
+
+            return Err(TxQueueError::OutOfSpace);
+        }
+        self.sequence += 1;
+
+        Ok(())
+    }
+
+    fn ensure_available_space(&self, frames_count: usize) -> Result<(), Self::Error> {
+        (self.available_space() >= frames_count)
+            .then_some(())
+            .ok_or(TxQueueError::OutOfSpace)
+    }
+
+    fn record_dropped_transfer(&mut self, frames_count: usize) {
+        self.statistics.drops += frames_count as u64;
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize>
+    super::async_transmitter::WakeOnSpace for TxQueue<Frame, CAPACITY, MTU>
+{
+    fn register_space_waker(&mut self, waker: &Waker) {
+        self.space_waker = Some(waker.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::session_id::{can_id_for_session_kind, TransferPriority};
+    use crate::tests::{frames_of, ClassicFrame};
+    use crate::tx::breakdown::Breakdown;
+    use crate::tx::transmitter::send;
+    use crate::CLASSIC_MTU;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    proptest! {
+        #[test]
+        fn frames_are_popped_in_order_of_can_id_keeping_the_order_of_each_transfer(
+            transfers in vec((session_kind(), 0..8u8, vec(proptest::num::u8::ANY, 0..30)), 1..6)
+        ) {
+            let mut queue = TxQueue::<ClassicFrame, 64, CLASSIC_MTU>::default();
+            let priorities = [
+                TransferPriority::Exceptional,
+                TransferPriority::Immediate,
+                TransferPriority::Fast,
+                TransferPriority::High,
+                TransferPriority::Nominal,
+                TransferPriority::Low,
+                TransferPriority::Slow,
+                TransferPriority::Optional,
+            ];
+
+            for (kind, priority, payload) in &transfers {
+                send(&mut queue, payload, *kind, priorities[*priority as usize]).unwrap();
+            }
+
+            let mut popped = Vec::new();
+            while let Some(frame) = queue.pop_ready(Timestamp::default()) {
+                popped.push(frame);
+            }
+
+            prop_assert!(popped.windows(2).all(|pair| pair[0].id() <= pair[1].id()));
+
+            for (kind, priority, payload) in &transfers {
+                let can_id = can_id_for_session_kind(*kind, priorities[*priority as usize]);
+                let expected: Vec<_> = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(payload, can_id)
+                    .map(|frame| *frame.payload().0)
+                    .collect();
+
+                let frames_with_id: Vec<_> = popped
+                    .iter()
+                    .filter(|frame| frame.id() == can_id)
+                    .map(|frame| *frame.payload().0)
+                    .collect();
+
+                prop_assert!(frames_with_id.windows(expected.len()).any(|window| window == expected.as_slice()));
+            }
+        }
+
+        #[test]
+        fn a_transfer_that_does_not_fit_the_queue_is_rejected_as_a_whole(
+            kind in session_kind(),
+            payload in vec(proptest::num::u8::ANY, 0..200)
+        ) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+            let frames_count = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, 0).frames_count();
+
+            let result = send(&mut queue, &payload, kind, TransferPriority::Nominal);
+
+            if frames_count <= 16 {
+                prop_assert_eq!(result, Ok(()));
+                prop_assert_eq!(queue.len(), frames_count);
+            } else {
+                prop_assert_eq!(result, Err(TxQueueError::OutOfSpace));
+                prop_assert!(queue.is_empty());
+            }
+        }
+
+        #[test]
+        fn frames_whose_deadline_has_passed_are_dropped(kind in session_kind(), deadline in 0..u64::MAX - 1) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+
+            queue.send_with_deadline(&[1, 2, 3], kind, TransferPriority::Nominal, TransferId::new(), Timestamp::from_micros(deadline)).unwrap();
+
+            prop_assert!(queue.pop_ready(Timestamp::from_micros(deadline + 1)).is_none());
+            prop_assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn frames_are_popped_until_their_deadline(kind in session_kind(), deadline in 0..u64::MAX) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+
+            queue.send_with_deadline(&[1, 2, 3], kind, TransferPriority::Nominal, TransferId::new(), Timestamp::from_micros(deadline)).unwrap();
+
+            prop_assert!(queue.pop_ready(Timestamp::from_micros(deadline)).is_some());
+        }
+
+        #[test]
+        fn a_transfer_that_expires_is_dropped_as_a_whole(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 8..60), deadline in 0..u64::MAX - 1) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+            let frames_count = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, 0).frames_count();
+
+            queue.send_with_deadline(&payload, kind, TransferPriority::Nominal, TransferId::new(), Timestamp::from_micros(deadline)).unwrap();
+            send(&mut queue, &[4, 5, 6], kind, TransferPriority::Nominal).unwrap();
+
+            let popped = queue.pop_ready(Timestamp::from_micros(deadline + 1)).unwrap();
+            prop_assert_eq!(*popped.payload().0, *frames_of::<ClassicFrame, CLASSIC_MTU>(&[4, 5, 6], kind)[0].payload().0);
+            prop_assert!(queue.is_empty());
+            prop_assert_eq!(queue.statistics().timeouts, frames_count as u64);
+        }
+
+        #[test]
+        fn a_started_transfer_is_completed_after_its_deadline(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 8..60), deadline in 0..u64::MAX - 1) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+            let frames_count = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(&payload, 0).frames_count();
+
+            queue.send_with_deadline(&payload, kind, TransferPriority::Nominal, TransferId::new(), Timestamp::from_micros(deadline)).unwrap();
+            prop_assert!(queue.pop_ready(Timestamp::from_micros(deadline)).is_some());
+
+            let rest = core::iter::from_fn(|| queue.pop_ready(Timestamp::from_micros(deadline + 1))).count();
+            prop_assert_eq!(rest, frames_count - 1);
+            prop_assert_eq!(queue.statistics().timeouts, 0);
+        }
+
+        #[test]
+        fn a_deadline_only_applies_to_the_transfer_it_was_given_with(kind in session_kind(), deadline in 0..u64::MAX - 1) {
+            let mut queue = TxQueue::<ClassicFrame, 16, CLASSIC_MTU>::default();
+
+            queue.send_with_deadline(&[1, 2, 3], kind, TransferPriority::Nominal, TransferId::new(), Timestamp::from_micros(deadline)).unwrap();
+            send(&mut queue, &[4, 5, 6], kind, TransferPriority::Nominal).unwrap();
+
+            prop_assert!(queue.pop_ready(Timestamp::from_micros(deadline + 1)).is_some());
+            prop_assert!(queue.is_empty());
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use core::marker::PhantomData;
+
+use crc_any::CRCu16;
+use heapless::Vec;
+
+use super::breakdown::{append_crc, breakdown_kind_for_payload, build_frame, BreakdownKind};
+use crate::{
+    session_id::{can_id_for_session_kind, SessionKind, TransferPriority},
+    tail_byte::{TailByte, TransferId},
+    CanFrame,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TxSessionError {
+    /// A transfer is already in progress.
+    Busy,
+    /// The payload, along with its CRC, does not fit the session.
+    OutOfSpace,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TxSessionState {
+    Idle,
+    InProgress,
+    Completed,
+    Aborted,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Progress {
+    can_id: u32,
+    tail_byte: TailByte,
+    /// The offset of the data of the next frame.
+    offset: usize,
+    is_multi_frame: bool,
+}
+
+/// A transfer that is transmitted one frame at a time, for drivers that
+/// refill their mailboxes from an interrupt.
+///
+/// Unlike a [Breakdown], the session owns a copy of the payload, so it can be
+/// kept in a static between interrupts. The frames are the same that a
+/// [Breakdown] would produce, which is why `CAPACITY` has to leave room for
+/// the two bytes of the CRC of a multi frame transfer.
+///
+/// The driver calls [TxSession::peek] when a mailbox is free, and
+/// [TxSession::advance] once the frame was accepted, so that a frame which
+/// could not be written is offered again. A driver with several mailboxes can
+/// keep a window of frames in flight with [TxSession::peek_nth], advancing
+/// past them with [TxSession::advance_by] as they are transmitted.
+///
+/// [Breakdown]: super::breakdown::Breakdown
+#[derive(Debug)]
+pub struct TxSession<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> {
+    data: Vec<u8, CAPACITY>,
+    progress: Option<Progress>,
+    state: TxSessionState,
+    _frame_marker: PhantomData<Frame>,
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize> Default
+    for TxSession<Frame, CAPACITY, MTU>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Frame: CanFrame<MTU>, const CAPACITY: usize, const MTU: usize>
+    TxSession<Frame, CAPACITY, MTU>
+{
+    pub const fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            progress: None,
+            state: TxSessionState::Idle,
+            _frame_marker: PhantomData,
+        }
+    }
+
+    /// Starts transmitting `payload`, unless another transfer is still in
+    /// progress.
+    pub fn start(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: TransferId,
+    ) -> Result<(), TxSessionError> {
+        if self.state == TxSessionState::InProgress {
+            return Err(TxSessionError::Busy);
+        }
+
+        let mut data = Vec::from_slice(payload).map_err(|_| TxSessionError::OutOfSpace)?;
+        let (tail_byte, is_multi_frame) = match breakdown_kind_for_payload::<MTU>(payload) {
+            BreakdownKind::SingleFrame => (TailByte::single_frame(transfer_id), false),
+            BreakdownKind::MultiFrame(_) => {
+                let mut crc = CRCu16::crc16ccitt_false();
+                crc.digest(payload);
+
+                // When the crc ends the last frame of data, it follows the
+                // zeros that pad the frame to a valid CAN FD length.
+                let remainder = payload.len() % (MTU - 1);
+                let mut last = [0u8; MTU];
+                let crc_bytes = if remainder + 3 <= MTU {
+                    last[..remainder].copy_from_slice(&payload[payload.len() - remainder..]);
+                    let len = append_crc(&mut last, remainder, &mut crc);
+                    &last[remainder..len]
+                } else {
+                    last[..2].copy_from_slice(&crc.get_crc().to_be_bytes());
+                    &last[..2]
+                };
+                data.extend_from_slice(crc_bytes)
+                    .map_err(|_| TxSessionError::OutOfSpace)?;
+
+                (TailByte::start_of_multi_frame(transfer_id), true)
+            }
+        };
+
+        self.data = data;
+        self.progress = Some(Progress {
+            can_id: can_id_for_session_kind(kind, priority),
+            tail_byte,
+            offset: 0,
+            is_multi_frame,
+        });
+        self.state = TxSessionState::InProgress;
+
+        Ok(())
+    }
+
+    pub fn state(&self) -> TxSessionState {
+        self.state
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.state == TxSessionState::Completed
+    }
+
+    /// The number of frames that are still to be transmitted.
+    pub fn frames_count(&self) -> usize {
+        match self.progress {
+            Some(progress) if progress.is_multi_frame => {
+                (self.data.len() - progress.offset).div_ceil(MTU - 1)
+            }
+            Some(_) => 1,
+            None => 0,
+        }
+    }
+
+    /// The next frame to transmit, which is offered again until
+    /// [TxSession::advance] is called.
+    pub fn peek(&self) -> Option<Frame> {
+        self.peek_nth(0)
+    }
+
+    /// The frame that follows the next `n` frames to transmit, if the
+    /// transfer has that many frames left.
+    pub fn peek_nth(&self, n: usize) -> Option<Frame> {
+        let progress = self.progress?;
+        if n >= self.frames_count() {
+            return None;
+        }
+
+        let start = progress.offset + n * (MTU - 1);
+        let end = self.data.len().min(start + MTU - 1);
+
+        let mut tail_byte = progress.tail_byte;
+        for _ in 0..n {
+            tail_byte.advance();
+        }
+        if progress.is_multi_frame && end == self.data.len() {
+            tail_byte = tail_byte.end_of_multi_transfer();
+        }
+
+        Some(build_frame(
+            progress.can_id,
+            &self.data[start..end],
+            tail_byte,
+        ))
+    }
+
+    /// Marks the frame returned by [TxSession::peek] as transmitted,
+    /// completing the transfer after its last frame.
+    pub fn advance(&mut self) {
+        self.advance_by(1);
+    }
+
+    /// Marks the next `n` frames as transmitted, completing the transfer
+    /// once its last frame is passed.
+    pub fn advance_by(&mut self, n: usize) {
+        let remaining = self.frames_count();
+
+        if let Some(progress) = self.progress.as_mut() {
+            if n >= remaining {
+                self.progress = None;
+                self.state = TxSessionState::Completed;
+            } else {
+                progress.offset += n * (MTU - 1);
+                for _ in 0..n {
+                    progress.tail_byte.advance();
+                }
+            }
+        }
+    }
+
+    /// Returns the next frame to transmit, marking it as transmitted.
+    pub fn next_frame(&mut self) -> Option<Frame> {
+        let frame = self.peek()?;
+        self.advance();
+
+        Some(frame)
+    }
+
+    /// Stops transmitting the transfer in progress, if any, so that the
+    /// session can start another one.
+    pub fn abort(&mut self) {
+        if self.progress.take().is_some() {
+            self.state = TxSessionState::Aborted;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use crate::tests::{message, ClassicFrame, FdFrame};
+    use crate::tx::breakdown::Breakdown;
+    use crate::{CLASSIC_MTU, EXTENDED_MTU};
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec as StdVec};
+
+    fn frame_contents<Frame: CanFrame<MTU>, const MTU: usize>(frame: Frame) -> (u32, StdVec<u8>) {
+        let (data, len) = frame.payload();
+
+        (frame.id(), data[..len].to_vec())
+    }
+
+    proptest! {
+        #[test]
+        fn a_session_transmits_the_same_frames_as_a_breakdown(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..100), id in 0..32u8) {
+            let transfer_id = TransferId::try_from(id).unwrap();
+            let mut session = TxSession::<ClassicFrame, 128, CLASSIC_MTU>::new();
+            session.start(&payload, kind, TransferPriority::Nominal, transfer_id).unwrap();
+            let frames_count = session.frames_count();
+
+            let frames: StdVec<_> = core::iter::from_fn(|| session.next_frame()).map(frame_contents).collect();
+            let can_id = can_id_for_session_kind(kind, TransferPriority::Nominal);
+            let expected: StdVec<_> = Breakdown::<ClassicFrame, CLASSIC_MTU>::with_transfer_id(&payload, can_id, transfer_id)
+                .map(frame_contents)
+                .collect();
+
+            prop_assert_eq!(frames.len(), frames_count);
+            prop_assert_eq!(frames, expected);
+            prop_assert!(session.is_complete());
+        }
+    }
+
+    #[test]
+    fn a_frame_is_offered_until_it_is_marked_as_transmitted() {
+        let mut session = TxSession::<ClassicFrame, 64, CLASSIC_MTU>::new();
+        session
+            .start(
+                &[1; 20],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new(),
+            )
+            .unwrap();
+
+        let first = frame_contents(session.peek().unwrap());
+        assert_eq!(frame_contents(session.peek().unwrap()), first);
+
+        session.advance();
+        assert_ne!(frame_contents(session.peek().unwrap()), first);
+    }
+
+    #[test]
+    fn three_mailboxes_are_filled_with_the_next_frames_of_the_transfer() {
+        let payload = [1; 40];
+        let mut session = TxSession::<ClassicFrame, 64, CLASSIC_MTU>::new();
+        session
+            .start(
+                &payload,
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new(),
+            )
+            .unwrap();
+        let expected: StdVec<_> = Breakdown::<ClassicFrame, CLASSIC_MTU>::new(
+            &payload,
+            can_id_for_session_kind(message(), TransferPriority::Nominal),
+        )
+        .map(frame_contents)
+        .collect();
+
+        let mut transmitted = StdVec::new();
+        while !session.is_complete() {
+            let mailboxes: StdVec<_> = (0..3)
+                .filter_map(|n| session.peek_nth(n))
+                .map(frame_contents)
+                .collect();
+            assert!(mailboxes.len() == 3 || mailboxes.len() == session.frames_count());
+
+            // The first two mailboxes are emptied before the next interrupt.
+            let sent = mailboxes.len().min(2);
+            transmitted.extend(mailboxes.into_iter().take(sent));
+            session.advance_by(sent);
+        }
+
+        assert_eq!(transmitted, expected);
+        assert!(session.peek_nth(0).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn a_session_pads_can_fd_frames_like_a_breakdown(payload in vec(proptest::num::u8::ANY, 0..300)) {
+            let mut session = TxSession::<FdFrame, 320, EXTENDED_MTU>::new();
+            session.start(&payload, message(), TransferPriority::Nominal, TransferId::new()).unwrap();
+
+            let frames: StdVec<_> = core::iter::from_fn(|| session.next_frame()).map(frame_contents).collect();
+            let can_id = can_id_for_session_kind(message(), TransferPriority::Nominal);
+            let expected: StdVec<_> = Breakdown::<FdFrame, EXTENDED_MTU>::new(&payload, can_id)
+                .map(frame_contents)
+                .collect();
+
+            prop_assert_eq!(frames, expected);
+        }
+    }
+
+    #[test]
+    fn a_transfer_cannot_be_started_while_another_is_in_progress() {
+        let mut session = TxSession::<ClassicFrame, 64, CLASSIC_MTU>::new();
+        session
+            .start(
+                &[1; 20],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            session.start(
+                &[2],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new()
+            ),
+            Err(TxSessionError::Busy)
+        );
+    }
+
+    #[test]
+    fn an_aborted_session_produces_no_more_frames_and_accepts_a_new_transfer() {
+        let mut session = TxSession::<ClassicFrame, 64, CLASSIC_MTU>::new();
+        session
+            .start(
+                &[1; 20],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new(),
+            )
+            .unwrap();
+        session.next_frame();
+
+        session.abort();
+
+        assert_eq!(session.state(), TxSessionState::Aborted);
+        assert!(session.peek().is_none());
+        assert!(session
+            .start(
+                &[2],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new()
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn a_payload_that_leaves_no_room_for_the_crc_does_not_fit() {
+        let mut session = TxSession::<ClassicFrame, 20, CLASSIC_MTU>::new();
+
+        assert_eq!(
+            session.start(
+                &[1; 19],
+                message(),
+                TransferPriority::Nominal,
+                TransferId::new()
+            ),
+            Err(TxSessionError::OutOfSpace)
+        );
+    }
+}
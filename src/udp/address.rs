@@ -0,0 +1,83 @@
+use core::net::{Ipv4Addr, SocketAddrV4};
+
+use super::{node_id::NodeId, session_kind::SessionKind};
+use crate::session_id::SubjectId;
+
+/// The UDP port of every Cyphal/UDP transfer.
+pub const PORT: u16 = 9382;
+
+const MESSAGE_GROUP_BASE: u32 = 0xEF00_0000;
+const SERVICE_GROUP_BASE: u32 = 0xEF01_0000;
+
+/// The multicast group of the messages published on `subject_id`.
+pub fn message_group(subject_id: SubjectId) -> Ipv4Addr {
+    Ipv4Addr::from(MESSAGE_GROUP_BASE | u32::from(u16::from_le_bytes(subject_id.into_bytes())))
+}
+
+/// The multicast group of the service transfers addressed to `node_id`.
+pub fn service_group(node_id: NodeId) -> Ipv4Addr {
+    Ipv4Addr::from(SERVICE_GROUP_BASE | u32::from(u16::from(node_id)))
+}
+
+/// The address to which a transfer of `kind` is sent.
+pub fn endpoint(kind: SessionKind) -> SocketAddrV4 {
+    let group = match kind {
+        SessionKind::Message { subject_id, .. } => message_group(subject_id),
+        SessionKind::Request {
+            destination_node_id,
+            ..
+        }
+        | SessionKind::Response {
+            destination_node_id,
+            ..
+        } => service_group(destination_node_id),
+    };
+
+    SocketAddrV4::new(group, PORT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::{self, session_kind::Request, ServiceId};
+    use core::convert::TryFrom;
+
+    #[test]
+    fn messages_are_sent_to_the_group_of_their_subject() {
+        let kind = SessionKind::Message {
+            source_node_id: Some(NodeId::try_from(1).unwrap()),
+            subject_id: SubjectId::from_const(7509),
+        };
+
+        assert_eq!(
+            endpoint(kind),
+            SocketAddrV4::new(Ipv4Addr::new(239, 0, 0x1D, 0x55), PORT)
+        );
+    }
+
+    #[test]
+    fn responses_are_sent_to_the_group_of_the_node_that_made_the_request() {
+        let request = Request::new(
+            session_id::NodeId::try_from(42).unwrap(),
+            session_id::NodeId::try_from(7).unwrap(),
+            ServiceId::from_const(430),
+        );
+
+        assert_eq!(
+            *endpoint(session_id::SessionKind::Request(request).into()).ip(),
+            Ipv4Addr::new(239, 1, 0, 7)
+        );
+        assert_eq!(
+            *endpoint(session_id::SessionKind::Response(request).into()).ip(),
+            Ipv4Addr::new(239, 1, 0, 42)
+        );
+    }
+
+    #[test]
+    fn the_group_of_a_node_is_made_of_the_16_bits_of_its_node_id() {
+        assert_eq!(
+            service_group(NodeId::try_from(0x1234).unwrap()),
+            Ipv4Addr::new(239, 1, 0x12, 0x34)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use super::{
+    header::{Header, HEADER_SIZE},
+    session_kind::SessionKind,
+};
+use crate::session_id::TransferPriority;
+use crc_any::CRCu32;
+
+/// Splits a transfer into Cyphal/UDP datagrams.
+///
+/// The transfer CRC is appended to the payload, so that it may be split
+/// between the last two datagrams.
+pub struct Breakdown<'a> {
+    header: Header,
+    payload: &'a [u8],
+    crc: [u8; 4],
+    offset: usize,
+    is_done: bool,
+}
+
+impl<'a> Breakdown<'a> {
+    pub fn new(
+        payload: &'a [u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Self {
+        let mut crc = CRCu32::crc32c();
+        crc.digest(payload);
+
+        Self {
+            header: Header::for_session_kind(kind, priority, transfer_id),
+            payload,
+            crc: crc.get_crc().to_le_bytes(),
+            offset: 0,
+            is_done: false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.payload.len() + self.crc.len()
+    }
+
+    /// The number of datagrams that carry the transfer when each of them
+    /// carries at most `mtu` bytes of payload.
+    pub fn frames_count(&self, mtu: usize) -> usize {
+        self.len().div_ceil(mtu)
+    }
+
+    /// Writes the next datagram into `buffer`, returning its length, or
+    /// `None` once the whole transfer has been written.
+    ///
+    /// Each datagram carries as much of the payload as fits in `buffer` after
+    /// the header. Panics if `buffer` cannot hold more than a header.
+    pub fn write_next(&mut self, buffer: &mut [u8]) -> Option<usize> {
+        assert!(
+            buffer.len() > HEADER_SIZE,
+            "buffer too small for a datagram"
+        );
+
+        if self.is_done {
+            return None;
+        }
+
+        let (header, data) = buffer.split_at_mut(HEADER_SIZE);
+        let chunk_len = data.len().min(self.len() - self.offset);
+        let end = self.offset + chunk_len;
+
+        for (byte, index) in data.iter_mut().zip(self.offset..end) {
+            *byte = match self.payload.get(index) {
+                Some(byte) => *byte,
+                None => self.crc[index - self.payload.len()],
+            };
+        }
+
+        self.is_done = end == self.len();
+        self.header.end_of_transfer = self.is_done;
+        let mut header_bytes = [0; HEADER_SIZE];
+        self.header.write(&mut header_bytes);
+        header.copy_from_slice(&header_bytes);
+
+        self.offset = end;
+        self.header.frame_index += 1;
+
+        Some(HEADER_SIZE + chunk_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::udp::session_kind::strategy::session_kind;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn the_number_of_written_datagrams_is_the_frames_count(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..200), mtu in 1usize..64) {
+            let mut breakdown = Breakdown::new(&payload, kind, TransferPriority::Nominal, 0);
+            let expected = breakdown.frames_count(mtu);
+
+            let mut buffer = [0; HEADER_SIZE + 64];
+            let mut count = 0;
+            while let Some(len) = breakdown.write_next(&mut buffer[..HEADER_SIZE + mtu]) {
+                let (header, _) = Header::parse(&buffer[..len]).unwrap();
+                prop_assert_eq!(header.frame_index as usize, count);
+                count += 1;
+                prop_assert_eq!(header.end_of_transfer, count == expected);
+            }
+
+            prop_assert_eq!(count, expected);
+        }
+    }
+
+    #[test]
+    fn the_transfer_crc_follows_the_payload() {
+        let kind = SessionKind::Message {
+            source_node_id: None,
+            subject_id: crate::session_id::SubjectId::new(),
+        };
+        let mut breakdown = Breakdown::new(b"123456789", kind, TransferPriority::Nominal, 0);
+
+        let mut buffer = [0; HEADER_SIZE + 64];
+        let len = breakdown.write_next(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[HEADER_SIZE..len], b"123456789\x83\x92\x06\xE3");
+        assert_eq!(breakdown.write_next(&mut buffer), None);
+    }
+}
@@ -0,0 +1,172 @@
+use super::{error::Error, header::Header, payload::PayloadWithCrc, transfer::Transfer};
+
+/// Reassembles the Cyphal/UDP datagrams of a session into transfers.
+///
+/// The datagrams of a transfer are expected in order, and datagrams of other
+/// sessions interrupt it, so a receiver of several sessions keeps a buildup
+/// per session, as [UdpSocket](super::UdpSocket) does. `CAPACITY` is the
+/// capacity of the payload of the transfers.
+#[derive(Debug)]
+pub struct Buildup<const CAPACITY: usize> {
+    payload: PayloadWithCrc<CAPACITY>,
+    last_header: Option<Header>,
+}
+
+impl<const CAPACITY: usize> Default for Buildup<CAPACITY> {
+    fn default() -> Self {
+        Self {
+            payload: PayloadWithCrc::default(),
+            last_header: None,
+        }
+    }
+}
+
+impl<const CAPACITY: usize> Buildup<CAPACITY> {
+    /// Accepts the next datagram, returning the transfer that it completes, if
+    /// any.
+    ///
+    /// A datagram that starts a transfer discards any transfer that was
+    /// being built up. On error, the transfer that was being built up is
+    /// discarded.
+    pub fn push(&mut self, datagram: &[u8]) -> Result<Option<Transfer<CAPACITY>>, Error> {
+        let (header, data) = Header::parse(datagram)?;
+
+        self.push_parsed(header, data)
+    }
+
+    /// Accepts the next datagram, already split into its `header` and the
+    /// `data` that follows it, as [Buildup::push] does.
+    pub fn push_parsed(
+        &mut self,
+        header: Header,
+        data: &[u8],
+    ) -> Result<Option<Transfer<CAPACITY>>, Error> {
+        let result = self.advance(header, data);
+        if !matches!(result, Ok(None)) {
+            self.reset();
+        }
+
+        result
+    }
+
+    fn reset(&mut self) {
+        self.payload = PayloadWithCrc::default();
+        self.last_header = None;
+    }
+
+    fn advance(
+        &mut self,
+        header: Header,
+        data: &[u8],
+    ) -> Result<Option<Transfer<CAPACITY>>, Error> {
+        let kind = header.session_kind()?;
+
+        if header.frame_index == 0 {
+            self.reset();
+        } else {
+            self.ensure_follows(&header)?;
+        }
+
+        self.payload.extend_from_slice(data)?;
+
+        if !header.end_of_transfer {
+            self.last_header = Some(header);
+            return Ok(None);
+        }
+
+        Ok(Some(Transfer {
+            payload: core::mem::take(&mut self.payload).into_payload()?,
+            kind,
+            transfer_id: header.transfer_id,
+        }))
+    }
+
+    fn ensure_follows(&self, header: &Header) -> Result<(), Error> {
+        self.last_header
+            .filter(|last| {
+                last.source_node_id == header.source_node_id
+                    && last.destination_node_id == header.destination_node_id
+                    && last.data_specifier == header.data_specifier
+                    && last.transfer_id == header.transfer_id
+                    && last.frame_index + 1 == header.frame_index
+            })
+            .map(|_| ())
+            .ok_or(Error::UnexpectedFrame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::TransferPriority;
+    use crate::udp::{
+        breakdown::Breakdown,
+        header::HEADER_SIZE,
+        session_kind::{strategy::session_kind, SessionKind},
+    };
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::{format, vec::Vec as StdVec};
+
+    /// The transfer ID of the transfers of the tests, which does not fit the
+    /// transfer ID of the CAN transport.
+    const TRANSFER_ID: u64 = 0x0123_4567_89AB_CDEF;
+
+    fn datagrams(payload: &[u8], kind: SessionKind, mtu: usize) -> StdVec<StdVec<u8>> {
+        let mut breakdown = Breakdown::new(payload, kind, TransferPriority::Nominal, TRANSFER_ID);
+        let mut buffer = [0; HEADER_SIZE + 64];
+        let mut datagrams = StdVec::new();
+        while let Some(len) = breakdown.write_next(&mut buffer[..HEADER_SIZE + mtu]) {
+            datagrams.push(buffer[..len].to_vec());
+        }
+
+        datagrams
+    }
+
+    proptest! {
+        #[test]
+        fn the_datagrams_of_a_transfer_are_built_up_into_the_same_transfer(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..200), mtu in 1usize..64) {
+            let datagrams = datagrams(&payload, kind, mtu);
+            let mut buildup = Buildup::<256>::default();
+
+            for datagram in &datagrams[..datagrams.len() - 1] {
+                prop_assert!(buildup.push(datagram).unwrap().is_none());
+            }
+            let transfer = buildup.push(datagrams.last().unwrap()).unwrap().unwrap();
+
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(transfer.transfer_id, TRANSFER_ID);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+        }
+
+        #[test]
+        fn a_payload_is_built_up_up_to_the_capacity_of_the_buildup(kind in session_kind(), mtu in 1usize..64) {
+            let datagrams = datagrams(&[7; 16], kind, mtu);
+            let mut buildup = Buildup::<16>::default();
+
+            let transfer = datagrams.iter().find_map(|datagram| buildup.push(datagram).unwrap()).unwrap();
+
+            prop_assert_eq!(&transfer.payload[..], &[7; 16][..]);
+        }
+
+        #[test]
+        fn a_missing_datagram_is_an_error(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 20..200)) {
+            let datagrams = datagrams(&payload, kind, 8);
+            let mut buildup = Buildup::<256>::default();
+
+            buildup.push(&datagrams[0]).unwrap();
+            prop_assert_eq!(buildup.push(&datagrams[2]).err(), Some(Error::UnexpectedFrame));
+        }
+
+        #[test]
+        fn a_corrupted_payload_is_an_error(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 1..50), flip in 1u8..) {
+            let mut datagram = datagrams(&payload, kind, 64).remove(0);
+            datagram[HEADER_SIZE] ^= flip;
+            let mut buildup = Buildup::<256>::default();
+
+            prop_assert!(matches!(buildup.push(&datagram), Err(Error::WrongCRC(_, _))));
+        }
+    }
+}
@@ -0,0 +1,21 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The datagram is shorter than a header, or the transfer is shorter than
+    /// its CRC.
+    TooShort,
+    UnsupportedVersion(u8),
+    /// The header CRC that was computed and the one that was received.
+    WrongHeaderCRC(u16, u16),
+    /// A field of the header holds a value that has no meaning.
+    InvalidHeader,
+    /// A node ID does not fit the range of [NodeId](super::NodeId), or, when
+    /// converted to the CAN transport, the range of
+    /// [NodeId](crate::session_id::NodeId), which excludes anonymous nodes.
+    NodeIdOutOfRange(u16),
+    /// The frame does not follow the previous frame of the transfer that is
+    /// being built up.
+    UnexpectedFrame,
+    OutOfSpace,
+    /// The transfer CRC that was computed and the one that was received.
+    WrongCRC(u32, u32),
+}
@@ -0,0 +1,276 @@
+use core::convert::TryFrom;
+
+use super::{
+    error::Error,
+    node_id::{NodeId, UNSET_NODE_ID},
+    session_kind::SessionKind,
+};
+use crate::session_id::{ServiceId, SubjectId, TransferPriority};
+use crc_any::CRCu16;
+
+pub const HEADER_SIZE: usize = 24;
+pub const HEADER_VERSION: u8 = 1;
+
+const SERVICE_NOT_MESSAGE: u16 = 1 << 15;
+const REQUEST_NOT_RESPONSE: u16 = 1 << 14;
+const END_OF_TRANSFER: u32 = 1 << 31;
+
+/// The header that precedes the payload of each Cyphal/UDP datagram.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub priority: u8,
+    pub source_node_id: u16,
+    pub destination_node_id: u16,
+    pub data_specifier: u16,
+    pub transfer_id: u64,
+    pub frame_index: u32,
+    pub end_of_transfer: bool,
+}
+
+/// The node ID of a service transfer, which cannot be unset.
+fn service_node_id(value: u16) -> Result<NodeId, Error> {
+    NodeId::try_from(value).map_err(|_| Error::InvalidHeader)
+}
+
+impl Header {
+    /// The header of the first frame of a transfer of `kind`.
+    pub fn for_session_kind(
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Self {
+        let (source_node_id, destination_node_id, data_specifier) = match kind {
+            SessionKind::Message {
+                source_node_id,
+                subject_id,
+            } => (
+                source_node_id.map_or(UNSET_NODE_ID, u16::from),
+                UNSET_NODE_ID,
+                u16::from_le_bytes(subject_id.into_bytes()),
+            ),
+            SessionKind::Request {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            } => (
+                u16::from(source_node_id),
+                u16::from(destination_node_id),
+                SERVICE_NOT_MESSAGE
+                    | REQUEST_NOT_RESPONSE
+                    | u16::from_le_bytes(service_id.into_bytes()),
+            ),
+            SessionKind::Response {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            } => (
+                u16::from(source_node_id),
+                u16::from(destination_node_id),
+                SERVICE_NOT_MESSAGE | u16::from_le_bytes(service_id.into_bytes()),
+            ),
+        };
+
+        Self {
+            priority: priority as u8,
+            source_node_id,
+            destination_node_id,
+            data_specifier,
+            transfer_id,
+            frame_index: 0,
+            end_of_transfer: false,
+        }
+    }
+
+    /// The kind of the session of the transfer.
+    ///
+    /// Messages are published by anonymous nodes when their source node ID
+    /// is unset, while service transfers always have a source and a
+    /// destination.
+    pub fn session_kind(&self) -> Result<SessionKind, Error> {
+        if self.data_specifier & SERVICE_NOT_MESSAGE == 0 {
+            let subject_id =
+                SubjectId::try_from(self.data_specifier).map_err(|_| Error::InvalidHeader)?;
+
+            return (self.destination_node_id == UNSET_NODE_ID)
+                .then_some(SessionKind::Message {
+                    source_node_id: NodeId::try_from(self.source_node_id).ok(),
+                    subject_id,
+                })
+                .ok_or(Error::InvalidHeader);
+        }
+
+        let source_node_id = service_node_id(self.source_node_id)?;
+        let destination_node_id = service_node_id(self.destination_node_id)?;
+        let service_id = ServiceId::try_from(
+            self.data_specifier & !(SERVICE_NOT_MESSAGE | REQUEST_NOT_RESPONSE),
+        )
+        .map_err(|_| Error::InvalidHeader)?;
+        if self.data_specifier & REQUEST_NOT_RESPONSE != 0 {
+            Ok(SessionKind::Request {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            })
+        } else {
+            Ok(SessionKind::Response {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            })
+        }
+    }
+
+    pub fn write(&self, buffer: &mut [u8; HEADER_SIZE]) {
+        let frame_index = self.frame_index
+            | if self.end_of_transfer {
+                END_OF_TRANSFER
+            } else {
+                0
+            };
+
+        buffer[0] = HEADER_VERSION;
+        buffer[1] = self.priority;
+        buffer[2..4].copy_from_slice(&self.source_node_id.to_le_bytes());
+        buffer[4..6].copy_from_slice(&self.destination_node_id.to_le_bytes());
+        buffer[6..8].copy_from_slice(&self.data_specifier.to_le_bytes());
+        buffer[8..16].copy_from_slice(&self.transfer_id.to_le_bytes());
+        buffer[16..20].copy_from_slice(&frame_index.to_le_bytes());
+        // The user data field, which is reserved.
+        buffer[20..22].copy_from_slice(&[0, 0]);
+
+        let mut crc = CRCu16::crc16ccitt_false();
+        crc.digest(&buffer[..HEADER_SIZE - 2]);
+        buffer[22..24].copy_from_slice(&crc.get_crc().to_be_bytes());
+    }
+
+    /// Splits `datagram` into its header and the payload that follows it.
+    pub fn parse(datagram: &[u8]) -> Result<(Self, &[u8]), Error> {
+        (datagram.len() >= HEADER_SIZE)
+            .then_some(())
+            .ok_or(Error::TooShort)?;
+        let (header, payload) = datagram.split_at(HEADER_SIZE);
+
+        (header[0] == HEADER_VERSION)
+            .then_some(())
+            .ok_or(Error::UnsupportedVersion(header[0]))?;
+
+        let mut crc = CRCu16::crc16ccitt_false();
+        crc.digest(&header[..HEADER_SIZE - 2]);
+        let received_crc = u16::from_be_bytes([header[22], header[23]]);
+        (crc.get_crc() == received_crc)
+            .then_some(())
+            .ok_or_else(|| Error::WrongHeaderCRC(crc.get_crc(), received_crc))?;
+
+        (header[1] < 8).then_some(()).ok_or(Error::InvalidHeader)?;
+
+        let u16_at = |index: usize| u16::from_le_bytes([header[index], header[index + 1]]);
+        let mut transfer_id = [0; 8];
+        transfer_id.copy_from_slice(&header[8..16]);
+        let frame_index = u32::from_le_bytes([header[16], header[17], header[18], header[19]]);
+
+        Ok((
+            Self {
+                priority: header[1],
+                source_node_id: u16_at(2),
+                destination_node_id: u16_at(4),
+                data_specifier: u16_at(6),
+                transfer_id: u64::from_le_bytes(transfer_id),
+                frame_index: frame_index & !END_OF_TRANSFER,
+                end_of_transfer: frame_index & END_OF_TRANSFER != 0,
+            },
+            payload,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::transfer_priority::strategy::transfer_priority;
+    use crate::udp::session_kind::strategy::session_kind;
+    use core::convert::TryInto;
+    use proptest::prelude::*;
+
+    extern crate std;
+    use std::format;
+
+    proptest! {
+        #[test]
+        fn a_written_header_is_parsed_back_to_the_same_session_kind(kind in session_kind(), priority in transfer_priority(), transfer_id in proptest::num::u64::ANY, frame_index in 0u32..(1 << 31), end_of_transfer in proptest::bool::ANY) {
+            let header = Header {
+                frame_index,
+                end_of_transfer,
+                ..Header::for_session_kind(kind, priority, transfer_id)
+            };
+            let mut datagram = [0; HEADER_SIZE + 1];
+            header.write((&mut datagram[..HEADER_SIZE]).try_into().unwrap());
+
+            let (parsed, payload) = Header::parse(&datagram).unwrap();
+            prop_assert_eq!(parsed, header);
+            prop_assert_eq!(parsed.session_kind().unwrap(), kind);
+            prop_assert_eq!(payload.len(), 1);
+        }
+
+        #[test]
+        fn a_header_with_a_corrupted_byte_is_rejected(kind in session_kind(), index in 1..HEADER_SIZE, flip in 1u8..) {
+            let mut datagram = [0; HEADER_SIZE];
+            Header::for_session_kind(kind, TransferPriority::Nominal, 0).write(&mut datagram);
+            datagram[index] ^= flip;
+
+            prop_assert!(matches!(Header::parse(&datagram), Err(Error::WrongHeaderCRC(_, _))));
+        }
+    }
+
+    #[test]
+    fn a_header_matches_the_layout_of_the_specification() {
+        let kind = SessionKind::Message {
+            source_node_id: Some(NodeId::try_from(0x1234).unwrap()),
+            subject_id: SubjectId::from_const(7509),
+        };
+        let mut datagram = [0; HEADER_SIZE];
+        Header {
+            frame_index: 3,
+            end_of_transfer: true,
+            ..Header::for_session_kind(kind, TransferPriority::Nominal, 0x0102)
+        }
+        .write(&mut datagram);
+
+        assert_eq!(
+            datagram[..22],
+            [
+                1, 4, 0x34, 0x12, 0xFF, 0xFF, 0x55, 0x1D, 2, 1, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0x80, 0,
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn a_message_with_an_unset_source_node_id_is_anonymous() {
+        let kind = SessionKind::Message {
+            source_node_id: None,
+            subject_id: SubjectId::new(),
+        };
+        let header = Header::for_session_kind(kind, TransferPriority::Nominal, 0);
+
+        assert_eq!(header.source_node_id, UNSET_NODE_ID);
+        assert_eq!(header.session_kind(), Ok(kind));
+    }
+
+    #[test]
+    fn a_request_with_an_unset_source_node_id_is_invalid() {
+        let header = Header {
+            source_node_id: UNSET_NODE_ID,
+            ..Header::for_session_kind(
+                SessionKind::Request {
+                    source_node_id: NodeId::try_from(1).unwrap(),
+                    destination_node_id: NodeId::try_from(2).unwrap(),
+                    service_id: ServiceId::from_const(430),
+                },
+                TransferPriority::Nominal,
+                0,
+            )
+        };
+
+        assert_eq!(header.session_kind(), Err(Error::InvalidHeader));
+    }
+}
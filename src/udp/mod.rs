@@ -0,0 +1,33 @@
+//! The Cyphal/UDP transport, exchanging the same [Transfer]s as the CAN
+//! transport over UDP/IPv4 multicast.
+//!
+//! Each transfer is sent as one or more datagrams, each starting with a
+//! [Header], to the multicast group derived from its [SessionKind] by
+//! [endpoint]. A ready-made socket is provided by the `std` feature.
+//!
+//! Node IDs are 16 bits wide and transfer IDs 64 bits wide, so the transport
+//! has its own [NodeId], [SessionKind] and [Transfer], which convert from and
+//! to those of the CAN transport.
+
+pub mod address;
+pub mod breakdown;
+pub mod buildup;
+pub mod error;
+pub mod header;
+pub mod node_id;
+pub(crate) mod payload;
+pub mod session_kind;
+#[cfg(feature = "std")]
+pub mod socket;
+pub mod transfer;
+
+pub use address::{endpoint, PORT};
+pub use breakdown::Breakdown;
+pub use buildup::Buildup;
+pub use error::Error;
+pub use header::Header;
+pub use node_id::NodeId;
+pub use session_kind::SessionKind;
+#[cfg(feature = "std")]
+pub use socket::UdpSocket;
+pub use transfer::Transfer;
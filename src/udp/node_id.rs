@@ -0,0 +1,89 @@
+use core::convert::TryFrom;
+
+use super::error::Error;
+use crate::session_id;
+
+/// The node ID of anonymous sources and of broadcast destinations.
+pub(super) const UNSET_NODE_ID: u16 = 0xFFFF;
+
+/// The ID of a node on Cyphal/UDP, which is 16 bits wide.
+///
+/// Every value but 0xFFFF, which marks anonymous sources and broadcast
+/// destinations on the wire, is a valid node ID.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u16);
+
+impl NodeId {
+    /// The highest node ID.
+    pub const MAX: u16 = UNSET_NODE_ID - 1;
+}
+
+impl TryFrom<u16> for NodeId {
+    type Error = Error;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        (value <= Self::MAX)
+            .then_some(Self(value))
+            .ok_or(Error::NodeIdOutOfRange(value))
+    }
+}
+
+impl From<NodeId> for u16 {
+    fn from(node_id: NodeId) -> Self {
+        node_id.0
+    }
+}
+
+/// The node IDs of the CAN transport are valid on Cyphal/UDP.
+impl From<session_id::NodeId> for NodeId {
+    fn from(node_id: session_id::NodeId) -> Self {
+        Self(u16::from(u8::from_le_bytes(node_id.into_bytes())))
+    }
+}
+
+impl TryFrom<NodeId> for session_id::NodeId {
+    type Error = Error;
+
+    fn try_from(node_id: NodeId) -> Result<Self, Self::Error> {
+        u8::try_from(node_id.0)
+            .ok()
+            .and_then(|value| session_id::NodeId::try_from(value).ok())
+            .ok_or(Error::NodeIdOutOfRange(node_id.0))
+    }
+}
+
+#[cfg(test)]
+pub mod strategy {
+    use super::*;
+    use proptest::prop_compose;
+
+    prop_compose! {
+        pub fn node_id()(id in 0..=NodeId::MAX) -> NodeId {
+            NodeId::try_from(id).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_16_bits_value_but_the_unset_one_is_a_node_id() {
+        assert_eq!(NodeId::try_from(NodeId::MAX).map(u16::from), Ok(65534));
+        assert_eq!(
+            NodeId::try_from(UNSET_NODE_ID),
+            Err(Error::NodeIdOutOfRange(UNSET_NODE_ID))
+        );
+    }
+
+    #[test]
+    fn a_node_id_beyond_the_range_of_the_can_transport_cannot_be_converted_to_it() {
+        let node_id = NodeId::try_from(128).unwrap();
+
+        assert_eq!(
+            session_id::NodeId::try_from(node_id),
+            Err(Error::NodeIdOutOfRange(128))
+        );
+    }
+}
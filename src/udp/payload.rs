@@ -0,0 +1,98 @@
+use super::error::Error;
+use crc_any::CRCu32;
+use heapless::{Deque, Vec};
+
+/// The size of the CRC that follows the payload of a transfer.
+const CRC_SIZE: usize = 4;
+
+/// The payload of a transfer followed by its CRC, received a piece at a
+/// time.
+///
+/// The last bytes received are held back until more bytes follow them, as
+/// they may be the CRC, so that the payload can take the whole `CAPACITY`.
+#[derive(Debug)]
+pub(crate) struct PayloadWithCrc<const CAPACITY: usize> {
+    payload: Vec<u8, CAPACITY>,
+    crc: Deque<u8, CRC_SIZE>,
+}
+
+impl<const CAPACITY: usize> Default for PayloadWithCrc<CAPACITY> {
+    fn default() -> Self {
+        Self {
+            payload: Vec::new(),
+            crc: Deque::new(),
+        }
+    }
+}
+
+impl<const CAPACITY: usize> PayloadWithCrc<CAPACITY> {
+    pub(crate) fn push(&mut self, byte: u8) -> Result<(), Error> {
+        if let Some(payload_byte) = self.crc.is_full().then(|| self.crc.pop_front()).flatten() {
+            self.payload
+                .push(payload_byte)
+                .map_err(|_| Error::OutOfSpace)?;
+        }
+        // There is room for the byte, which was made above when needed.
+        let _ = self.crc.push_back(byte);
+
+        Ok(())
+    }
+
+    pub(crate) fn extend_from_slice(&mut self, data: &[u8]) -> Result<(), Error> {
+        data.iter().try_for_each(|byte| self.push(*byte))
+    }
+
+    /// Returns the payload, once checked against the CRC that follows it.
+    pub(crate) fn into_payload(self) -> Result<Vec<u8, CAPACITY>, Error> {
+        if !self.crc.is_full() {
+            return Err(Error::TooShort);
+        }
+
+        let mut crc_bytes = [0; CRC_SIZE];
+        for (byte, crc_byte) in crc_bytes.iter_mut().zip(self.crc.iter()) {
+            *byte = *crc_byte;
+        }
+        let crc = u32::from_le_bytes(crc_bytes);
+
+        let mut own_crc = CRCu32::crc32c();
+        own_crc.digest(&self.payload);
+
+        (own_crc.get_crc() == crc)
+            .then_some(self.payload)
+            .ok_or_else(|| Error::WrongCRC(own_crc.get_crc(), crc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_payload_can_take_the_whole_capacity() {
+        let mut payload = PayloadWithCrc::<9>::default();
+        payload.extend_from_slice(b"123456789").unwrap();
+        payload
+            .extend_from_slice(&[0x83, 0x92, 0x06, 0xE3])
+            .unwrap();
+
+        assert_eq!(&payload.into_payload().unwrap()[..], b"123456789");
+    }
+
+    #[test]
+    fn a_payload_beyond_the_capacity_is_out_of_space() {
+        let mut payload = PayloadWithCrc::<8>::default();
+
+        assert_eq!(
+            payload.extend_from_slice(b"123456789\x83\x92\x06\xE3"),
+            Err(Error::OutOfSpace)
+        );
+    }
+
+    #[test]
+    fn a_payload_shorter_than_a_crc_is_too_short() {
+        let mut payload = PayloadWithCrc::<8>::default();
+        payload.extend_from_slice(&[1, 2, 3]).unwrap();
+
+        assert_eq!(payload.into_payload(), Err(Error::TooShort));
+    }
+}
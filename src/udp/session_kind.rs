@@ -0,0 +1,153 @@
+use core::convert::TryFrom;
+
+use super::{error::Error, node_id::NodeId};
+use crate::session_id::{self, session_kind::Request, ServiceId, SubjectId};
+
+/// The kind of the session of a Cyphal/UDP transfer.
+///
+/// This is the counterpart of [session_id::SessionKind] with the 16 bits node
+/// IDs of Cyphal/UDP, where messages may also be published by anonymous
+/// nodes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SessionKind {
+    Message {
+        /// `None` for the messages of anonymous nodes.
+        source_node_id: Option<NodeId>,
+        subject_id: SubjectId,
+    },
+    Request {
+        source_node_id: NodeId,
+        destination_node_id: NodeId,
+        service_id: ServiceId,
+    },
+    /// A response is sent by the node that received the request, to the node
+    /// that made it.
+    Response {
+        source_node_id: NodeId,
+        destination_node_id: NodeId,
+        service_id: ServiceId,
+    },
+}
+
+impl SessionKind {
+    /// The node that sent the transfer, or `None` for an anonymous node.
+    pub fn source_node_id(&self) -> Option<NodeId> {
+        match *self {
+            SessionKind::Message { source_node_id, .. } => source_node_id,
+            SessionKind::Request { source_node_id, .. }
+            | SessionKind::Response { source_node_id, .. } => Some(source_node_id),
+        }
+    }
+}
+
+impl From<session_id::SessionKind> for SessionKind {
+    fn from(kind: session_id::SessionKind) -> Self {
+        match kind {
+            session_id::SessionKind::Message {
+                source_node_id,
+                subject_id,
+            } => SessionKind::Message {
+                source_node_id: Some(source_node_id.into()),
+                subject_id,
+            },
+            session_id::SessionKind::Request(request) => SessionKind::Request {
+                source_node_id: request.source_node_id().into(),
+                destination_node_id: request.destination_node_id().into(),
+                service_id: request.service_id(),
+            },
+            // A response carries the request it answers.
+            session_id::SessionKind::Response(request) => SessionKind::Response {
+                source_node_id: request.destination_node_id().into(),
+                destination_node_id: request.source_node_id().into(),
+                service_id: request.service_id(),
+            },
+        }
+    }
+}
+
+/// Only the sessions of identified nodes whose node IDs fit the range of the
+/// CAN transport can be converted.
+impl TryFrom<SessionKind> for session_id::SessionKind {
+    type Error = Error;
+
+    fn try_from(kind: SessionKind) -> Result<Self, Self::Error> {
+        let node_id = |node_id: NodeId| session_id::NodeId::try_from(node_id);
+
+        Ok(match kind {
+            SessionKind::Message {
+                source_node_id,
+                subject_id,
+            } => session_id::SessionKind::Message {
+                source_node_id: source_node_id
+                    .ok_or(Error::NodeIdOutOfRange(super::node_id::UNSET_NODE_ID))
+                    .and_then(node_id)?,
+                subject_id,
+            },
+            SessionKind::Request {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            } => session_id::SessionKind::Request(Request::new(
+                node_id(source_node_id)?,
+                node_id(destination_node_id)?,
+                service_id,
+            )),
+            SessionKind::Response {
+                source_node_id,
+                destination_node_id,
+                service_id,
+            } => session_id::SessionKind::Response(Request::new(
+                node_id(destination_node_id)?,
+                node_id(source_node_id)?,
+                service_id,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod strategy {
+    use super::super::node_id::strategy::node_id;
+    use super::*;
+    use crate::session_id::{service_id::strategy::service_id, subject_id::strategy::subject_id};
+    use proptest::{option, prop_oneof, strategy::Strategy};
+
+    pub fn session_kind() -> impl Strategy<Value = SessionKind> {
+        prop_oneof![
+            (option::of(node_id()), subject_id()).prop_map(|(source_node_id, subject_id)| {
+                SessionKind::Message {
+                    source_node_id,
+                    subject_id,
+                }
+            }),
+            (node_id(), node_id(), service_id()).prop_map(
+                |(source_node_id, destination_node_id, service_id)| SessionKind::Request {
+                    source_node_id,
+                    destination_node_id,
+                    service_id,
+                }
+            ),
+            (node_id(), node_id(), service_id()).prop_map(
+                |(source_node_id, destination_node_id, service_id)| SessionKind::Response {
+                    source_node_id,
+                    destination_node_id,
+                    service_id,
+                }
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_id::session_kind::strategy::session_kind;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn a_session_kind_of_the_can_transport_is_converted_back_unchanged(kind in session_kind()) {
+            prop_assert_eq!(session_id::SessionKind::try_from(SessionKind::from(kind)), Ok(kind));
+        }
+    }
+}
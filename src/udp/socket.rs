@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{self, Ipv4Addr, ToSocketAddrs},
+    vec,
+    vec::Vec,
+};
+
+use super::{
+    address::{endpoint, message_group, service_group},
+    breakdown::Breakdown,
+    buildup::Buildup,
+    error::Error,
+    header::{Header, HEADER_SIZE},
+    node_id::NodeId,
+    session_kind::SessionKind,
+    transfer::Transfer,
+};
+use crate::{
+    session_id::{SubjectId, TransferPriority},
+    transport::Transport,
+};
+
+/// The largest payload of a UDP datagram over IPv4.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+#[derive(Debug)]
+pub enum ReceiveError {
+    Io(io::Error),
+    Udp(Error),
+}
+
+/// The source node, destination node and data specifier of the datagrams of
+/// a session, as found in their header.
+type SessionKey = (u16, u16, u16);
+
+/// A UDP socket exchanging Cyphal/UDP transfers.
+///
+/// Transfers are sent in datagrams carrying at most `MTU` bytes of payload
+/// after the header. Datagrams of any size are received, and the datagrams
+/// of each source node and session are reassembled separately, so that the
+/// multi-datagram transfers of different senders can be interleaved.
+#[derive(Debug)]
+pub struct UdpSocket<const CAPACITY: usize, const MTU: usize> {
+    socket: net::UdpSocket,
+    /// The transfers being built up, which are removed once they are
+    /// complete or have failed.
+    buildups: HashMap<SessionKey, Buildup<CAPACITY>>,
+    buffer: Vec<u8>,
+}
+
+impl<const CAPACITY: usize, const MTU: usize> UdpSocket<CAPACITY, MTU> {
+    /// Binds the socket to `address`.
+    ///
+    /// A node that receives multicast transfers binds to
+    /// [PORT](super::address::PORT) of the unspecified address, and then
+    /// joins the groups of the transfers it expects.
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        Ok(Self {
+            socket: net::UdpSocket::bind(address)?,
+            buildups: HashMap::new(),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        })
+    }
+
+    pub fn socket(&self) -> &net::UdpSocket {
+        &self.socket
+    }
+
+    /// Joins the group of the messages published on `subject_id`, on the
+    /// network interface whose address is `interface`.
+    pub fn subscribe(&self, subject_id: SubjectId, interface: Ipv4Addr) -> io::Result<()> {
+        self.socket
+            .join_multicast_v4(&message_group(subject_id), &interface)
+    }
+
+    /// Joins the group of the service transfers addressed to `node_id`, on
+    /// the network interface whose address is `interface`.
+    pub fn listen(&self, node_id: NodeId, interface: Ipv4Addr) -> io::Result<()> {
+        self.socket
+            .join_multicast_v4(&service_group(node_id), &interface)
+    }
+
+    /// Sends a transfer to the multicast group of `kind`.
+    pub fn send(
+        &self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> io::Result<()> {
+        self.send_to(payload, kind, priority, transfer_id, endpoint(kind))
+    }
+
+    /// Sends a transfer to `address` instead of the multicast group of
+    /// `kind`.
+    pub fn send_to<A: ToSocketAddrs + Copy>(
+        &self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+        address: A,
+    ) -> io::Result<()> {
+        let mut breakdown = Breakdown::new(payload, kind, priority, transfer_id);
+        let mut buffer = vec![0; HEADER_SIZE + MTU];
+
+        while let Some(len) = breakdown.write_next(&mut buffer) {
+            self.socket.send_to(&buffer[..len], address)?;
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the next datagram, returning the transfer that it completes,
+    /// if any.
+    pub fn receive(&mut self) -> Result<Option<Transfer<CAPACITY>>, ReceiveError> {
+        let len = self
+            .socket
+            .recv(&mut self.buffer)
+            .map_err(ReceiveError::Io)?;
+        let (header, data) = Header::parse(&self.buffer[..len]).map_err(ReceiveError::Udp)?;
+
+        let key = (
+            header.source_node_id,
+            header.destination_node_id,
+            header.data_specifier,
+        );
+        let result = self
+            .buildups
+            .entry(key)
+            .or_default()
+            .push_parsed(header, data);
+        if !matches!(result, Ok(None)) {
+            self.buildups.remove(&key);
+        }
+
+        result.map_err(ReceiveError::Udp)
+    }
+}
+
+/// Receiving from the transport only avoids waiting for a datagram when the
+/// socket is set non-blocking.
+impl<const CAPACITY: usize, const MTU: usize> Transport<CAPACITY> for UdpSocket<CAPACITY, MTU> {
+    type SendError = io::Error;
+    type ReceiveError = ReceiveError;
+
+    fn mtu(&self) -> usize {
+        MTU
+    }
+
+    fn max_node_id(&self) -> u16 {
+        NodeId::MAX
+    }
+
+    fn send(
+        &mut self,
+        payload: &[u8],
+        kind: SessionKind,
+        priority: TransferPriority,
+        transfer_id: u64,
+    ) -> Result<(), Self::SendError> {
+        UdpSocket::send(self, payload, kind, priority, transfer_id)
+    }
+
+    fn receive(&mut self) -> Result<Option<Transfer<CAPACITY>>, Self::ReceiveError> {
+        match UdpSocket::receive(self) {
+            Err(ReceiveError::Io(error)) if error.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::udp::session_kind::strategy::session_kind;
+    use core::convert::TryFrom;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::{format, time::Duration};
+
+    fn loopback<const MTU: usize>() -> UdpSocket<512, MTU> {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .socket()
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+
+        socket
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn a_transfer_is_received_over_loopback(kind in session_kind(), payload in vec(proptest::num::u8::ANY, 0..300), transfer_id in proptest::num::u64::ANY) {
+            let sender = loopback::<32>();
+            let mut receiver = loopback::<32>();
+            let address = receiver.socket().local_addr().unwrap();
+
+            sender.send_to(&payload, kind, TransferPriority::Nominal, transfer_id, address).unwrap();
+
+            let transfer = loop {
+                if let Some(transfer) = receiver.receive().unwrap() {
+                    break transfer;
+                }
+            };
+            prop_assert_eq!(transfer.kind, kind);
+            prop_assert_eq!(transfer.transfer_id, transfer_id);
+            prop_assert_eq!(&transfer.payload[..], &payload[..]);
+        }
+    }
+
+    #[test]
+    fn the_interleaved_datagrams_of_two_senders_are_built_up_separately() {
+        let sender = loopback::<8>();
+        let mut receiver = loopback::<8>();
+        let address = receiver.socket().local_addr().unwrap();
+        let kind = |source_node_id| SessionKind::Message {
+            source_node_id: Some(NodeId::try_from(source_node_id).unwrap()),
+            subject_id: SubjectId::from_const(7509),
+        };
+
+        let mut first = Breakdown::new(&[1; 20], kind(1000), TransferPriority::Nominal, 5);
+        let mut second = Breakdown::new(&[2; 20], kind(2000), TransferPriority::Nominal, 9);
+        let mut buffer = [0; HEADER_SIZE + 8];
+        // Each transfer takes three datagrams, with its CRC.
+        for _ in 0..3 {
+            for breakdown in [&mut first, &mut second] {
+                let len = breakdown.write_next(&mut buffer).unwrap();
+                sender.socket().send_to(&buffer[..len], address).unwrap();
+            }
+        }
+
+        let transfers: Vec<Transfer<512>> =
+            (0..6).filter_map(|_| receiver.receive().unwrap()).collect();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].kind, kind(1000));
+        assert_eq!(
+            (transfers[0].transfer_id, &transfers[0].payload[..]),
+            (5, &[1; 20][..])
+        );
+        assert_eq!(transfers[1].kind, kind(2000));
+        assert_eq!(
+            (transfers[1].transfer_id, &transfers[1].payload[..]),
+            (9, &[2; 20][..])
+        );
+    }
+
+    #[test]
+    fn node_ids_are_16_bits_wide() {
+        assert_eq!(loopback::<32>().max_node_id(), 65534);
+    }
+}
@@ -0,0 +1,30 @@
+use core::convert::TryFrom;
+
+use super::{error::Error, session_kind::SessionKind};
+use crate::{rx, session_id, tail_byte::TransferId};
+use heapless::Vec;
+
+/// A transfer received over Cyphal/UDP, whose transfer ID is 64 bits wide.
+///
+/// This is also the transfer received from any [Transport](crate::transport::Transport).
+#[derive(Debug)]
+pub struct Transfer<const CAPACITY: usize> {
+    pub payload: Vec<u8, CAPACITY>,
+    pub kind: SessionKind,
+    pub transfer_id: u64,
+}
+
+/// Only the transfers whose session can be converted to the CAN transport can
+/// be converted, and their transfer ID is reduced modulo 32.
+impl<const CAPACITY: usize> TryFrom<Transfer<CAPACITY>> for rx::transfer::Transfer<CAPACITY> {
+    type Error = Error;
+
+    fn try_from(transfer: Transfer<CAPACITY>) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            transfer.payload,
+            session_id::SessionKind::try_from(transfer.kind)?,
+            TransferId::try_from((transfer.transfer_id % 32) as u8).unwrap(),
+            None,
+        ))
+    }
+}